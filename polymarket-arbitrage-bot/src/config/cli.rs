@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "Usage:
+  arb-rust [--config <file>] [--headless | --dashboard] [--coin <COIN>] [--log-format json|text]
+  arb-rust replay <path> [--threshold X] [--token-amount N] [--latency-ms M]
+  arb-rust report [--db <path>] [--days N] [--coin COIN]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json, // One JSON object per line (FYI: default when headless)
+    Text, // Colored banners
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "json" => Ok(LogFormat::Json),
+            "text" => Ok(LogFormat::Text),
+            _ => Err(anyhow!("Unknown log format: {} (expected json or text)", value)),
+        }
+    }
+}
+
+// What the user asked for on the command line
+#[derive(Debug, Clone, Default)]
+pub struct CliArgs {
+    pub config: Option<PathBuf>, // --config <file>
+    pub headless: bool, // --headless
+    pub dashboard: bool, // --dashboard
+    pub coin: Option<String>, // --coin <COIN>
+    pub log_format: Option<LogFormat>, // --log-format json|text
+    pub replay: Option<Vec<String>>, // `replay ...` args, parsed by ReplayConfig
+    pub report: Option<Vec<String>>, // `report ...` args, parsed by ReportConfig
+}
+
+impl CliArgs {
+    // Parse std::env::args minus the binary name (BTW: tiny on purpose, no clap needed for 4 flags)
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut cli = Self::default();
+        match args.first().map(|a| a.as_str()) {
+            Some("replay") => {
+                cli.replay = Some(args[1..].to_vec());
+                return Ok(cli);
+            }
+            Some("report") => {
+                cli.report = Some(args[1..].to_vec());
+                return Ok(cli);
+            }
+            _ => {}
+        }
+
+        let mut iter = args.iter();
+        while let Some(flag) = iter.next() {
+            match flag.as_str() {
+                "--headless" => cli.headless = true,
+                "--dashboard" => cli.dashboard = true,
+                "--config" => cli.config = Some(PathBuf::from(Self::value(&mut iter, flag)?)),
+                "--coin" => cli.coin = Some(Self::value(&mut iter, flag)?.to_uppercase()),
+                "--log-format" => cli.log_format = Some(LogFormat::parse(&Self::value(&mut iter, flag)?)?),
+                "--help" | "-h" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                _ => return Err(anyhow!("Unknown argument: {}\n{}", flag, USAGE)),
+            }
+        }
+        Ok(cli)
+    }
+
+    fn value<'a>(iter: &mut impl Iterator<Item = &'a String>, flag: &str) -> Result<String> {
+        iter.next()
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing value for {}", flag))
+    }
+}
+
+// Config file (TOML or YAML by extension)
+// `settings` uses the same keys as .env, e.g. ARBITRAGE_THRESHOLD = 0.99
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FileConfig {
+    pub coin: Option<String>,
+    pub headless: Option<bool>,
+    pub dashboard: Option<bool>,
+    pub log_format: Option<LogFormat>,
+    #[serde(default)]
+    pub settings: HashMap<String, serde_json::Value>,
+}
+
+impl FileConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read config {}: {}", path.display(), e))?;
+        let is_yaml = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml") | Some("yml")
+        );
+        let config = if is_yaml {
+            serde_yaml::from_str(&raw)?
+        } else {
+            toml::from_str(&raw)?
+        };
+        Ok(config)
+    }
+
+    // Export settings as env vars before Env::load (FYI: real env vars win, the file wins over .env)
+    pub fn apply_settings(&self) {
+        for (key, value) in &self.settings {
+            if env::var(key).is_ok() {
+                continue;
+            }
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            env::set_var(key, value);
+        }
+    }
+}
+
+// Resolved run mode (AFAIK: precedence is CLI flag > config file > HEADLESS/COIN env vars)
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    pub headless: bool,
+    pub dashboard: bool, // Full-screen ratatui UI (FYI: ignored when headless)
+    pub coin: Option<String>,
+    pub log_format: LogFormat,
+}
+
+impl RunOptions {
+    pub fn resolve(cli: &CliArgs, file: &FileConfig) -> Self {
+        let headless = cli.headless
+            || file.headless.unwrap_or_else(|| {
+                env::var("HEADLESS")
+                    .map(|v| v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false)
+            });
+        let dashboard = !headless
+            && (cli.dashboard
+                || file.dashboard.unwrap_or_else(|| {
+                    env::var("DASHBOARD")
+                        .map(|v| v.eq_ignore_ascii_case("true"))
+                        .unwrap_or(false)
+                }));
+        let coin = cli
+            .coin
+            .clone()
+            .or_else(|| file.coin.clone())
+            .or_else(|| env::var("COIN").ok())
+            .map(|c| c.to_uppercase());
+        let log_format = cli
+            .log_format
+            .or(file.log_format)
+            .or_else(|| env::var("LOG_FORMAT").ok().and_then(|v| LogFormat::parse(&v).ok()))
+            .unwrap_or(if headless { LogFormat::Json } else { LogFormat::Text });
+
+        Self {
+            headless,
+            dashboard,
+            coin,
+            log_format,
+        }
+    }
+}
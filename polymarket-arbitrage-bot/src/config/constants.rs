@@ -19,6 +19,13 @@ pub const WSS_MARKET_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/
 // Gamma API host for market discovery (BTW: this is Polymarket's market data API)
 pub const GAMMA_API_HOST: &str = "https://gamma-api.polymarket.com";
 
+// Polygon contracts (FYI: Conditional Tokens Framework + bridged USDC.e collateral)
+pub const POLYGON_CHAIN_ID: u64 = 137;
+pub const CTF_CONTRACT_ADDRESS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+pub const USDC_CONTRACT_ADDRESS: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+// CLOB exchange that pulls USDC when a buy fills (BTW: the funder must approve it)
+pub const CTF_EXCHANGE_ADDRESS: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+
 // Trading constants (IMO: these defaults work well for most cases)
 pub const TOKEN_AMOUNT: f64 = 5.0; // Fixed token qty per side (UP/DOWN)
 pub const MIN_ORDER_SIZE_USD: f64 = 1.0; // Min order size in USD (Polymarket requirement)
@@ -13,6 +13,50 @@ pub struct Env {
     pub arbitrage_amount_usdc: f64, // USDC amount per token side
     pub token_amount: f64, // Fixed token qty to buy
     pub arbitrage_threshold: f64, // Threshold for arb detection (usually 1.0)
+    pub min_net_edge_bps: f64, // Min net edge per set after fees, rounding and gas
+    pub fee_rate_bps: f64, // Fallback taker fee rate if the CLOB lookup fails
+    pub gas_price_gwei: f64, // Gas price used to amortize merge/redeem cost
+    pub sell_arbitrage_enabled: bool, // Split-and-sell when bid sum > threshold
+    pub sell_arbitrage_threshold: f64, // Bid sum must exceed this (usually 1.0)
+    pub pol_usd_price: f64, // POL price in USD for gas cost estimates
+    pub trading_budget_usdc: f64, // Total USDC the bot may have deployed at once
+    pub position_settlement: String, // merge | redeem | off
+    pub settle_interval_secs: u64, // Seconds between settlement passes
+    pub record_enabled: bool, // Capture raw WS messages for replay
+    pub record_dir: String, // Directory for compressed recordings
+    pub record_rotate_mb: u64, // Rotate after this many MB (uncompressed)
+    pub record_max_files: usize, // Oldest recordings beyond this are deleted
+    pub event_scan_enabled: bool, // Scan multi-outcome / neg-risk events too
+    pub event_scan_interval_secs: u64, // Seconds between event scans
+    pub event_scan_limit: usize, // Max Gamma events per scan
+    pub event_scan_max_sets: f64, // Cap on basket size when sizing by depth
+    pub ledger_enabled: bool, // Write detections, orders and settlements to SQLite
+    pub ledger_path: String, // SQLite file for the trade ledger
+    pub funds_check_enabled: bool, // Check USDC balance/allowance before every trade
+    pub funds_refresh_secs: u64, // Seconds between balance/allowance reads
+    pub max_spend_per_window_usdc: f64, // Cap per 15-min window (0 = no cap)
+    pub max_spend_per_day_usdc: f64, // Cap per UTC day (0 = no cap)
+    pub expiry_cutoff_secs: i64, // No new sets with this little time left in the window
+    pub expiry_tiers: String, // secs:edge_multiplier:size_factor, comma separated
+    pub alert_webhook_url: Option<String>, // JSON webhook for alerts (Slack/Discord-style `text` field)
+    pub telegram_bot_token: Option<String>, // Telegram bot for alerts (needs TELEGRAM_CHAT_ID too)
+    pub telegram_chat_id: Option<String>, // Chat the bot posts to
+    pub alert_min_detection_sets: f64, // Only alert on detections at least this many sets
+    pub alert_batch_secs: u64, // Collect alerts this long before sending
+    pub alert_max_per_minute: u32, // Max messages per sink per minute
+    pub mm_enabled: bool, // Rest bids on both legs instead of taking asks
+    pub mm_target_edge_bps: f64, // UP bid + DOWN bid stays at or below 1 - this
+    pub mm_requote_ticks: f64, // Min quote move (in ticks) before cancel/replace
+    pub mm_hedge_max_sum: f64, // Max set cost when lifting the other leg after a fill
+    pub mm_poll_ms: u64, // Order status polling interval
+    pub log_dir: String, // Directory for monitor.log and error.log
+    pub log_timezone: String, // IANA timezone for log and banner timestamps
+    pub log_rotate_mb: u64, // Rotate a log file past this size (0 = never)
+    pub log_rotate_daily: bool, // Rotate when the local date changes
+    pub log_max_files: usize, // Rotated files kept per log (0 = keep all)
+    pub log_file_format: String, // text | json
+    pub metrics_enabled: bool, // Serve Prometheus metrics over HTTP
+    pub metrics_addr: String, // Listen address for GET /metrics
 }
 
 impl Env {
@@ -42,6 +86,145 @@ impl Env {
                 .unwrap_or_else(|_| "1.0".to_string())
                 .parse()
                 .unwrap_or(1.0),
+            min_net_edge_bps: env::var("MIN_NET_EDGE_BPS")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50.0),
+            fee_rate_bps: env::var("FEE_RATE_BPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            gas_price_gwei: env::var("GAS_PRICE_GWEI")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50.0),
+            sell_arbitrage_enabled: env::var("SELL_ARBITRAGE_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            sell_arbitrage_threshold: env::var("SELL_ARBITRAGE_THRESHOLD")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            pol_usd_price: env::var("POL_USD_PRICE")
+                .unwrap_or_else(|_| "0.5".to_string())
+                .parse()
+                .unwrap_or(0.5),
+            trading_budget_usdc: env::var("TRADING_BUDGET_USDC")
+                .unwrap_or_else(|_| "100.0".to_string())
+                .parse()
+                .unwrap_or(100.0),
+            position_settlement: env::var("POSITION_SETTLEMENT")
+                .unwrap_or_else(|_| "merge".to_string()),
+            settle_interval_secs: env::var("SETTLE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            record_enabled: env::var("RECORD_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            record_dir: env::var("RECORD_DIR")
+                .unwrap_or_else(|_| "recordings".to_string()),
+            record_rotate_mb: env::var("RECORD_ROTATE_MB")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
+            record_max_files: env::var("RECORD_MAX_FILES")
+                .unwrap_or_else(|_| "48".to_string())
+                .parse()
+                .unwrap_or(48),
+            event_scan_enabled: env::var("EVENT_SCAN_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            event_scan_interval_secs: env::var("EVENT_SCAN_INTERVAL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            event_scan_limit: env::var("EVENT_SCAN_LIMIT")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .unwrap_or(200),
+            event_scan_max_sets: env::var("EVENT_SCAN_MAX_SETS")
+                .unwrap_or_else(|_| "100.0".to_string())
+                .parse()
+                .unwrap_or(100.0),
+            ledger_enabled: env::var("LEDGER_ENABLED")
+                .map(|v| !v.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            ledger_path: env::var("LEDGER_PATH")
+                .unwrap_or_else(|_| "ledger.db".to_string()),
+            funds_check_enabled: env::var("FUNDS_CHECK_ENABLED")
+                .map(|v| !v.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            funds_refresh_secs: env::var("FUNDS_REFRESH_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            max_spend_per_window_usdc: env::var("MAX_SPEND_PER_WINDOW_USDC")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            max_spend_per_day_usdc: env::var("MAX_SPEND_PER_DAY_USDC")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            expiry_cutoff_secs: env::var("EXPIRY_CUTOFF_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            expiry_tiers: env::var("EXPIRY_TIERS")
+                .unwrap_or_else(|_| "180:1.5:0.5,60:3:0.25".to_string()),
+            alert_webhook_url: env::var("ALERT_WEBHOOK_URL").ok().filter(|v| !v.is_empty()),
+            telegram_bot_token: env::var("TELEGRAM_BOT_TOKEN").ok().filter(|v| !v.is_empty()),
+            telegram_chat_id: env::var("TELEGRAM_CHAT_ID").ok().filter(|v| !v.is_empty()),
+            alert_min_detection_sets: env::var("ALERT_MIN_DETECTION_SETS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            alert_batch_secs: env::var("ALERT_BATCH_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            alert_max_per_minute: env::var("ALERT_MAX_PER_MINUTE")
+                .unwrap_or_else(|_| "6".to_string())
+                .parse()
+                .unwrap_or(6),
+            mm_enabled: env::var("MM_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            mm_target_edge_bps: env::var("MM_TARGET_EDGE_BPS")
+                .unwrap_or_else(|_| "150".to_string())
+                .parse()
+                .unwrap_or(150.0),
+            mm_requote_ticks: env::var("MM_REQUOTE_TICKS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2.0),
+            mm_hedge_max_sum: env::var("MM_HEDGE_MAX_SUM")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            mm_poll_ms: env::var("MM_POLL_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            log_dir: env::var("LOG_DIR").unwrap_or_else(|_| ".".to_string()),
+            log_timezone: env::var("LOG_TIMEZONE").unwrap_or_else(|_| "America/New_York".to_string()),
+            log_rotate_mb: env::var("LOG_ROTATE_MB")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            log_rotate_daily: env::var("LOG_ROTATE_DAILY")
+                .map(|v| !v.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            log_max_files: env::var("LOG_MAX_FILES")
+                .unwrap_or_else(|_| "14".to_string())
+                .parse()
+                .unwrap_or(14),
+            log_file_format: env::var("LOG_FILE_FORMAT").unwrap_or_else(|_| "text".to_string()),
+            metrics_enabled: env::var("METRICS_ENABLED")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            metrics_addr: env::var("METRICS_ADDR").unwrap_or_else(|_| "127.0.0.1:9100".to_string()),
         }
     }
 }
@@ -1,6 +1,7 @@
+pub mod cli;
 pub mod constants;
 pub mod env;
 
+pub use cli::{CliArgs, FileConfig, LogFormat, RunOptions};
 pub use constants::*;
 pub use env::Env;
-
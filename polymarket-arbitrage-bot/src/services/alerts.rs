@@ -0,0 +1,310 @@
+use crate::config::Env;
+use crate::services::engine::EngineEvent;
+use crate::services::websocket_client::ConnectionState;
+use crate::utils::logger::log_error;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration, Instant};
+
+// Alerts waiting for the next batch (FYI: beyond this the oldest are dropped and counted)
+const ALERT_QUEUE_CAPACITY: usize = 256;
+// Telegram caps messages at 4096 chars (BTW: webhooks get the same cut for consistency)
+const MAX_MESSAGE_CHARS: usize = 3900;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub level: &'static str, // info | warn | error
+    pub title: String,
+    pub body: String,
+    pub ts: i64, // ms
+}
+
+impl Alert {
+    fn new(level: &'static str, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            level,
+            title: title.into(),
+            body: body.into(),
+            ts: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    fn line(&self) -> String {
+        let icon = match self.level {
+            "error" => "🔴",
+            "warn" => "🟡",
+            _ => "🟢",
+        };
+        format!("{} {}: {}", icon, self.title, self.body)
+    }
+}
+
+// Where alerts go (IMO: anything that can take a batch of lines is a sink)
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn send(&self, alerts: &[Alert]) -> Result<()>;
+}
+
+// Render a batch as one message (FYI: newest last, truncated to the sink limit)
+fn render_batch(alerts: &[Alert]) -> String {
+    let mut text = alerts.iter().map(Alert::line).collect::<Vec<_>>().join("\n");
+    if text.chars().count() > MAX_MESSAGE_CHARS {
+        text = text.chars().take(MAX_MESSAGE_CHARS).collect::<String>() + "\n…";
+    }
+    text
+}
+
+// Generic JSON webhook (AFAIK: `text` is what Slack/Discord-style incoming webhooks read)
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        Self { client: reqwest::Client::new(), url }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, alerts: &[Alert]) -> Result<()> {
+        let body = serde_json::json!({
+            "text": render_batch(alerts),
+            "alerts": alerts,
+        });
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Webhook returned {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+// Telegram Bot API sendMessage
+pub struct TelegramSink {
+    client: reqwest::Client,
+    token: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(token: String, chat_id: String) -> Self {
+        Self { client: reqwest::Client::new(), token, chat_id }
+    }
+}
+
+#[async_trait]
+impl AlertSink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn send(&self, alerts: &[Alert]) -> Result<()> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.token);
+        let response = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({
+                "chat_id": self.chat_id,
+                "text": render_batch(alerts),
+                "disable_web_page_preview": true,
+            }))
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            // Don't log the URL, it carries the bot token
+            return Err(anyhow!("Telegram returned {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+// Which engine events become alerts
+#[derive(Debug, Clone)]
+pub struct AlertRules {
+    pub min_detection_sets: f64, // Detections smaller than this are not alerted
+    pub batch_secs: u64, // Collect alerts this long before sending
+    pub max_per_minute: u32, // Messages per sink per minute
+}
+
+impl AlertRules {
+    pub fn from_env(env: &Env) -> Self {
+        Self {
+            min_detection_sets: env.alert_min_detection_sets,
+            batch_secs: env.alert_batch_secs.max(1),
+            max_per_minute: env.alert_max_per_minute.max(1),
+        }
+    }
+
+    // Map an engine event to an alert (BTW: None = not alert-worthy)
+    pub fn alert_for(&self, event: &EngineEvent) -> Option<Alert> {
+        match event {
+            // Once per market and price pair (FYI: the engine re-emits it on every book tick)
+            EngineEvent::ArbitrageDetected { coin, ask_sum, net_edge_bps, sets, new_prices: true, .. } if *sets >= self.min_detection_sets => {
+                Some(Alert::new(
+                    "info",
+                    format!("{} arbitrage", coin),
+                    format!("ask sum {:.4}, net edge {:.0} bps, {:.2} sets", ask_sum, net_edge_bps, sets),
+                ))
+            }
+            EngineEvent::TradeResult { coin, kind, success, up_usdc, down_usdc, error } => {
+                let one_legged = !success && ((*up_usdc > 0.0) != (*down_usdc > 0.0));
+                let (level, title) = match (kind.as_str(), success, one_legged) {
+                    ("unwind", true, _) => ("warn", format!("{} lone leg unwound", coin)),
+                    ("unwind", false, _) => ("error", format!("{} unwind FAILED", coin)),
+                    (_, true, _) => ("info", format!("{} {} filled", coin, kind)),
+                    (_, false, true) => ("error", format!("{} {} ONE-LEGGED", coin, kind)),
+                    (_, false, false) => return None, // Nothing filled, nothing at risk
+                };
+                let mut body = format!("UP ${:.2} / DOWN ${:.2}", up_usdc, down_usdc);
+                if let Some(error) = error {
+                    body.push_str(&format!(" ({})", error));
+                }
+                Some(Alert::new(level, title, body))
+            }
+            EngineEvent::ConnectionChanged { state } => match state {
+                ConnectionState::Reconnecting { attempt, delay_ms } => Some(Alert::new(
+                    "warn",
+                    "WebSocket disconnected",
+                    format!("reconnect attempt {} in {:.0}s", attempt, *delay_ms as f64 / 1000.0),
+                )),
+                ConnectionState::Connected => Some(Alert::new("info", "WebSocket connected", "feed is live")),
+                ConnectionState::Stopped => Some(Alert::new("error", "WebSocket stopped", "no more book updates")),
+                _ => None,
+            },
+            EngineEvent::MarketFound { coin, slug, .. } => {
+                Some(Alert::new("info", format!("{} rollover", coin), format!("now trading {}", slug)))
+            }
+            EngineEvent::Error { message } => Some(Alert::new("error", "Error", message.clone())),
+            _ => None,
+        }
+    }
+}
+
+// Fan-out with batching and rate limiting (IMO: a burst of book updates must never become a burst of messages)
+pub struct Alerter {
+    rules: AlertRules,
+    tx: mpsc::Sender<Alert>,
+}
+
+impl Alerter {
+    // Sinks from env (FYI: None when nothing is configured, so the engine can skip alerting entirely)
+    pub fn start(env: &Env) -> Option<Arc<Self>> {
+        let mut sinks: Vec<Arc<dyn AlertSink>> = Vec::new();
+        if let Some(ref url) = env.alert_webhook_url {
+            sinks.push(Arc::new(WebhookSink::new(url.clone())));
+        }
+        if let (Some(ref token), Some(ref chat_id)) = (&env.telegram_bot_token, &env.telegram_chat_id) {
+            sinks.push(Arc::new(TelegramSink::new(token.clone(), chat_id.clone())));
+        }
+        if sinks.is_empty() {
+            return None;
+        }
+        Some(Self::with_sinks(AlertRules::from_env(env), sinks))
+    }
+
+    pub fn with_sinks(rules: AlertRules, sinks: Vec<Arc<dyn AlertSink>>) -> Arc<Self> {
+        let (tx, rx) = mpsc::channel(ALERT_QUEUE_CAPACITY);
+        tokio::spawn(run_batcher(rx, sinks, rules.batch_secs, rules.max_per_minute));
+        Arc::new(Self { rules, tx })
+    }
+
+    // Called from ArbEngine::emit (BTW: never blocks, a full queue just drops the alert)
+    pub fn notify(&self, event: &EngineEvent) {
+        if let Some(alert) = self.rules.alert_for(event) {
+            let _ = self.tx.try_send(alert);
+        }
+    }
+}
+
+// Sliding one-minute window of send times per sink
+struct RateLimit {
+    max_per_minute: u32,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimit {
+    fn allow(&mut self) -> bool {
+        let now = Instant::now();
+        while self.sent.front().is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(60)) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() as u32 >= self.max_per_minute {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+// Alerts waiting for one sink (BTW: each sink keeps its own, so one failing sink can't lose or repeat the other's)
+struct SinkQueue {
+    limit: RateLimit,
+    pending: Vec<Alert>,
+    dropped: usize,
+}
+
+async fn run_batcher(mut rx: mpsc::Receiver<Alert>, sinks: Vec<Arc<dyn AlertSink>>, batch_secs: u64, max_per_minute: u32) {
+    let mut queues: Vec<SinkQueue> = sinks
+        .iter()
+        .map(|_| SinkQueue {
+            limit: RateLimit { max_per_minute, sent: VecDeque::new() },
+            pending: Vec::new(),
+            dropped: 0,
+        })
+        .collect();
+    let mut tick = interval(Duration::from_secs(batch_secs));
+
+    loop {
+        tokio::select! {
+            alert = rx.recv() => match alert {
+                Some(alert) => {
+                    for queue in queues.iter_mut() {
+                        queue.pending.push(alert.clone());
+                        if queue.pending.len() > ALERT_QUEUE_CAPACITY {
+                            queue.pending.remove(0);
+                            queue.dropped += 1;
+                        }
+                    }
+                }
+                None => break,
+            },
+            _ = tick.tick() => {
+                // Held back while a sink is over its limit or failing (FYI: the next tick retries with the grown batch)
+                for (sink, queue) in sinks.iter().zip(queues.iter_mut()) {
+                    if queue.pending.is_empty() || !queue.limit.allow() {
+                        continue;
+                    }
+                    let mut batch = queue.pending.clone();
+                    if queue.dropped > 0 {
+                        batch.push(Alert::new("warn", "Alerts dropped", format!("{} alert(s) dropped by the rate limit", queue.dropped)));
+                    }
+                    match sink.send(&batch).await {
+                        Ok(()) => {
+                            queue.pending.clear();
+                            queue.dropped = 0;
+                        }
+                        Err(e) => log_error(&format!("Alert sink {} failed: {}", sink.name(), e), Some("alerts")),
+                    }
+                }
+            }
+        }
+    }
+}
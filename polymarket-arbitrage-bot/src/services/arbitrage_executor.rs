@@ -1,8 +1,13 @@
-use crate::config::{Env, MIN_ORDER_SIZE_USD, TOKEN_AMOUNT};
+use crate::config::MIN_ORDER_SIZE_USD;
 use crate::services::create_clob_client::{ClobClient, OrderResponse, OrderSide, OrderType};
+use crate::services::ctf::CtfClient;
+use crate::services::funds_guard::FundsGuard;
+use crate::services::metrics::observe_order_roundtrip;
+use crate::services::profitability::taker_fee_usdc;
 use crate::utils::logger::log_error;
 use anyhow::{anyhow, Result};
 use colored::*;
+use std::time::Instant;
 
 // Trading constants (FYI: Polymarket has strict precision requirements)
 const MIN_TOKEN_AMOUNT: f64 = 5.0; // Min tokens per order
@@ -18,6 +23,7 @@ pub struct ArbitrageOrderResult {
     pub amount: f64,
     pub price: f64,
     pub tokens_bought: Option<f64>,
+    pub tokens_sold: Option<f64>,
     pub error: Option<String>,
 }
 
@@ -67,38 +73,25 @@ fn create_error_result(token_id: &str, side: &str, error: String) -> ArbitrageOr
         amount: 0.0,
         price: 0.0,
         tokens_bought: None,
+        tokens_sold: None,
         error: Some(error),
     }
 }
 
-// Execute buy order for arbitrage (FYI: handles precision and validation)
-pub async fn execute_buy_order(
-    clob_client: &ClobClient,
-    token_id: &str,
-    side: &str,
-    amount_usdc: f64,
-    ask_price: f64,
-) -> ArbitrageOrderResult {
-    // Validate inputs (IMO: fail fast on bad data)
-    if token_id.trim().is_empty() {
-        return create_error_result(token_id, side, "Invalid tokenId".to_string());
-    }
-
+// Work out the exact order a buy leg would send (FYI: shared with the profitability model so both see the same rounding)
+// Returns (floored price, share quantity, USDC amount)
+pub(crate) fn plan_buy_leg(amount_usdc: f64, ask_price: f64) -> Result<(f64, f64, f64), String> {
     if amount_usdc < MIN_ORDER_SIZE_USD {
-        return create_error_result(
-            token_id,
-            side,
-            format!("Order size (${:.2}) below minimum (${:.2})", amount_usdc, MIN_ORDER_SIZE_USD),
-        );
+        return Err(format!("Order size (${:.2}) below minimum (${:.2})", amount_usdc, MIN_ORDER_SIZE_USD));
     }
 
     if ask_price <= 0.0 || !ask_price.is_finite() {
-        return create_error_result(token_id, side, format!("Invalid ask price: {}", ask_price));
+        return Err(format!("Invalid ask price: {}", ask_price));
     }
 
     let floored_price = floor_to_decimals(ask_price, PRICE_DECIMALS);
     if floored_price <= 0.0 || !floored_price.is_finite() {
-        return create_error_result(token_id, side, format!("Invalid floored price: {}", floored_price));
+        return Err(format!("Invalid floored price: {}", floored_price));
     }
 
     // Calculate token quantity (AFAIK: ensure we meet minimums)
@@ -123,14 +116,31 @@ pub async fn execute_buy_order(
 
     // Final validation (FYI: precision adjustments might drop below minimum)
     if floored_amount_usdc < MIN_ORDER_SIZE_USD {
-        return create_error_result(
-            token_id,
-            side,
-            format!("After precision adjustment, USDC amount (${:.2}) below minimum (${:.2})", floored_amount_usdc, MIN_ORDER_SIZE_USD),
-        );
+        return Err(format!("After precision adjustment, USDC amount (${:.2}) below minimum (${:.2})", floored_amount_usdc, MIN_ORDER_SIZE_USD));
+    }
+
+    Ok((floored_price, share_quantity, floored_amount_usdc))
+}
+
+// Execute buy order for arbitrage (FYI: handles precision and validation)
+pub async fn execute_buy_order(
+    clob_client: &ClobClient,
+    token_id: &str,
+    side: &str,
+    amount_usdc: f64,
+    ask_price: f64,
+) -> ArbitrageOrderResult {
+    // Validate inputs (IMO: fail fast on bad data)
+    if token_id.trim().is_empty() {
+        return create_error_result(token_id, side, "Invalid tokenId".to_string());
     }
 
-    println!(
+    let (floored_price, share_quantity, floored_amount_usdc) = match plan_buy_leg(amount_usdc, ask_price) {
+        Ok(plan) => plan,
+        Err(e) => return create_error_result(token_id, side, e),
+    };
+
+    crate::console_println!(
         "{}",
         format!(
             "[{}] Executing at ${:.4} (original: ${:.4})\n  Amount: ${:.4} USDC\n  Share quantity: {:.2} tokens\n  TokenID: {}...",
@@ -140,6 +150,7 @@ pub async fn execute_buy_order(
     );
 
     // Create and submit order (IMO: this is where we actually trade)
+    let started = Instant::now();
     match clob_client
         .create_market_order(
             OrderSide::Buy,
@@ -150,11 +161,13 @@ pub async fn execute_buy_order(
         .await
     {
         Ok(signed_order) => {
-            match clob_client.post_order(&signed_order, OrderType::FAK).await {
+            let posted = clob_client.post_order(signed_order, OrderType::FAK).await;
+            observe_order_roundtrip("buy", started.elapsed());
+            match posted {
                 Ok(resp) => {
                     if resp.success {
                         let tokens_bought = floored_amount_usdc / floored_price;
-                        println!(
+                        crate::console_println!(
                             "{}",
                             format!(
                                 "\n✓✓✓ [{}] ORDER COMPLETED ✓✓✓\n  Order ID: {}\n  Amount: ${:.4} USDC\n  Price: ${:.4}\n  Tokens Bought: {:.2} tokens\n",
@@ -174,18 +187,19 @@ pub async fn execute_buy_order(
                             amount: floored_amount_usdc,
                             price: floored_price,
                             tokens_bought: Some(tokens_bought),
+                            tokens_sold: None,
                             error: None,
                         }
                     } else {
                         let error_msg = resp.error.unwrap_or_else(|| "Unknown error".to_string());
-                        println!("{}", format!("✗ [{}] Order failed: {}", side, error_msg).red());
+                        crate::console_println!("{}", format!("✗ [{}] Order failed: {}", side, error_msg).red());
                         log_error(&format!("[{}] Order failed: {}", side, error_msg), Some(&format!("executeBuyOrder-{}", side)));
                         create_error_result(token_id, side, error_msg)
                     }
                 }
                 Err(e) => {
                     let error_msg = format!("Failed to post order: {}", e);
-                    println!("{}", format!("✗ [{}] {}", side, error_msg).red());
+                    crate::console_println!("{}", format!("✗ [{}] {}", side, error_msg).red());
                     log_error(&error_msg, Some(&format!("executeBuyOrder-{}", side)));
                     create_error_result(token_id, side, error_msg)
                 }
@@ -193,15 +207,112 @@ pub async fn execute_buy_order(
         }
         Err(e) => {
             let error_msg = format!("Failed to create order: {}", e);
-            println!("{}", format!("✗ [{}] {}", side, error_msg).red());
+            crate::console_println!("{}", format!("✗ [{}] {}", side, error_msg).red());
             log_error(&error_msg, Some(&format!("executeBuyOrder-{}", side)));
             create_error_result(token_id, side, error_msg)
         }
     }
 }
 
-fn calculate_trade_amounts(up_price: f64, down_price: f64) -> (f64, f64, f64) {
-    let token_amount = floor_to_decimals(TOKEN_AMOUNT, TOKEN_DECIMALS);
+// Execute sell order for split-and-sell (FYI: amount is in shares, not USDC)
+pub async fn execute_sell_order(
+    clob_client: &ClobClient,
+    token_id: &str,
+    side: &str,
+    share_quantity: f64,
+    bid_price: f64,
+) -> ArbitrageOrderResult {
+    if token_id.trim().is_empty() {
+        return create_error_result(token_id, side, "Invalid tokenId".to_string());
+    }
+
+    if bid_price <= 0.0 || !bid_price.is_finite() {
+        return create_error_result(token_id, side, format!("Invalid bid price: {}", bid_price));
+    }
+
+    // Floor both so we never ask for more than we hold or a better price than the book (AFAIK: same precision rules as buys)
+    let floored_price = floor_to_decimals(bid_price, PRICE_DECIMALS);
+    let shares = floor_to_decimals(share_quantity, TOKEN_DECIMALS);
+    let proceeds_usdc = floor_to_decimals(shares * floored_price, PRICE_DECIMALS);
+
+    if shares < MIN_TOKEN_AMOUNT || proceeds_usdc < MIN_ORDER_SIZE_USD {
+        return create_error_result(
+            token_id,
+            side,
+            format!("Sell size ({:.2} tokens, ${:.2}) below minimum", shares, proceeds_usdc),
+        );
+    }
+
+    crate::console_println!(
+        "{}",
+        format!(
+            "[{}] Selling at ${:.4} (original: ${:.4})\n  Share quantity: {:.2} tokens\n  Expected: ${:.4} USDC\n  TokenID: {}...",
+            side, floored_price, bid_price, shares, proceeds_usdc, &token_id[..token_id.len().min(20)]
+        )
+        .cyan()
+    );
+
+    let context = format!("executeSellOrder-{}", side);
+    let started = Instant::now();
+    let signed_order = match clob_client
+        .create_market_order(OrderSide::Sell, token_id, shares, floored_price)
+        .await
+    {
+        Ok(order) => order,
+        Err(e) => {
+            let error_msg = format!("Failed to create order: {}", e);
+            crate::console_println!("{}", format!("✗ [{}] {}", side, error_msg).red());
+            log_error(&error_msg, Some(&context));
+            return create_error_result(token_id, side, error_msg);
+        }
+    };
+
+    let posted = clob_client.post_order(signed_order, OrderType::FAK).await;
+    observe_order_roundtrip("sell", started.elapsed());
+    match posted {
+        Ok(resp) if resp.success => {
+            crate::console_println!(
+                "{}",
+                format!(
+                    "\n✓✓✓ [{}] SELL COMPLETED ✓✓✓\n  Order ID: {}\n  Tokens Sold: {:.2} tokens\n  Price: ${:.4}\n  Proceeds: ${:.4} USDC\n",
+                    side,
+                    resp.order_id.as_deref().unwrap_or("N/A"),
+                    shares,
+                    floored_price,
+                    proceeds_usdc
+                )
+                .green()
+            );
+
+            ArbitrageOrderResult {
+                success: true,
+                token_id: token_id.to_string(),
+                side: side.to_string(),
+                amount: proceeds_usdc,
+                price: floored_price,
+                tokens_bought: None,
+                tokens_sold: Some(shares),
+                error: None,
+            }
+        }
+        Ok(resp) => {
+            let error_msg = resp.error.unwrap_or_else(|| "Unknown error".to_string());
+            crate::console_println!("{}", format!("✗ [{}] Sell failed: {}", side, error_msg).red());
+            log_error(&format!("[{}] Sell failed: {}", side, error_msg), Some(&context));
+            create_error_result(token_id, side, error_msg)
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to post order: {}", e);
+            crate::console_println!("{}", format!("✗ [{}] {}", side, error_msg).red());
+            log_error(&error_msg, Some(&context));
+            create_error_result(token_id, side, error_msg)
+        }
+    }
+}
+
+// Per-leg USDC for `token_amount` tokens each (FYI: plan_buy_leg still bumps tiny sizes up to the minimum)
+pub(crate) fn calculate_trade_amounts(token_amount: f64, up_price: f64, down_price: f64) -> (f64, f64, f64) {
+    let token_amount = floor_to_decimals(token_amount, TOKEN_DECIMALS);
     let up_amount_usdc = floor_to_decimals(token_amount * up_price, PRICE_DECIMALS);
     let down_amount_usdc = floor_to_decimals(token_amount * down_price, PRICE_DECIMALS);
     (token_amount, up_amount_usdc, down_amount_usdc)
@@ -216,7 +327,8 @@ pub async fn execute_arbitrage_trade(
     down_price: f64,
     _up_bid_price: f64, // Unused (would be for liquidation)
     _down_bid_price: f64, // Unused
-    env: &Env,
+    token_amount: f64, // Tokens per leg (BTW: already scaled by the expiry policy)
+    funds: Option<&FundsGuard>, // Balance/allowance check before each leg
 ) -> Result<(ArbitrageOrderResult, ArbitrageOrderResult, bool)> {
     // Validate inputs (AFAIK: fail fast on bad data)
     if up_token_id.trim().is_empty() || down_token_id.trim().is_empty() {
@@ -227,7 +339,7 @@ pub async fn execute_arbitrage_trade(
         return Err(anyhow!("Invalid prices"));
     }
 
-    let (token_amount, up_amount_usdc, down_amount_usdc) = calculate_trade_amounts(up_price, down_price);
+    let (token_amount, up_amount_usdc, down_amount_usdc) = calculate_trade_amounts(token_amount, up_price, down_price);
 
     // Check minimums (BTW: both sides must meet minimum order size)
     if up_amount_usdc < MIN_ORDER_SIZE_USD || down_amount_usdc < MIN_ORDER_SIZE_USD {
@@ -238,7 +350,7 @@ pub async fn execute_arbitrage_trade(
         ));
     }
 
-    println!(
+    crate::console_println!(
         "{}",
         format!(
             "\n⚡ Executing arbitrage trade: Buying {:.2} tokens each\n  UP: ${:.4} → ${:.2} USDC\n  DOWN: ${:.4} → ${:.2} USDC\n  Total: {:.2} tokens, ${:.2} USDC\n",
@@ -249,13 +361,37 @@ pub async fn execute_arbitrage_trade(
     );
 
     // Execute both orders (IMO: sequential for now, could be parallel)
-    let up_result = execute_buy_order(clob_client, up_token_id, "UP", up_amount_usdc, up_price).await;
-    let down_result = execute_buy_order(clob_client, down_token_id, "DOWN", down_amount_usdc, down_price).await;
+    // Each leg is checked against the cached balance first (BTW: a filled UP leg is debited before DOWN is checked)
+    let up_check = funds.map_or(Ok(()), |f| f.can_fund(up_amount_usdc, true));
+    let up_result = match up_check {
+        Err(ref reason) => create_error_result(up_token_id, "UP", reason.clone()),
+        Ok(()) => execute_buy_order(clob_client, up_token_id, "UP", up_amount_usdc, up_price).await,
+    };
+    if let (Some(f), true) = (funds, up_result.success) {
+        f.debit(up_result.amount, true);
+    }
+
+    // No funds for DOWN means no DOWN order (IMO: never start a leg we can't pay for)
+    let down_check = match (funds, up_check) {
+        (_, Err(_)) => Err("UP leg refused, DOWN not sent".to_string()),
+        (Some(f), Ok(())) => f.can_fund(down_amount_usdc, true),
+        (None, Ok(())) => Ok(()),
+    };
+    let down_result = match down_check {
+        Err(reason) => {
+            crate::console_println!("{}", format!("✗ [DOWN] Not sent: {}", reason).red());
+            create_error_result(down_token_id, "DOWN", reason)
+        }
+        Ok(()) => execute_buy_order(clob_client, down_token_id, "DOWN", down_amount_usdc, down_price).await,
+    };
+    if let (Some(f), true) = (funds, down_result.success) {
+        f.debit(down_result.amount, true);
+    }
 
     let both_success = up_result.success && down_result.success; // Check if both succeeded
 
     if both_success {
-        println!(
+        crate::console_println!(
             "{}",
             format!(
                 "\n╔════════════════════════════════════════════════════════════════╗\n║         🎉 ARBITRAGE TRADE COMPLETED SUCCESSFULLY! 🎉          ║\n╚════════════════════════════════════════════════════════════════╝\n  ✅ UP Order:\n     • Tokens: {:.2}\n     • Price: ${:.4}\n     • Amount: ${:.2} USDC\n  ✅ DOWN Order:\n     • Tokens: {:.2}\n     • Price: ${:.4}\n     • Amount: ${:.2} USDC\n  📊 Summary:\n     • Total Spent: ${:.2} USDC\n     • Status: Both orders executed successfully\n╔════════════════════════════════════════════════════════════════╗\n\n",
@@ -282,3 +418,109 @@ pub async fn execute_arbitrage_trade(
     Ok((up_result, down_result, both_success))
 }
 
+// Sell back a leg that filled when its partner didn't (IMO: a lone leg is a directional bet, not an arb)
+pub async fn unwind_leg(clob_client: &ClobClient, filled: &ArbitrageOrderResult, bid_price: f64) -> ArbitrageOrderResult {
+    let shares = filled.tokens_bought.unwrap_or(0.0);
+    crate::console_println!(
+        "{}",
+        format!("↩ Unwinding {} leg: {:.2} tokens at ${:.4}", filled.side, shares, bid_price).yellow()
+    );
+    execute_sell_order(clob_client, &filled.token_id, &filled.side, shares, bid_price).await
+}
+
+// Sell-side arbitrage (FYI: split USDC into UP+DOWN sets on-chain, then sell both legs into the bids)
+pub async fn execute_split_and_sell(
+    clob_client: &ClobClient,
+    ctf_client: &CtfClient,
+    condition_id: &str,
+    up_token_id: &str,
+    down_token_id: &str,
+    up_bid_price: f64,
+    down_bid_price: f64,
+    sets: f64,
+    fee_rate_bps: f64,
+) -> Result<(ArbitrageOrderResult, ArbitrageOrderResult, bool, String)> {
+    if condition_id.trim().is_empty() || up_token_id.trim().is_empty() || down_token_id.trim().is_empty() {
+        return Err(anyhow!("Invalid condition or token IDs"));
+    }
+
+    if up_bid_price <= 0.0 || !up_bid_price.is_finite() || down_bid_price <= 0.0 || !down_bid_price.is_finite() {
+        return Err(anyhow!("Invalid prices"));
+    }
+
+    let sets = floor_to_decimals(sets, TOKEN_DECIMALS);
+    if sets < MIN_TOKEN_AMOUNT {
+        return Err(anyhow!("Set size {:.2} below minimum {:.2} tokens", sets, MIN_TOKEN_AMOUNT));
+    }
+
+    // Fee and gas check (IMO: a split that costs more than the spread is just a donation)
+    // Taker fees on both sells, same formula evaluate_buy uses for the buy legs
+    let gross_profit = (up_bid_price + down_bid_price - 1.0) * sets;
+    let fees_usd = taker_fee_usdc(fee_rate_bps, up_bid_price, sets) + taker_fee_usdc(fee_rate_bps, down_bid_price, sets);
+    let gas_cost_usd = ctf_client.estimate_split_cost_usd(condition_id, sets).await?;
+    if fees_usd + gas_cost_usd >= gross_profit {
+        return Err(anyhow!(
+            "Split fees ${:.4} + gas ${:.4} exceed spread profit ${:.4}, skipping",
+            fees_usd,
+            gas_cost_usd,
+            gross_profit
+        ));
+    }
+
+    // Approve before splitting (BTW: otherwise the split succeeds and both sells fail)
+    ctf_client.ensure_exchange_approval().await.map_err(|e| {
+        log_error(&format!("setApprovalForAll failed: {}", e), Some("executeSplitAndSell"));
+        e
+    })?;
+
+    crate::console_println!(
+        "{}",
+        format!(
+            "\n⚡ Executing split-and-sell: {:.2} sets\n  UP_BID: ${:.4} + DOWN_BID: ${:.4} = ${:.4}\n  Gross: ${:.4} USDC, Fees: ~${:.4} USDC, Gas: ~${:.4} USDC\n",
+            sets, up_bid_price, down_bid_price, up_bid_price + down_bid_price, gross_profit, fees_usd, gas_cost_usd
+        )
+        .green()
+        .bold()
+    );
+
+    let tx_hash = ctf_client.split_position(condition_id, sets).await.map_err(|e| {
+        log_error(&format!("splitPosition failed: {}", e), Some("executeSplitAndSell"));
+        e
+    })?;
+    crate::console_println!("{}", format!("✓ Split {:.2} sets (tx: {:?})", sets, tx_hash).green());
+    let tx_hash = format!("{:?}", tx_hash); // Returned for the ledger
+
+    let up_result = execute_sell_order(clob_client, up_token_id, "UP", sets, up_bid_price).await;
+    let down_result = execute_sell_order(clob_client, down_token_id, "DOWN", sets, down_bid_price).await;
+
+    let both_success = up_result.success && down_result.success;
+
+    if both_success {
+        crate::console_println!(
+            "{}",
+            format!(
+                "\n🎉 SPLIT-AND-SELL COMPLETED\n  UP: {:.2} @ ${:.4} → ${:.2}\n  DOWN: {:.2} @ ${:.4} → ${:.2}\n  Cost: ${:.2} USDC, Proceeds: ${:.2} USDC\n",
+                up_result.tokens_sold.unwrap_or(0.0),
+                up_result.price,
+                up_result.amount,
+                down_result.tokens_sold.unwrap_or(0.0),
+                down_result.price,
+                down_result.amount,
+                sets,
+                up_result.amount + down_result.amount
+            )
+            .green()
+            .bold()
+        );
+    } else {
+        // Unsold legs stay in the wallet as tokens (BTW: they can still be merged back later)
+        let error_msg = format!(
+            "Split-and-sell incomplete - UP: {}, DOWN: {}",
+            up_result.error.as_deref().unwrap_or("OK"),
+            down_result.error.as_deref().unwrap_or("OK")
+        );
+        log_error(&error_msg, Some("executeSplitAndSell"));
+    }
+
+    Ok((up_result, down_result, both_success, tx_hash))
+}
@@ -1,15 +1,90 @@
-use crate::config::Env;
+use crate::config::{Env, POLYGON_CHAIN_ID};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer as _;
 use anyhow::{anyhow, Result};
+use colored::*;
 use ethers::prelude::*;
-use std::sync::Arc;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::Normal;
+use polymarket_client_sdk::clob::types::{
+    Amount, OrderStatusType, OrderType as SdkOrderType, Side, SignatureType, SignedOrder,
+};
+use polymarket_client_sdk::clob::Client;
+use polymarket_client_sdk::types::{Decimal, U256 as SdkU256};
+use std::str::FromStr;
 
-// Note: This is a placeholder implementation
-// In a real implementation, you would need to integrate with Polymarket's CLOB client SDK
-// For Rust, you might need to use their REST API directly or create bindings
+// Safe getThreshold() selector (FYI: Polymarket's email/Magic proxy wallets don't have it)
+const GET_THRESHOLD_SELECTOR: [u8; 4] = [0xe7, 0x52, 0x35, 0xb8];
 
+// How PROXY_WALLET holds the funds the CLOB trades from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletKind {
+    Eoa,        // The signer itself
+    GnosisSafe, // Safe owned by the signer
+    Proxy,      // Polymarket email/Magic proxy
+}
+
+impl WalletKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            WalletKind::Eoa => "EOA (Externally Owned Account)",
+            WalletKind::GnosisSafe => "Gnosis Safe",
+            WalletKind::Proxy => "Polymarket Proxy",
+        }
+    }
+}
+
+// Contract with a working getThreshold() is a Safe; any other contract is a Polymarket proxy
+pub async fn detect_wallet_kind(provider: &Provider<Http>, wallet: Address, signer: Address) -> Result<WalletKind> {
+    if wallet == signer {
+        return Ok(WalletKind::Eoa);
+    }
+    let code = provider.get_code(wallet, None).await?;
+    if code.is_empty() {
+        return Err(anyhow!(
+            "PROXY_WALLET {:?} is neither the signer {:?} nor a contract wallet",
+            wallet,
+            signer
+        ));
+    }
+    let call: TypedTransaction = TransactionRequest::new()
+        .to(wallet)
+        .data(Bytes::from(GET_THRESHOLD_SELECTOR.to_vec()))
+        .into();
+    match provider.call(&call, None).await {
+        Ok(threshold) if threshold.len() == 32 && threshold.iter().any(|b| *b != 0) => Ok(WalletKind::GnosisSafe),
+        _ => Ok(WalletKind::Proxy),
+    }
+}
+
+fn parse_token_id(token_id: &str) -> Result<SdkU256> {
+    SdkU256::from_str_radix(token_id.trim(), 10).map_err(|e| anyhow!("Invalid token ID {}: {}", token_id, e))
+}
+
+// f64 -> Decimal at `decimals` places (BTW: normalized so 0.4500 passes a 0.01 tick check)
+fn to_decimal(value: f64, decimals: usize) -> Result<Decimal> {
+    Ok(Decimal::from_str(&format!("{:.*}", decimals, value))?.normalize())
+}
+
+fn to_sdk_side(side: OrderSide) -> Side {
+    match side {
+        OrderSide::Buy => Side::Buy,
+        OrderSide::Sell => Side::Sell,
+    }
+}
+
+fn to_sdk_order_type(order_type: OrderType) -> SdkOrderType {
+    match order_type {
+        OrderType::FAK => SdkOrderType::FAK,
+        OrderType::GTC => SdkOrderType::GTC,
+    }
+}
+
+// Authenticated CLOB client plus the key that signs its orders
 pub struct ClobClient {
-    // Placeholder fields
-    // In a real implementation, this would contain the actual client
+    client: Client<Authenticated<Normal>>,
+    signer: PrivateKeySigner,
 }
 
 impl ClobClient {
@@ -23,63 +98,125 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| anyhow!("PROXY_WALLET is required"))?;
 
-        // Check if proxy wallet is a contract (Gnosis Safe)
+        let signer = PrivateKeySigner::from_str(private_key.trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Invalid private key: {}", e))?
+            .with_chain_id(Some(POLYGON_CHAIN_ID));
+        let signer_address = Address::from_slice(signer.address().as_slice());
+        let funder = proxy_wallet.trim().parse::<Address>()?;
+
         let provider = Provider::<Http>::try_from(&env.rpc_url)?;
-        let code = provider.get_code(proxy_wallet.parse::<Address>()?, None).await?;
-        let is_proxy_safe = !code.is_empty();
-
-        println!(
-            "{}",
-            format!(
-                "Wallet type detected: {}",
-                if is_proxy_safe {
-                    "Gnosis Safe"
-                } else {
-                    "EOA (Externally Owned Account)"
-                }
-            )
-            .cyan()
-        );
-
-        // TODO: Initialize actual CLOB client
-        // This would require implementing the Polymarket CLOB API client in Rust
-        // or using their REST API directly
-
-        Ok(ClobClient {})
+        let kind = detect_wallet_kind(&provider, funder, signer_address).await?;
+
+        crate::console_println!("{}", format!("Wallet type detected: {}", kind.label()).cyan());
+
+        // L1 auth derives (or creates) the API key; a Safe or proxy funds orders from PROXY_WALLET
+        let mut auth = Client::new(&env.clob_http_url, Default::default())?.authentication_builder(&signer);
+        auth = match kind {
+            WalletKind::Eoa => auth.signature_type(SignatureType::Eoa),
+            WalletKind::GnosisSafe => auth
+                .funder(funder.0.into())
+                .signature_type(SignatureType::GnosisSafe),
+            WalletKind::Proxy => auth.funder(funder.0.into()).signature_type(SignatureType::Proxy),
+        };
+        let client = auth.authenticate().await?;
+
+        Ok(ClobClient { client, signer })
     }
 
+    // Marketable order (FYI: buys spend `amount` USDC, sells give up `amount` shares; `price` is the worst fill)
     pub async fn create_market_order(
         &self,
         side: OrderSide,
         token_id: &str,
         amount: f64,
         price: f64,
-    ) -> Result<String> {
-        // TODO: Implement actual order creation
-        // This is a placeholder
-        Err(anyhow!("CLOB client not fully implemented - requires Polymarket SDK integration"))
+    ) -> Result<SignedOrder> {
+        let amount = match side {
+            OrderSide::Buy => Amount::usdc(to_decimal(amount, 2)?)?,
+            OrderSide::Sell => Amount::shares(to_decimal(amount, 2)?)?,
+        };
+        let order = self
+            .client
+            .market_order()
+            .token_id(parse_token_id(token_id)?)
+            .side(to_sdk_side(side))
+            .amount(amount)
+            .price(to_decimal(price, 4)?)
+            .order_type(SdkOrderType::FAK)
+            .build()
+            .await?;
+        Ok(self.client.sign(&self.signer, order).await?)
+    }
+
+    pub async fn post_order(&self, mut signed_order: SignedOrder, order_type: OrderType) -> Result<OrderResponse> {
+        // Order type isn't part of the signed payload (BTW: so it can be set at post time)
+        signed_order.order_type = to_sdk_order_type(order_type);
+        let resp = self.client.post_order(signed_order).await?;
+        let error = resp.error_msg.filter(|msg| !msg.is_empty());
+        Ok(OrderResponse {
+            success: resp.success && error.is_none(),
+            order_id: Some(resp.order_id).filter(|id| !id.is_empty()),
+            error,
+        })
+    }
+
+    pub async fn post_orders(&self, orders: Vec<(SignedOrder, OrderType)>) -> Result<Vec<OrderResponse>> {
+        let mut responses = Vec::with_capacity(orders.len());
+        for (signed_order, order_type) in orders {
+            responses.push(self.post_order(signed_order, order_type).await?);
+        }
+        Ok(responses)
+    }
+
+    // Limit order sized in shares (FYI: posted as GTC it rests on the book until filled or cancelled)
+    pub async fn create_limit_order(
+        &self,
+        side: OrderSide,
+        token_id: &str,
+        size: f64,
+        price: f64,
+    ) -> Result<SignedOrder> {
+        let order = self
+            .client
+            .limit_order()
+            .token_id(parse_token_id(token_id)?)
+            .side(to_sdk_side(side))
+            .size(to_decimal(size, 2)?)
+            .price(to_decimal(price, 4)?)
+            .order_type(SdkOrderType::GTC)
+            .build()
+            .await?;
+        Ok(self.client.sign(&self.signer, order).await?)
     }
 
-    pub async fn post_order(&self, signed_order: &str, order_type: OrderType) -> Result<OrderResponse> {
-        // TODO: Implement actual order posting
-        Err(anyhow!("CLOB client not fully implemented - requires Polymarket SDK integration"))
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let resp = self.client.cancel_order(order_id).await?;
+        match resp.not_canceled.get(order_id) {
+            Some(reason) => Err(anyhow!("Order {} not cancelled: {}", order_id, reason)),
+            None => Ok(()),
+        }
     }
 
-    pub async fn post_orders(&self, orders: Vec<(String, OrderType)>) -> Result<Vec<OrderResponse>> {
-        // TODO: Implement batch order posting
-        Err(anyhow!("CLOB client not fully implemented - requires Polymarket SDK integration"))
+    // Current state of one of our orders (AFAIK: size_matched is cumulative)
+    pub async fn get_order(&self, order_id: &str) -> Result<OpenOrder> {
+        let order = self.client.order(order_id).await?;
+        Ok(OpenOrder {
+            size_matched: order.size_matched.to_string().parse()?,
+            live: matches!(order.status, OrderStatusType::Live | OrderStatusType::Delayed),
+        })
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub enum OrderSide {
     Buy,
     Sell,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub enum OrderType {
     FAK, // Fill and Kill
+    GTC, // Good Til Cancelled (rests on the book)
 }
 
 #[derive(Debug, Clone)]
@@ -89,7 +226,12 @@ pub struct OrderResponse {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct OpenOrder {
+    pub size_matched: f64, // Shares filled so far
+    pub live: bool, // Still resting on the book
+}
+
 pub async fn create_clob_client(env: &Env) -> Result<ClobClient> {
     ClobClient::new(env).await
 }
-
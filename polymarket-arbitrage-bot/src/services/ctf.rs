@@ -0,0 +1,311 @@
+use crate::config::{Env, CTF_CONTRACT_ADDRESS, CTF_EXCHANGE_ADDRESS, POLYGON_CHAIN_ID, USDC_CONTRACT_ADDRESS};
+use crate::services::create_clob_client::{detect_wallet_kind, WalletKind};
+use anyhow::{anyhow, Result};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Minimal ABIs (FYI: only the calls we actually make)
+abigen!(
+    ConditionalTokens,
+    r#"[
+        function splitPosition(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount)
+        function mergePositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount)
+        function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets)
+        function payoutDenominator(bytes32 conditionId) view returns (uint256)
+        function balanceOf(address owner, uint256 id) view returns (uint256)
+        function isApprovedForAll(address owner, address operator) view returns (bool)
+        function setApprovalForAll(address operator, bool approved)
+    ]"#
+);
+
+abigen!(
+    Erc20,
+    r#"[
+        function balanceOf(address owner) view returns (uint256)
+        function allowance(address owner, address spender) view returns (uint256)
+        function approve(address spender, uint256 amount) returns (bool)
+    ]"#
+);
+
+abigen!(
+    GnosisSafe,
+    r#"[
+        function getThreshold() view returns (uint256)
+        function isOwner(address owner) view returns (bool)
+        function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures) payable returns (bool)
+    ]"#
+);
+
+type SignerClient = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+// USDC and outcome tokens both use 6 decimals (AFAIK: 1 set = 1_000_000 units)
+const TOKEN_UNIT: f64 = 1_000_000.0;
+// Fallback if estimate_gas reverts (BTW: e.g. allowance not set yet)
+const SPLIT_GAS_FALLBACK: u64 = 200_000;
+
+// Binary partition: index sets for outcome 0 and outcome 1
+fn binary_partition() -> Vec<U256> {
+    vec![U256::from(1), U256::from(2)]
+}
+
+fn to_units(amount: f64) -> U256 {
+    U256::from((amount * TOKEN_UNIT).floor() as u64)
+}
+
+fn from_units(amount: U256) -> f64 {
+    amount.as_u128() as f64 / TOKEN_UNIT
+}
+
+fn parse_condition_id(condition_id: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(condition_id.trim_start_matches("0x"))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("Invalid condition ID: {}", condition_id))
+}
+
+// On-chain Conditional Tokens helper (FYI: acts as the funder - directly for an EOA, through execTransaction for a Safe)
+pub struct CtfClient {
+    client: Arc<SignerClient>,
+    ctf: ConditionalTokens<SignerClient>,
+    usdc: Erc20<SignerClient>,
+    owner: Address, // Signs and pays gas
+    holder: Address, // Holds USDC and outcome tokens (PROXY_WALLET, else the signer)
+    safe: Option<GnosisSafe<SignerClient>>, // Set when the holder is a Safe the signer owns
+    exchange: Address,
+    exchange_approved: AtomicBool, // Cached once isApprovedForAll is true (BTW: approval never expires)
+    pol_usd_price: f64,
+}
+
+impl CtfClient {
+    pub async fn new(env: &Env) -> Result<Self> {
+        let private_key = env
+            .private_key
+            .as_ref()
+            .ok_or_else(|| anyhow!("PRIVATE_KEY is required"))?;
+        let wallet = private_key
+            .trim_start_matches("0x")
+            .parse::<LocalWallet>()?
+            .with_chain_id(POLYGON_CHAIN_ID);
+        let owner = wallet.address();
+        let holder = match env.proxy_wallet {
+            Some(ref proxy) => proxy.trim().parse::<Address>()?,
+            None => owner,
+        };
+
+        let provider = Provider::<Http>::try_from(&env.rpc_url)?;
+        let kind = detect_wallet_kind(&provider, holder, owner).await?;
+        let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+        // Split, merge and redeem must run as the wallet the CLOB trades from (IMO: anything else strands tokens on the signer)
+        let safe = match kind {
+            WalletKind::Eoa => None,
+            WalletKind::GnosisSafe => {
+                // Single-owner Safes only: the owner's own tx is the signature (see send)
+                let safe = GnosisSafe::new(holder, client.clone());
+                if !safe.is_owner(owner).call().await? {
+                    return Err(anyhow!("Signer {:?} is not an owner of the Safe {:?}", owner, holder));
+                }
+                let threshold = safe.get_threshold().call().await?;
+                if threshold != U256::one() {
+                    return Err(anyhow!(
+                        "Safe {:?} needs {} signatures - only 1-of-n Safes are supported",
+                        holder,
+                        threshold
+                    ));
+                }
+                Some(safe)
+            }
+            WalletKind::Proxy => {
+                return Err(anyhow!(
+                    "PROXY_WALLET {:?} is a Polymarket proxy wallet, which on-chain CTF calls don't support (use an EOA or a Gnosis Safe)",
+                    holder
+                ));
+            }
+        };
+
+        let ctf = ConditionalTokens::new(CTF_CONTRACT_ADDRESS.parse::<Address>()?, client.clone());
+        let usdc_address = env
+            .usdc_contract_address
+            .as_deref()
+            .unwrap_or(USDC_CONTRACT_ADDRESS)
+            .parse::<Address>()?;
+        let usdc = Erc20::new(usdc_address, client.clone());
+
+        Ok(Self {
+            client,
+            ctf,
+            usdc,
+            owner,
+            holder,
+            safe,
+            exchange: CTF_EXCHANGE_ADDRESS.parse::<Address>()?,
+            exchange_approved: AtomicBool::new(false),
+            pol_usd_price: env.pol_usd_price,
+        })
+    }
+
+    // Tx that runs `data` on `to` as the holder
+    fn holder_tx(&self, to: Address, data: Bytes) -> TypedTransaction {
+        let (to, data) = match self.safe {
+            Some(ref safe) => {
+                // Pre-validated signature (r = owner, s = 0, v = 1): accepted because msg.sender is that owner
+                let mut signatures = Vec::with_capacity(65);
+                signatures.extend_from_slice(H256::from(self.owner).as_bytes());
+                signatures.extend_from_slice(&[0u8; 32]);
+                signatures.push(1);
+                let exec = safe.exec_transaction(
+                    to,
+                    U256::zero(),
+                    data,
+                    0,
+                    U256::zero(),
+                    U256::zero(),
+                    U256::zero(),
+                    Address::zero(),
+                    Address::zero(),
+                    signatures.into(),
+                );
+                (safe.address(), exec.calldata().unwrap_or_default())
+            }
+            None => (to, data),
+        };
+        TransactionRequest::new().from(self.owner).to(to).data(data).into()
+    }
+
+    // Send a call as the holder, wait for the receipt and fail on revert
+    async fn send(&self, to: Address, data: Bytes, what: &str) -> Result<TxHash> {
+        let receipt = self
+            .client
+            .send_transaction(self.holder_tx(to, data), None)
+            .await?
+            .await?
+            .ok_or_else(|| anyhow!("{} dropped from mempool", what))?;
+        if receipt.status != Some(U64::from(1)) {
+            return Err(anyhow!("{} reverted: {:?}", what, receipt.transaction_hash));
+        }
+        Ok(receipt.transaction_hash)
+    }
+
+    // Convert a gas amount to USD at the current gas price
+    async fn gas_to_usd(&self, gas: U256) -> Result<f64> {
+        let gas_price = self.client.get_gas_price().await?;
+        let cost_wei = gas.saturating_mul(gas_price);
+        let cost_pol = ethers::utils::format_units(cost_wei, "ether")?.parse::<f64>()?;
+        Ok(cost_pol * self.pol_usd_price)
+    }
+
+    // Estimate USD gas cost of splitting `sets` complete sets
+    pub async fn estimate_split_cost_usd(&self, condition_id: &str, sets: f64) -> Result<f64> {
+        let data = self
+            .ctf
+            .split_position(
+                self.usdc.address(),
+                [0u8; 32],
+                parse_condition_id(condition_id)?,
+                binary_partition(),
+                to_units(sets),
+            )
+            .calldata()
+            .unwrap_or_default();
+        let gas = self
+            .client
+            .estimate_gas(&self.holder_tx(self.ctf.address(), data), None)
+            .await
+            .unwrap_or_else(|_| U256::from(SPLIT_GAS_FALLBACK));
+        self.gas_to_usd(gas).await
+    }
+
+    // Approve CTF to pull USDC if current allowance is short (FYI: approves max once)
+    async fn ensure_usdc_allowance(&self, amount: U256) -> Result<()> {
+        let allowance = self.usdc.allowance(self.holder, self.ctf.address()).call().await?;
+        if allowance >= amount {
+            return Ok(());
+        }
+
+        let data = self.usdc.approve(self.ctf.address(), U256::MAX).calldata().unwrap_or_default();
+        self.send(self.usdc.address(), data, "USDC approval").await?;
+        Ok(())
+    }
+
+    // Let the CLOB exchange move our outcome tokens (FYI: sells fail without it, checked once per client)
+    pub async fn ensure_exchange_approval(&self) -> Result<()> {
+        if self.exchange_approved.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        if !self.ctf.is_approved_for_all(self.holder, self.exchange).call().await? {
+            let data = self.ctf.set_approval_for_all(self.exchange, true).calldata().unwrap_or_default();
+            self.send(self.ctf.address(), data, "setApprovalForAll").await?;
+        }
+        self.exchange_approved.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    // Split USDC into `sets` UP+DOWN complete sets (BTW: waits for the receipt before returning)
+    pub async fn split_position(&self, condition_id: &str, sets: f64) -> Result<TxHash> {
+        let amount = to_units(sets);
+        self.ensure_usdc_allowance(amount).await?;
+
+        let data = self
+            .ctf
+            .split_position(
+                self.usdc.address(),
+                [0u8; 32],
+                parse_condition_id(condition_id)?,
+                binary_partition(),
+                amount,
+            )
+            .calldata()
+            .unwrap_or_default();
+        self.send(self.ctf.address(), data, "splitPosition").await
+    }
+
+    // Outcome token balance held by the holder, in sets (FYI: token IDs are decimal strings from Gamma)
+    pub async fn token_balance(&self, token_id: &str) -> Result<f64> {
+        let id = U256::from_dec_str(token_id)?;
+        let balance = self.ctf.balance_of(self.holder, id).call().await?;
+        Ok(from_units(balance))
+    }
+
+    // Resolved once the oracle has reported payouts (AFAIK: payoutDenominator is 0 until then)
+    pub async fn is_resolved(&self, condition_id: &str) -> Result<bool> {
+        let denominator = self
+            .ctf
+            .payout_denominator(parse_condition_id(condition_id)?)
+            .call()
+            .await?;
+        Ok(!denominator.is_zero())
+    }
+
+    // Merge `sets` UP+DOWN pairs back into USDC (BTW: works any time before or after resolution)
+    pub async fn merge_positions(&self, condition_id: &str, sets: f64) -> Result<TxHash> {
+        let data = self
+            .ctf
+            .merge_positions(
+                self.usdc.address(),
+                [0u8; 32],
+                parse_condition_id(condition_id)?,
+                binary_partition(),
+                to_units(sets),
+            )
+            .calldata()
+            .unwrap_or_default();
+        self.send(self.ctf.address(), data, "mergePositions").await
+    }
+
+    // Redeem every outcome we hold for a resolved condition (FYI: burns losers, pays winners 1 USDC each)
+    pub async fn redeem_positions(&self, condition_id: &str) -> Result<TxHash> {
+        let data = self
+            .ctf
+            .redeem_positions(
+                self.usdc.address(),
+                [0u8; 32],
+                parse_condition_id(condition_id)?,
+                binary_partition(),
+            )
+            .calldata()
+            .unwrap_or_default();
+        self.send(self.ctf.address(), data, "redeemPositions").await
+    }
+}
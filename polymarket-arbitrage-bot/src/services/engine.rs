@@ -0,0 +1,1057 @@
+use crate::config::Env;
+use crate::services::alerts::Alerter;
+use crate::services::arbitrage_executor::{execute_arbitrage_trade, execute_split_and_sell, unwind_leg, ArbitrageOrderResult};
+use crate::services::create_clob_client::{create_clob_client, ClobClient};
+use crate::services::ctf::CtfClient;
+use crate::services::event_scanner::{scan_events, EventArbitrage};
+use crate::services::expiry_policy::ExpiryPolicy;
+use crate::services::funds_guard::FundsGuard;
+use crate::services::ledger::{Ledger, LedgerEntry, LedgerKind};
+use crate::services::market_discovery::{find_15_min_market, market_end, CoinMarket, WINDOW_SECS};
+use crate::services::market_maker::{MakerConfig, MarketMaker};
+use crate::services::metrics::{forget_books, observe_detection_to_order, observe_event, record_book, record_order, serve_metrics, set_exposure};
+use crate::services::position_manager::{settle_all, PositionManager, SettlementMode};
+use crate::services::price_monitor::{create_price_data, PriceData, PriceMonitor};
+use crate::services::profitability::{evaluate_buy, fetch_fee_rate_bps};
+use crate::services::recorder::MessageRecorder;
+use crate::services::websocket_client::{ConnectionState, MarketWebSocket, OrderbookSnapshot};
+use crate::utils::logger::log_error;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration};
+
+// Everything the engine reports (FYI: frontends decide how to render it, banners or JSON lines)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EngineEvent {
+    Discovering { coin: String },
+    ClientReady { client: String },
+    ClientUnavailable { client: String, error: String, impact: String },
+    RecorderStarted { dir: String },
+    MarketFound { coin: String, slug: String, end_date: String, fee_rate_bps: f64 },
+    NoMarket { coin: String },
+    MarketClosed { coin: String, slug: String, end_date: String },
+    ClosingSoon { coin: String, seconds_remaining: i64 },
+    ArbitrageDetected {
+        coin: String,
+        up_ask: f64,
+        down_ask: f64,
+        ask_sum: f64,
+        spread_pct: f64,
+        net_edge_bps: f64,
+        sets: f64,
+        fees_usdc: f64,
+        gas_usdc: f64,
+        new_prices: bool, // First tick at these prices for this market (BTW: false while the book just repeats)
+    },
+    SellArbitrageDetected { coin: String, up_bid: f64, down_bid: f64, bid_sum: f64, spread_pct: f64, new_prices: bool },
+    QuotesUpdated { coin: String, up_bid: f64, down_bid: f64, sets: f64 },
+    TradeSkipped { coin: String, reason: String },
+    TradeResult {
+        coin: String,
+        kind: String, // "buy", "split_sell", "unwind", "make" or "hedge"
+        success: bool,
+        up_usdc: f64,
+        down_usdc: f64,
+        error: Option<String>,
+    },
+    PositionSettled { market_slug: String, sets: f64, recovered_usdc: f64, available_budget: f64 },
+    ControlsChanged { paused: bool, arbitrage_threshold: f64, min_net_edge_bps: f64 },
+    ConnectionChanged { state: ConnectionState },
+    EventArbitrage(EventArbitrage),
+    Error { message: String },
+    Shutdown,
+}
+
+impl EngineEvent {
+    pub fn level(&self) -> &'static str {
+        match self {
+            EngineEvent::ClientUnavailable { .. } | EngineEvent::NoMarket { .. } | EngineEvent::ClosingSoon { .. } => "warn",
+            EngineEvent::ConnectionChanged { state: ConnectionState::Reconnecting { .. } } => "warn",
+            EngineEvent::ConnectionChanged { state: ConnectionState::Stopped } => "error",
+            EngineEvent::TradeResult { success: false, .. } | EngineEvent::Error { .. } => "error",
+            _ => "info",
+        }
+    }
+}
+
+// Front-end on top of the engine (IMO: TUI, JSON logs and dashboards are all just renderers)
+pub trait Frontend: Send + Sync {
+    fn event(&self, event: &EngineEvent);
+
+    // Called on every book update for the monitored market (BTW: default is to ignore it)
+    fn book_update(&self, _coin: &str, _price_data: &PriceData, _market: &CoinMarket, _monitor: &PriceMonitor) {}
+}
+
+// Knobs that can change while running (FYI: start from Env, the dashboard tweaks them)
+#[derive(Debug, Clone, Copy)]
+pub struct TradingControls {
+    pub paused: bool, // Detection keeps running, no orders are sent
+    pub arbitrage_threshold: f64,
+    pub min_net_edge_bps: f64,
+}
+
+// Latest books for the monitored market (BTW: exactly what the executor decided on)
+#[derive(Debug, Clone)]
+pub struct MarketView {
+    pub coin: String,
+    pub market: Arc<CoinMarket>,
+    pub price_data: PriceData,
+    pub up_book: OrderbookSnapshot,
+    pub down_book: OrderbookSnapshot,
+    pub updated_at: i64, // Local receive time in ms (IMO: best proxy for feed health)
+}
+
+// Milliseconds until the market closes (FYI: None if neither end_date nor the slug gives a usable time)
+pub fn time_until_end_ms(market: &CoinMarket) -> Option<i64> {
+    market_end(market).map(|end| (end - chrono::Utc::now()).num_milliseconds())
+}
+
+// Shared trading state + monitoring loop (AFAIK: one engine per monitored coin)
+pub struct ArbEngine {
+    pub env: Env,
+    frontend: Arc<dyn Frontend>,
+    pub clob_client: Mutex<Option<Arc<ClobClient>>>, // Trading client (lazy init)
+    pub ctf_client: Mutex<Option<Arc<CtfClient>>>, // On-chain CTF client (lazy init, split/merge/redeem)
+    pub funds: Mutex<Option<Arc<FundsGuard>>>, // Balance/allowance/spend-cap checks (lazy init)
+    pub monitor: Mutex<PriceMonitor>, // Price history tracker
+    pub recent_opportunities: Mutex<HashSet<String>>, // Dedup tracker (prevents duplicate trades)
+    pub is_executing_trade: Mutex<bool>, // Trade lock (prevents concurrent executions)
+    pub positions: Mutex<PositionManager>, // Held complete sets + available budget
+    pub controls: Mutex<TradingControls>, // Pause + live thresholds
+    pub expiry: ExpiryPolicy, // Edge/size scaling by time left in the window
+    pub latest: Mutex<Option<MarketView>>, // Last book state per decision
+    pub book_updates: AtomicU64, // Book updates handled since start
+    pub current_market: Mutex<Option<Arc<CoinMarket>>>, // Market book updates are routed to
+    pub ledger: Option<Arc<Ledger>>, // SQLite trade ledger (None if disabled or it failed to open)
+    alerts: Option<Arc<Alerter>>, // Webhook/Telegram alerts (None if no sink is configured)
+    pub maker: Option<Arc<MarketMaker>>, // Passive quoting instead of taking asks (MM_ENABLED)
+    last_detection: Mutex<Option<String>>, // Last detection key seen (BTW: one ledger row / alert per price change)
+    last_skip: Mutex<Option<String>>, // Last skip reported, so a busy book doesn't repeat it every tick
+    lone_legs: Mutex<HashMap<String, (String, f64)>>, // trade_id -> (slug, USDC) of taker legs whose twin failed, until unwound
+    ws: Mutex<Option<Arc<MarketWebSocket>>>, // WS connection (lazy init)
+    closing_warned: Mutex<Option<String>>, // Slug we've already warned about
+}
+
+impl ArbEngine {
+    pub fn new(env: Env, frontend: Arc<dyn Frontend>) -> Arc<Self> {
+        let (settlement, settlement_problem) = SettlementMode::parse(&env.position_settlement);
+        if let Some(message) = settlement_problem {
+            frontend.event(&EngineEvent::Error { message });
+        }
+        let positions = PositionManager::new(env.trading_budget_usdc, settlement);
+        let controls = TradingControls {
+            paused: false,
+            arbitrage_threshold: env.arbitrage_threshold,
+            min_net_edge_bps: env.min_net_edge_bps,
+        };
+
+        let expiry = ExpiryPolicy::from_env(&env);
+
+        // Ledger is best-effort (NGL: no ledger is better than no trading)
+        let ledger = if env.ledger_enabled {
+            match Ledger::open(std::path::Path::new(&env.ledger_path)) {
+                Ok(ledger) => Some(Arc::new(ledger)),
+                Err(e) => {
+                    frontend.event(&EngineEvent::Error {
+                        message: format!("Failed to open ledger {}: {}", env.ledger_path, e),
+                    });
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let alerts = Alerter::start(&env);
+        let maker = env.mm_enabled.then(|| Arc::new(MarketMaker::new(MakerConfig::from_env(&env))));
+
+        Arc::new(Self {
+            env,
+            frontend,
+            clob_client: Mutex::new(None),
+            ctf_client: Mutex::new(None),
+            funds: Mutex::new(None),
+            monitor: Mutex::new(PriceMonitor::new()),
+            recent_opportunities: Mutex::new(HashSet::new()),
+            is_executing_trade: Mutex::new(false),
+            positions: Mutex::new(positions),
+            controls: Mutex::new(controls),
+            expiry,
+            latest: Mutex::new(None),
+            book_updates: AtomicU64::new(0),
+            current_market: Mutex::new(None),
+            ledger,
+            alerts,
+            maker,
+            last_detection: Mutex::new(None),
+            last_skip: Mutex::new(None),
+            lone_legs: Mutex::new(HashMap::new()),
+            ws: Mutex::new(None),
+            closing_warned: Mutex::new(None),
+        })
+    }
+
+    pub fn emit(&self, event: EngineEvent) {
+        observe_event(&event);
+        if let Some(ref alerts) = self.alerts {
+            alerts.notify(&event);
+        }
+        self.frontend.event(&event);
+    }
+
+    pub(crate) fn record(&self, entry: LedgerEntry) {
+        if let Some(ref ledger) = self.ledger {
+            ledger.record(entry);
+        }
+    }
+
+    // Detection row, skipped while the prices haven't moved (FYI: the book ticks far more often than it changes)
+    // Returns whether the prices are new, so alerts can use the same dedup
+    async fn record_detection(&self, market: &CoinMarket, key: String, price: f64, sets: f64, edge_bps: f64) -> bool {
+        let key = format!("{}_{}", market.slug, key);
+        {
+            let mut last = self.last_detection.lock().await;
+            if last.as_deref() == Some(key.as_str()) {
+                return false;
+            }
+            *last = Some(key);
+        }
+        if self.ledger.is_none() {
+            return true;
+        }
+        let mut entry = LedgerEntry::for_market(LedgerKind::Detection, market);
+        entry.price = Some(price);
+        entry.size = Some(sets);
+        entry.edge_bps = Some(edge_bps);
+        self.record(entry);
+        true
+    }
+
+    // Every opportunity we pass on gets a reason (FYI: the same key + reason is only reported once in a row)
+    pub(crate) async fn skip(&self, market: &CoinMarket, key: &str, reason: String) {
+        let tag = format!("{}_{}|{}", market.slug, key, reason);
+        {
+            let mut last = self.last_skip.lock().await;
+            if last.as_deref() == Some(tag.as_str()) {
+                return;
+            }
+            *last = Some(tag);
+        }
+        let mut entry = LedgerEntry::for_market(LedgerKind::Skip, market);
+        entry.error = Some(reason.clone());
+        self.record(entry);
+        self.emit(EngineEvent::TradeSkipped { coin: market.coin.clone(), reason });
+    }
+
+    // Order + fill rows for each leg of one attempt (BTW: `requested` is the price we decided on)
+    pub(crate) fn record_legs(&self, market: &CoinMarket, trade_id: &str, legs: &[(&ArbitrageOrderResult, f64)]) {
+        for (leg, requested) in legs {
+            record_order(leg.success);
+            let mut order = LedgerEntry::for_market(LedgerKind::Order, market);
+            order.trade_id = Some(trade_id.to_string());
+            order.leg = Some(leg.side.clone());
+            order.price = Some(*requested);
+            order.success = Some(leg.success);
+            order.error = leg.error.clone();
+            self.record(order);
+
+            if leg.success {
+                let mut fill = LedgerEntry::for_market(LedgerKind::Fill, market);
+                fill.trade_id = Some(trade_id.to_string());
+                fill.leg = Some(leg.side.clone());
+                fill.price = Some(leg.price);
+                fill.size = leg.tokens_bought.or(leg.tokens_sold);
+                fill.usdc = Some(leg.amount);
+                fill.success = Some(true);
+                self.record(fill);
+            }
+        }
+    }
+
+    // Pull resting market-maker bids (FYI: called on shutdown so nothing is left on the book)
+    pub async fn cancel_quotes(&self) {
+        let client = self.clob_client.lock().await.clone();
+        if let (Some(maker), Some(client)) = (self.maker.as_ref(), client) {
+            maker.cancel_all(self, &client).await;
+        }
+    }
+
+    // Point-in-time gauges for a metrics scrape (BTW: counters are updated as things happen)
+    pub async fn refresh_metrics(&self) {
+        let held = self.positions.lock().await.deployed_usdc();
+        let maker_unhedged = match self.maker {
+            Some(ref maker) => maker.unhedged_usdc().await,
+            None => 0.0,
+        };
+        let taker_unhedged: f64 = self.lone_legs.lock().await.values().map(|(_, usdc)| usdc).sum();
+        set_exposure(held, maker_unhedged + taker_unhedged);
+    }
+
+    // WS health for frontends (FYI: None until the first market is found)
+    pub async fn connection_state(&self) -> Option<ConnectionState> {
+        self.ws.lock().await.as_ref().map(|ws| ws.connection_state())
+    }
+
+    pub async fn set_paused(&self, paused: bool) {
+        self.controls.lock().await.paused = paused;
+        self.emit_controls().await;
+    }
+
+    // Nudge thresholds by a delta (FYI: clamped so a fat finger can't go negative)
+    pub async fn adjust_thresholds(&self, threshold_delta: f64, edge_bps_delta: f64) {
+        {
+            let mut controls = self.controls.lock().await;
+            controls.arbitrage_threshold = (controls.arbitrage_threshold + threshold_delta).clamp(0.5, 1.5);
+            controls.min_net_edge_bps = (controls.min_net_edge_bps + edge_bps_delta).max(0.0);
+        }
+        self.emit_controls().await;
+    }
+
+    async fn emit_controls(&self) {
+        let controls = *self.controls.lock().await;
+        self.emit(EngineEvent::ControlsChanged {
+            paused: controls.paused,
+            arbitrage_threshold: controls.arbitrage_threshold,
+            min_net_edge_bps: controls.min_net_edge_bps,
+        });
+    }
+
+    // Main monitoring loop (FYI: auto-switches to next market when current closes)
+    pub async fn run(self: Arc<Self>, coin: String) -> Result<()> {
+        self.clone().spawn_settlement_loop();
+        if self.env.event_scan_enabled {
+            self.clone().spawn_event_scanner();
+        }
+        if let Some(ref maker) = self.maker {
+            tokio::spawn(maker.clone().run(self.clone()));
+        }
+        if self.env.metrics_enabled {
+            let engine = self.clone();
+            let addr = self.env.metrics_addr.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_metrics(engine.clone(), addr.clone()).await {
+                    engine.emit(EngineEvent::Error { message: format!("Metrics endpoint on {} failed: {}", addr, e) });
+                }
+            });
+        }
+
+        loop {
+            match self.discover_and_monitor(&coin).await {
+                Ok(Some(market)) => {
+                    // Monitor until market closes (BTW: auto-finds next market after)
+                    // Unknown end time: give it one window, then look again
+                    let fallback_deadline = tokio::time::Instant::now() + Duration::from_secs(WINDOW_SECS as u64);
+                    while time_until_end_ms(&market).map_or(tokio::time::Instant::now() < fallback_deadline, |ms| ms > 0) {
+                        sleep(Duration::from_secs(1)).await;
+                    }
+                    self.emit(EngineEvent::MarketClosed {
+                        coin: coin.clone(),
+                        slug: market.slug.clone(),
+                        end_date: market.end_date.clone(),
+                    });
+                    // Lone legs left after a failed unwind resolve with the window (FYI: no longer a hedge problem)
+                    self.lone_legs.lock().await.retain(|_, (slug, _)| *slug != market.slug);
+                }
+                Ok(None) => {
+                    self.emit(EngineEvent::NoMarket { coin: coin.clone() });
+                    sleep(Duration::from_secs(10)).await;
+                }
+                Err(e) => {
+                    self.emit(EngineEvent::Error { message: e.to_string() });
+                    sleep(Duration::from_secs(10)).await;
+                }
+            }
+        }
+    }
+
+    // Periodic settlement (FYI: retries failed merges and redeems resolved markets)
+    fn spawn_settlement_loop(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                sleep(Duration::from_secs(self.env.settle_interval_secs)).await;
+                self.settle_positions().await;
+            }
+        });
+    }
+
+    pub(crate) async fn settle_positions(&self) {
+        let ctf = self.ctf_client.lock().await.clone();
+        let ctf = match ctf {
+            Some(ctf) => ctf,
+            None => return,
+        };
+        for settlement in settle_all(&self.positions, &ctf).await {
+            let held = settlement.held;
+            let kind = if settlement.mode == SettlementMode::Redeem { LedgerKind::Redeem } else { LedgerKind::Merge };
+            let mut entry = LedgerEntry::new(kind, &held.coin, &held.market_slug, &held.window_end);
+            entry.size = Some(settlement.sets);
+            entry.usdc = Some(settlement.recovered_usdc);
+            entry.pnl_usdc = Some(settlement.pnl_usdc);
+            entry.success = Some(true);
+            entry.tx_hash = Some(settlement.tx_hash);
+            self.record(entry);
+
+            let available_budget = self.positions.lock().await.available_budget();
+            self.emit(EngineEvent::PositionSettled {
+                market_slug: held.market_slug,
+                sets: settlement.sets,
+                recovered_usdc: settlement.recovered_usdc,
+                available_budget,
+            });
+        }
+    }
+
+    // Multi-outcome / neg-risk scanner (BTW: detection only, no orders)
+    fn spawn_event_scanner(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                match scan_events(&self.env).await {
+                    Ok(opportunities) => {
+                        for arb in opportunities {
+                            self.emit(EngineEvent::EventArbitrage(arb));
+                        }
+                    }
+                    Err(e) => log_error(&format!("Event scan failed: {}", e), Some("eventScanner")),
+                }
+                sleep(Duration::from_secs(self.env.event_scan_interval_secs)).await;
+            }
+        });
+    }
+
+    // Lazy init of trading clients (NGL: trading disabled but detection still works if these fail)
+    async fn init_clients(&self) {
+        {
+            let mut client_guard = self.clob_client.lock().await;
+            if client_guard.is_none() {
+                match create_clob_client(&self.env).await {
+                    Ok(client) => {
+                        *client_guard = Some(Arc::new(client));
+                        self.emit(EngineEvent::ClientReady { client: "clob".to_string() });
+                    }
+                    Err(e) => self.emit(EngineEvent::ClientUnavailable {
+                        client: "clob".to_string(),
+                        error: e.to_string(),
+                        impact: "Arbitrage detection will work, but automatic trading is disabled.".to_string(),
+                    }),
+                }
+            }
+        }
+
+        // Funds guard, refreshed on its own timer (BTW: one read up front so the first trade isn't refused)
+        if self.env.funds_check_enabled {
+            let mut funds_guard = self.funds.lock().await;
+            if funds_guard.is_none() {
+                match FundsGuard::new(&self.env) {
+                    Ok(guard) => {
+                        let guard = Arc::new(guard);
+                        if let Err(e) = guard.refresh().await {
+                            log_error(&format!("Funds refresh failed: {}", e), Some("fundsGuard"));
+                        }
+                        spawn_funds_refresh(guard.clone(), self.env.funds_refresh_secs);
+                        *funds_guard = Some(guard);
+                        self.emit(EngineEvent::ClientReady { client: "funds".to_string() });
+                    }
+                    Err(e) => self.emit(EngineEvent::ClientUnavailable {
+                        client: "funds".to_string(),
+                        error: e.to_string(),
+                        impact: "Balance and allowance can't be checked, so trades will be skipped.".to_string(),
+                    }),
+                }
+            }
+        }
+
+        // CTF client for split-and-sell and merge/redeem (FYI: skipped if neither is enabled)
+        let settlement = self.positions.lock().await.mode();
+        if self.env.sell_arbitrage_enabled || settlement != SettlementMode::Off {
+            let mut ctf_guard = self.ctf_client.lock().await;
+            if ctf_guard.is_none() {
+                match CtfClient::new(&self.env).await {
+                    Ok(client) => {
+                        *ctf_guard = Some(Arc::new(client));
+                        self.emit(EngineEvent::ClientReady { client: "ctf".to_string() });
+                    }
+                    Err(e) => {
+                        // No settlement path means held sets never credit the budget back, so settlement goes off
+                        if settlement != SettlementMode::Off {
+                            self.positions.lock().await.set_mode(SettlementMode::Off);
+                            self.emit(EngineEvent::Error {
+                                message: format!(
+                                    "POSITION_SETTLEMENT={} needs the CTF client ({}), settlement is off",
+                                    self.env.position_settlement, e
+                                ),
+                            });
+                        }
+                        // Asked-for split-and-sell that can't run is an error, not just a degraded client
+                        if self.env.sell_arbitrage_enabled {
+                            self.emit(EngineEvent::Error {
+                                message: format!("SELL_ARBITRAGE_ENABLED is set but split-and-sell can't run: {}", e),
+                            });
+                        }
+                        self.emit(EngineEvent::ClientUnavailable {
+                            client: "ctf".to_string(),
+                            error: e.to_string(),
+                            impact: "Split-and-sell and automatic merge/redeem are disabled.".to_string(),
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    // Initialize WebSocket if needed (FYI: runs in background task with auto-reconnect)
+    async fn ensure_websocket(self: &Arc<Self>) -> Arc<MarketWebSocket> {
+        let mut ws_guard = self.ws.lock().await;
+        if let Some(ref ws) = *ws_guard {
+            return ws.clone();
+        }
+
+        let mut ws_client = MarketWebSocket::new(self.env.clob_ws_url.clone());
+        if self.env.record_enabled {
+            // Record raw traffic for offline replay (BTW: failure here shouldn't stop trading)
+            match MessageRecorder::start(
+                std::path::PathBuf::from(&self.env.record_dir),
+                self.env.record_rotate_mb * 1024 * 1024,
+                self.env.record_max_files,
+            ) {
+                Ok(recorder) => {
+                    ws_client = ws_client.with_recorder(recorder);
+                    self.emit(EngineEvent::RecorderStarted { dir: self.env.record_dir.clone() });
+                }
+                Err(e) => self.emit(EngineEvent::Error { message: format!("Failed to start recorder: {}", e) }),
+            }
+        }
+        let ws_client = Arc::new(ws_client);
+
+        // One listener for the lifetime of the socket (IMO: this is where the magic happens)
+        // Updates are handled in order; trades are spawned, so a slow order never stalls the feed
+        let mut books = ws_client.book_updates();
+        let engine = self.clone();
+        let ws_clone = ws_client.clone();
+        tokio::spawn(async move {
+            loop {
+                match books.recv().await {
+                    Ok(snapshot) => {
+                        let market = engine.current_market.lock().await.clone();
+                        if let Some(market) = market {
+                            engine.clone().handle_book(market, &ws_clone, snapshot).await;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        log_error(&format!("Book listener lagged, skipped {} updates", skipped), Some("websocket"));
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        // Surface disconnects/reconnects (FYI: alerts and the dashboard both key off these)
+        let mut states = ws_client.watch_state();
+        let engine = self.clone();
+        tokio::spawn(async move {
+            while states.changed().await.is_ok() {
+                let state = states.borrow_and_update().clone();
+                if matches!(state, ConnectionState::Idle | ConnectionState::Connecting) {
+                    continue;
+                }
+                engine.emit(EngineEvent::ConnectionChanged { state });
+            }
+        });
+
+        // Start WebSocket in background (BTW: reconnects with exponential backoff)
+        let ws_clone = ws_client.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ws_clone.run(true).await {
+                log_error(&format!("WebSocket error: {}", e), Some("websocket"));
+            }
+        });
+
+        *ws_guard = Some(ws_client.clone());
+        ws_client
+    }
+
+    async fn discover_and_monitor(self: &Arc<Self>, coin: &str) -> Result<Option<Arc<CoinMarket>>> {
+        self.emit(EngineEvent::Discovering { coin: coin.to_string() });
+        self.init_clients().await;
+
+        // Discover active 15-min market (AFAIK: checks current/next/prev windows)
+        let market = match find_15_min_market(coin).await? {
+            Some(mut m) => {
+                // Taker fee feeds the profitability model (BTW: UP and DOWN share the market's rate)
+                m.fee_rate_bps = fetch_fee_rate_bps(&self.env, &m.up_token_id)
+                    .await
+                    .unwrap_or(self.env.fee_rate_bps);
+                Arc::new(m)
+            }
+            None => return Ok(None),
+        };
+
+        self.emit(EngineEvent::MarketFound {
+            coin: coin.to_string(),
+            slug: market.slug.clone(),
+            end_date: market.end_date.clone(),
+            fee_rate_bps: market.fee_rate_bps,
+        });
+        if market_end(&market).is_none() {
+            self.emit(EngineEvent::Error {
+                message: format!(
+                    "Can't parse end date '{}' for {}; monitoring only, no new sets this window",
+                    market.end_date, market.slug
+                ),
+            });
+        }
+
+        let ws = self.ensure_websocket().await;
+        if let Some(recorder) = ws.recorder() {
+            recorder.record_market(&market); // Replay needs the UP/DOWN pairing
+        }
+
+        // Route updates to the new market and swap subscriptions on the live connection
+        let previous = self.current_market.lock().await.replace(market.clone());
+        if let Some(previous) = previous {
+            if previous.slug != market.slug {
+                let old_tokens = vec![previous.up_token_id.clone(), previous.down_token_id.clone()];
+                forget_books(&old_tokens);
+                ws.unsubscribe(old_tokens)?;
+            }
+        }
+        ws.subscribe(vec![market.up_token_id.clone(), market.down_token_id.clone()])?;
+
+        sleep(Duration::from_secs(2)).await;
+
+        Ok(Some(market))
+    }
+
+    async fn handle_book(self: Arc<Self>, market: Arc<CoinMarket>, ws: &MarketWebSocket, snapshot: OrderbookSnapshot) {
+        let coin = market.coin.as_str();
+        let received_at = std::time::Instant::now();
+
+        // Market closed, stop trading (FYI: the run loop reports the close and rolls over)
+        let time_until_end = time_until_end_ms(&market);
+        if time_until_end.is_some_and(|ms| ms <= 0) {
+            return;
+        }
+
+        // Not our tokens, ignore (AFAIK: we need both UP and DOWN for arb calc)
+        if snapshot.asset_id != market.up_token_id && snapshot.asset_id != market.down_token_id {
+            return;
+        }
+        record_book(&snapshot.asset_id);
+
+        let (up_snap, down_snap) = match (ws.get_orderbook(&market.up_token_id), ws.get_orderbook(&market.down_token_id)) {
+            (Some(up), Some(down)) => (up, down),
+            _ => return,
+        };
+        let controls = *self.controls.lock().await;
+        let price_data = create_price_data(coin, Some(&up_snap), Some(&down_snap), &self.env)
+            .with_threshold(controls.arbitrage_threshold);
+        self.book_updates.fetch_add(1, Ordering::Relaxed);
+        *self.latest.lock().await = Some(MarketView {
+            coin: coin.to_string(),
+            market: market.clone(),
+            price_data: price_data.clone(),
+            up_book: up_snap.clone(),
+            down_book: down_snap.clone(),
+            updated_at: chrono::Utc::now().timestamp_millis(),
+        });
+
+        // Warn once per market when it's closing soon
+        if let Some(ms) = time_until_end.filter(|ms| *ms < 60000) {
+            let mut warned = self.closing_warned.lock().await;
+            if warned.as_deref() != Some(market.slug.as_str()) {
+                *warned = Some(market.slug.clone());
+                self.emit(EngineEvent::ClosingSoon {
+                    coin: coin.to_string(),
+                    seconds_remaining: ms / 1000,
+                });
+            }
+        }
+
+        // Edge and size allowed with this much time left (BTW: Err = no new sets, detection still runs)
+        let rule = self.expiry.evaluate(time_until_end, controls.min_net_edge_bps, self.env.token_amount);
+        let sets = rule.as_ref().map(|r| r.max_sets).unwrap_or(self.env.token_amount);
+
+        // Arbitrage detection (IMO: this is the core logic, net of fees, rounding and gas)
+        // Market-maker mode quotes from its own task instead of taking (BTW: it reads the view stored above)
+        let estimate = evaluate_buy(&up_snap, &down_snap, market.fee_rate_bps, sets, &self.env);
+        if let Some(ref maker) = self.maker {
+            maker.wake();
+        } else if price_data.ask_sum < controls.arbitrage_threshold && estimate.is_tradeable(controls.min_net_edge_bps) {
+            self.monitor.lock().await.record_arbitrage(coin, &price_data); // Log detection
+            let new_prices = self.record_detection(
+                &market,
+                format!("buy_{:.4}_{:.4}", price_data.up_ask, price_data.down_ask),
+                price_data.ask_sum,
+                estimate.sets,
+                estimate.net_edge_bps,
+            )
+            .await;
+            self.emit(EngineEvent::ArbitrageDetected {
+                coin: coin.to_string(),
+                up_ask: price_data.up_ask,
+                down_ask: price_data.down_ask,
+                ask_sum: price_data.ask_sum,
+                spread_pct: price_data.spread * 100.0,
+                net_edge_bps: estimate.net_edge_bps,
+                sets: estimate.sets,
+                fees_usdc: estimate.fees_usdc,
+                gas_usdc: estimate.gas_usdc,
+                new_prices,
+            });
+            let key = format!("buy_{:.4}_{:.4}", price_data.up_ask, price_data.down_ask);
+            match rule {
+                _ if controls.paused => self.skip(&market, &key, "Trading paused".to_string()).await,
+                Err(reason) => self.skip(&market, &key, reason).await,
+                Ok(rule) if estimate.net_edge_bps < rule.min_net_edge_bps => {
+                    let reason = format!(
+                        "Net edge {:.0} bps below the {:.0} bps required with {}s left",
+                        estimate.net_edge_bps,
+                        rule.min_net_edge_bps,
+                        time_until_end.unwrap_or(0) / 1000
+                    );
+                    self.skip(&market, &key, reason).await;
+                }
+                Ok(rule) => self.clone().try_buy(market.clone(), &price_data, rule.max_sets, received_at).await,
+            }
+        } else if self.env.sell_arbitrage_enabled && price_data.bid_sum > self.env.sell_arbitrage_threshold {
+            // Size by top-of-book depth on both bids (AFAIK: we only sell into the best level)
+            let sets = sets
+                .min(up_snap.bids.first().map(|l| l.size).unwrap_or(0.0))
+                .min(down_snap.bids.first().map(|l| l.size).unwrap_or(0.0));
+            let new_prices = self
+                .record_detection(
+                    &market,
+                    format!("sell_{:.4}_{:.4}", price_data.up_bid, price_data.down_bid),
+                    price_data.bid_sum,
+                    sets,
+                    (price_data.bid_sum - 1.0) * 10000.0,
+                )
+                .await;
+            self.emit(EngineEvent::SellArbitrageDetected {
+                coin: coin.to_string(),
+                up_bid: price_data.up_bid,
+                down_bid: price_data.down_bid,
+                bid_sum: price_data.bid_sum,
+                spread_pct: (price_data.bid_sum - self.env.sell_arbitrage_threshold) * 100.0,
+                new_prices,
+            });
+            let key = format!("sell_{:.4}_{:.4}", price_data.up_bid, price_data.down_bid);
+            match rule {
+                _ if controls.paused => self.skip(&market, &key, "Trading paused".to_string()).await,
+                Err(reason) => self.skip(&market, &key, reason).await,
+                Ok(_) => self.clone().try_split_and_sell(market.clone(), &price_data, sets, received_at).await,
+            }
+        }
+
+        let monitor_guard = {
+            let mut monitor_guard = self.monitor.lock().await;
+            monitor_guard.add_to_history(coin, price_data.clone(), &self.env);
+            monitor_guard
+        };
+        self.frontend.book_update(coin, &price_data, &market, &monitor_guard);
+    }
+
+    // Take the trade lock if this opportunity is new (FYI: dedups on prices, keeps last 50 keys)
+    async fn claim_opportunity(&self, opportunity_key: String) -> Result<(), String> {
+        let mut is_exec = self.is_executing_trade.lock().await;
+        let mut opps = self.recent_opportunities.lock().await;
+        if *is_exec {
+            return Err("Another trade is in flight".to_string());
+        }
+        if opps.contains(&opportunity_key) {
+            return Err("Already traded at these prices".to_string());
+        }
+
+        *is_exec = true; // Lock to prevent concurrent trades
+        opps.insert(opportunity_key);
+        if opps.len() > 50 {
+            let first_key = opps.iter().next().cloned();
+            if let Some(key) = first_key {
+                opps.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    // `detected_at` is when the book update arrived (FYI: feeds the detection-to-order latency metric)
+    async fn try_buy(self: Arc<Self>, market: Arc<CoinMarket>, price_data: &PriceData, sets: f64, detected_at: std::time::Instant) {
+        let opportunity_key = format!("buy_{:.4}_{:.4}", price_data.up_ask, price_data.down_ask);
+        let client = match self.clob_client.lock().await.clone() {
+            Some(client) => client,
+            None => {
+                self.skip(&market, &opportunity_key, "CLOB client unavailable".to_string()).await;
+                return;
+            }
+        };
+
+        // Check budget (BTW: merged/redeemed collateral is added back by the position manager)
+        let estimated_cost = sets * (price_data.up_ask + price_data.down_ask);
+        if let Err(reason) = self.check_budget(estimated_cost).await {
+            self.skip(&market, &opportunity_key, reason).await;
+            return;
+        }
+
+        // Wallet funds, exchange allowance and spend caps (FYI: all from cache, no RPC on the hot path)
+        let funds = self.funds.lock().await.clone();
+        if let Err(reason) = self.check_funds(funds.as_deref(), &market, estimated_cost, true) {
+            self.skip(&market, &opportunity_key, reason).await;
+            return;
+        }
+
+        if let Err(reason) = self.claim_opportunity(opportunity_key.clone()).await {
+            self.skip(&market, &opportunity_key, reason).await;
+            return;
+        }
+
+        // Execute trade in background (IMO: don't block price updates)
+        let engine = self.clone();
+        let coin = market.coin.clone();
+        let price_data = price_data.clone();
+        let trade_id = format!("{}-{}", market.slug, chrono::Utc::now().timestamp_millis());
+        tokio::spawn(async move {
+            observe_detection_to_order(detected_at.elapsed());
+            let result = execute_arbitrage_trade(
+                &client,
+                &market.up_token_id,
+                &market.down_token_id,
+                price_data.up_ask,
+                price_data.down_ask,
+                price_data.up_bid,
+                price_data.down_bid,
+                sets,
+                funds.as_deref(),
+            )
+            .await;
+
+            match result {
+                Ok((up, down, success)) => {
+                    if let Some(ref funds) = funds {
+                        let spent: f64 = [&up, &down].iter().filter(|leg| leg.success).map(|leg| leg.amount).sum();
+                        funds.record_spend(&market.slug, spent);
+                    }
+                    engine.record_legs(&market, &trade_id, &[(&up, price_data.up_ask), (&down, price_data.down_ask)]);
+                    engine.emit(EngineEvent::TradeResult {
+                        coin: coin.clone(),
+                        kind: "buy".to_string(),
+                        success,
+                        up_usdc: up.amount,
+                        down_usdc: down.amount,
+                        error: up.error.clone().or_else(|| down.error.clone()),
+                    });
+
+                    // Track the complete sets we now hold (IMO: capital shouldn't sit idle until resolution)
+                    if success {
+                        let mode = {
+                            let mut manager = engine.positions.lock().await;
+                            manager.record_buy(
+                                &market,
+                                up.tokens_bought.unwrap_or(0.0),
+                                down.tokens_bought.unwrap_or(0.0),
+                                up.amount + down.amount,
+                            );
+                            manager.mode()
+                        };
+                        if mode == SettlementMode::Merge {
+                            engine.settle_positions().await;
+                        }
+                    } else if up.success != down.success {
+                        let (filled, bid) = if up.success { (&up, price_data.up_bid) } else { (&down, price_data.down_bid) };
+                        engine.lone_legs.lock().await.insert(trade_id.clone(), (market.slug.clone(), filled.amount));
+                        engine.unwind(&client, &market, &trade_id, filled, bid).await;
+                    }
+                }
+                Err(e) => engine.skip(&market, &opportunity_key, e.to_string()).await,
+            }
+
+            *engine.is_executing_trade.lock().await = false; // Release lock when done
+        });
+    }
+
+    async fn try_split_and_sell(
+        self: Arc<Self>,
+        market: Arc<CoinMarket>,
+        price_data: &PriceData,
+        sets: f64,
+        detected_at: std::time::Instant,
+    ) {
+        let opportunity_key = format!("sell_{:.4}_{:.4}", price_data.up_bid, price_data.down_bid);
+        let clob = self.clob_client.lock().await.clone();
+        let ctf = self.ctf_client.lock().await.clone();
+        let (client, ctf) = match (clob, ctf) {
+            (Some(client), Some(ctf)) => (client, ctf),
+            _ => {
+                self.skip(&market, &opportunity_key, "CLOB or CTF client unavailable".to_string()).await;
+                return;
+            }
+        };
+
+        // Splitting spends `sets` USDC straight from the wallet (BTW: CTF allowance is handled by the split itself)
+        let funds = self.funds.lock().await.clone();
+        if let Err(reason) = self.check_funds(funds.as_deref(), &market, sets, false) {
+            self.skip(&market, &opportunity_key, reason).await;
+            return;
+        }
+
+        if let Err(reason) = self.claim_opportunity(opportunity_key.clone()).await {
+            self.skip(&market, &opportunity_key, reason).await;
+            return;
+        }
+
+        let engine = self.clone();
+        let coin = market.coin.clone();
+        let up_bid = price_data.up_bid;
+        let down_bid = price_data.down_bid;
+        let trade_id = format!("{}-{}", market.slug, chrono::Utc::now().timestamp_millis());
+        tokio::spawn(async move {
+            observe_detection_to_order(detected_at.elapsed());
+            match execute_split_and_sell(
+                &client,
+                &ctf,
+                &market.condition_id,
+                &market.up_token_id,
+                &market.down_token_id,
+                up_bid,
+                down_bid,
+                sets,
+                market.fee_rate_bps,
+            )
+            .await
+            {
+                Ok((up, down, success, tx_hash)) => {
+                    if let Some(ref funds) = funds {
+                        funds.debit(sets, false);
+                        funds.record_spend(&market.slug, sets);
+                    }
+
+                    // Each sold set cost 1 USDC to split (BTW: unsold legs stay as tokens, not counted)
+                    let pnl = if success {
+                        let sold = up.tokens_sold.unwrap_or(0.0).min(down.tokens_sold.unwrap_or(0.0));
+                        let pnl = up.amount + down.amount - sold;
+                        engine.positions.lock().await.record_realized(pnl);
+                        Some(pnl)
+                    } else {
+                        None
+                    };
+
+                    let mut split = LedgerEntry::for_market(LedgerKind::Split, &market);
+                    split.trade_id = Some(trade_id.clone());
+                    split.size = Some(sets);
+                    split.usdc = Some(sets);
+                    split.pnl_usdc = pnl;
+                    split.success = Some(true);
+                    split.tx_hash = Some(tx_hash);
+                    engine.record(split);
+                    engine.record_legs(&market, &trade_id, &[(&up, up_bid), (&down, down_bid)]);
+
+                    engine.emit(EngineEvent::TradeResult {
+                        coin: coin.clone(),
+                        kind: "split_sell".to_string(),
+                        success,
+                        up_usdc: up.amount,
+                        down_usdc: down.amount,
+                        error: up.error.clone().or_else(|| down.error.clone()),
+                    });
+                }
+                Err(e) => engine.skip(&market, &opportunity_key, e.to_string()).await,
+            }
+
+            *engine.is_executing_trade.lock().await = false;
+        });
+    }
+
+    // Pre-trade budget check (BTW: the first refusal is reported as an error, later ones are plain skips)
+    pub(crate) async fn check_budget(&self, cost_usdc: f64) -> Result<(), String> {
+        let mut manager = self.positions.lock().await;
+        if manager.can_afford(cost_usdc) {
+            return Ok(());
+        }
+        if manager.note_exhausted() {
+            let (available, deployed) = (manager.available_budget(), manager.deployed_usdc());
+            drop(manager);
+            self.emit(EngineEvent::Error {
+                message: format!(
+                    "Trading budget exhausted: ${:.2} of TRADING_BUDGET_USDC left, ${:.2} in unsettled sets. Buys resume once sets are merged or redeemed",
+                    available, deployed
+                ),
+            });
+        }
+        Err(format!("Trading budget exhausted (need ${:.2})", cost_usdc))
+    }
+
+    // Pre-trade funds check (FYI: no guard is only fine when FUNDS_CHECK_ENABLED=false)
+    pub(crate) fn check_funds(&self, funds: Option<&FundsGuard>, market: &CoinMarket, cost_usdc: f64, exchange: bool) -> Result<(), String> {
+        match funds {
+            Some(guard) => guard.check(&market.slug, cost_usdc, exchange),
+            None if self.env.funds_check_enabled => Err("Funds guard unavailable".to_string()),
+            None => Ok(()),
+        }
+    }
+
+    // Sell a lone filled leg back into the book (FYI: prefers the latest bid over the one at detection)
+    // A failed unwind leaves the tokens in the wallet, they resolve like any other position
+    pub(crate) async fn unwind(
+        &self,
+        client: &ClobClient,
+        market: &CoinMarket,
+        trade_id: &str,
+        filled: &ArbitrageOrderResult,
+        detection_bid: f64,
+    ) {
+        let bid = self
+            .latest
+            .lock()
+            .await
+            .as_ref()
+            .filter(|view| view.market.slug == market.slug)
+            .map(|view| if filled.side == "UP" { view.price_data.up_bid } else { view.price_data.down_bid })
+            .unwrap_or(detection_bid);
+
+        let result = unwind_leg(client, filled, bid).await;
+        let pnl = if result.success {
+            self.lone_legs.lock().await.remove(trade_id);
+            let pnl = result.amount - filled.amount;
+            self.positions.lock().await.record_realized(pnl);
+            Some(pnl)
+        } else {
+            None
+        };
+
+        let mut entry = LedgerEntry::for_market(LedgerKind::Unwind, market);
+        entry.trade_id = Some(trade_id.to_string());
+        entry.leg = Some(filled.side.clone());
+        entry.price = Some(bid);
+        entry.size = result.tokens_sold.or(filled.tokens_bought);
+        entry.usdc = Some(result.amount);
+        entry.pnl_usdc = pnl;
+        entry.success = Some(result.success);
+        entry.error = result.error.clone();
+        self.record(entry);
+
+        let (up_usdc, down_usdc) = if filled.side == "UP" { (result.amount, 0.0) } else { (0.0, result.amount) };
+        self.emit(EngineEvent::TradeResult {
+            coin: market.coin.clone(),
+            kind: "unwind".to_string(),
+            success: result.success,
+            up_usdc,
+            down_usdc,
+            error: result.error,
+        });
+    }
+}
+
+// Keep the cached balance/allowance fresh (BTW: a failed read just leaves the snapshot to go stale)
+fn spawn_funds_refresh(guard: Arc<FundsGuard>, interval_secs: u64) {
+    tokio::spawn(async move {
+        loop {
+            sleep(Duration::from_secs(interval_secs.max(1))).await;
+            if let Err(e) = guard.refresh().await {
+                log_error(&format!("Funds refresh failed: {}", e), Some("fundsGuard"));
+            }
+        }
+    });
+}
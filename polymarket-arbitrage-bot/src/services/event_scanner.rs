@@ -0,0 +1,369 @@
+use crate::config::{Env, GAMMA_API_HOST};
+use crate::services::market_discovery::{parse_token_ids, GammaMarket};
+use crate::services::websocket_client::{MarketWebSocket, OrderbookLevel, OrderbookSnapshot};
+use anyhow::Result;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Max tokens per POST /books request (FYI: keeps payloads small for big neg-risk events)
+const BOOKS_BATCH_SIZE: usize = 100;
+
+// Raw Gamma event (AFAIK: an event groups one or more binary markets)
+#[derive(Debug, Clone, Deserialize)]
+struct GammaEvent {
+    slug: String,
+    #[serde(default)]
+    title: String,
+    #[serde(rename = "negRisk", default)]
+    neg_risk: bool,
+    #[serde(rename = "endDate", default)]
+    end_date: String,
+    #[serde(default)]
+    markets: Vec<GammaMarket>,
+}
+
+// One leg of a mutually exclusive event (e.g. a candidate's YES token)
+#[derive(Debug, Clone)]
+pub struct EventOutcome {
+    pub label: String,
+    pub token_id: String,
+    pub market_slug: String,
+}
+
+// Event whose outcomes are mutually exclusive (FYI: exactly one leg pays out 1.0)
+#[derive(Debug, Clone)]
+pub struct ExclusiveEvent {
+    pub slug: String,
+    pub title: String,
+    pub neg_risk: bool,
+    pub end_date: String,
+    pub outcomes: Vec<EventOutcome>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BasketSide {
+    Buy,  // Buy every outcome when sum of asks < 1
+    Sell, // Sell every outcome when sum of bids > 1
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BasketLeg {
+    pub label: String,
+    pub token_id: String,
+    pub market_slug: String,
+    pub best_price: f64,
+    pub avg_price: f64, // Volume-weighted price over the sized basket
+}
+
+// Detected basket opportunity across all outcomes of an event
+#[derive(Debug, Clone, Serialize)]
+pub struct EventArbitrage {
+    pub event_slug: String,
+    pub title: String,
+    pub neg_risk: bool,
+    pub end_date: String, // Event end (BTW: capital in a Buy basket is locked until then)
+    pub side: BasketSide,
+    pub price_sum: f64, // Sum of best asks (Buy) or best bids (Sell)
+    pub edge: f64, // Per-set edge at top of book
+    pub basket_size: f64, // Complete sets fillable while still profitable
+    pub basket_notional: f64, // USDC paid (Buy) or received (Sell) for the basket
+    pub legs: Vec<BasketLeg>,
+}
+
+// Fetch one page of active events from Gamma (BTW: 10s timeout like market discovery)
+async fn get_active_events(limit: usize, offset: usize) -> Result<Vec<GammaEvent>> {
+    let url = format!(
+        "{}/events?active=true&closed=false&limit={}&offset={}",
+        GAMMA_API_HOST, limit, offset
+    );
+    let client = reqwest::Client::new();
+    let response = client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Ok(Vec::new());
+    }
+
+    // Parse events one by one (IMO: one malformed event shouldn't kill the whole page)
+    let raw: Vec<serde_json::Value> = response.json().await?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect())
+}
+
+// Turn a Gamma event into its mutually exclusive legs (AFAIK: only neg-risk or single-market events qualify)
+fn to_exclusive_event(event: GammaEvent) -> Option<ExclusiveEvent> {
+    let mut outcomes = Vec::new();
+
+    if event.neg_risk && event.markets.len() > 1 {
+        // Neg-risk: YES of each market is one outcome (FYI: every market must be tradable or the basket is incomplete)
+        for market in &event.markets {
+            if !market.is_accepting_orders() {
+                return None;
+            }
+            let token_ids = parse_token_ids(market).ok()?;
+            let yes_token = token_ids.get("yes")?.clone();
+            outcomes.push(EventOutcome {
+                label: market.question.clone(),
+                token_id: yes_token,
+                market_slug: market.slug.clone(),
+            });
+        }
+    } else if event.markets.len() == 1 {
+        // Single market: its own outcomes are exclusive (e.g. YES/NO, UP/DOWN)
+        let market = &event.markets[0];
+        if !market.is_accepting_orders() {
+            return None;
+        }
+        let token_ids = parse_token_ids(market).ok()?;
+        for (label, token_id) in token_ids {
+            outcomes.push(EventOutcome {
+                label,
+                token_id,
+                market_slug: market.slug.clone(),
+            });
+        }
+    } else {
+        return None; // Independent markets, sums mean nothing
+    }
+
+    if outcomes.len() < 2 {
+        return None;
+    }
+
+    Some(ExclusiveEvent {
+        slug: event.slug,
+        title: event.title,
+        neg_risk: event.neg_risk,
+        end_date: event.end_date,
+        outcomes,
+    })
+}
+
+// List every active event with mutually exclusive outcomes (BTW: pages until Gamma runs dry or limit is hit)
+pub async fn discover_exclusive_events(max_events: usize) -> Result<Vec<ExclusiveEvent>> {
+    let page_size = max_events.clamp(1, 100);
+    let mut events = Vec::new();
+    let mut offset = 0;
+
+    while offset < max_events {
+        let page = get_active_events(page_size, offset).await?;
+        let page_len = page.len();
+        events.extend(page.into_iter().filter_map(to_exclusive_event));
+        if page_len < page_size {
+            break;
+        }
+        offset += page_len;
+    }
+
+    // Last page can overshoot the limit
+    events.truncate(max_events);
+    Ok(events)
+}
+
+// Fetch books for many tokens via CLOB REST (FYI: same level format as WS book messages)
+async fn get_orderbooks(env: &Env, token_ids: &[String]) -> Result<HashMap<String, OrderbookSnapshot>> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/books", env.clob_http_url.trim_end_matches('/'));
+    let mut books = HashMap::new();
+
+    for chunk in token_ids.chunks(BOOKS_BATCH_SIZE) {
+        let body: Vec<serde_json::Value> = chunk
+            .iter()
+            .map(|id| serde_json::json!({ "token_id": id }))
+            .collect();
+        let response = client
+            .post(&url)
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            continue;
+        }
+
+        let raw: Vec<serde_json::Value> = response.json().await?;
+        for book in raw {
+            if let Ok(snapshot) = MarketWebSocket::parse_orderbook_snapshot(&book) {
+                books.insert(snapshot.asset_id.clone(), snapshot);
+            }
+        }
+    }
+
+    Ok(books)
+}
+
+// Walk all legs' ladders in lockstep while a complete set stays profitable (IMO: simplest correct depth sizing)
+// Returns (sets, notional, per-leg notional)
+fn size_basket(ladders: &[&[OrderbookLevel]], side: BasketSide, max_sets: f64) -> (f64, f64, Vec<f64>) {
+    let mut idx = vec![0usize; ladders.len()];
+    let mut remaining: Vec<f64> = ladders
+        .iter()
+        .map(|l| l.first().map(|lvl| lvl.size).unwrap_or(0.0))
+        .collect();
+    let mut sets = 0.0;
+    let mut notional = 0.0;
+    let mut leg_notional = vec![0.0; ladders.len()];
+
+    loop {
+        // Marginal price of one more set at current levels
+        let mut marginal = 0.0;
+        for (leg, ladder) in ladders.iter().enumerate() {
+            match ladder.get(idx[leg]) {
+                Some(level) => marginal += level.price,
+                None => return (sets, notional, leg_notional), // A leg ran out of depth
+            }
+        }
+
+        let profitable = match side {
+            BasketSide::Buy => marginal < 1.0,
+            BasketSide::Sell => marginal > 1.0,
+        };
+        if !profitable || sets >= max_sets {
+            break;
+        }
+
+        let qty = remaining
+            .iter()
+            .cloned()
+            .fold(f64::INFINITY, f64::min)
+            .min(max_sets - sets);
+        if qty <= 0.0 {
+            break;
+        }
+
+        sets += qty;
+        notional += qty * marginal;
+        for (leg, ladder) in ladders.iter().enumerate() {
+            leg_notional[leg] += qty * ladder[idx[leg]].price;
+            remaining[leg] -= qty;
+            // Advance exhausted legs to their next level
+            if remaining[leg] <= f64::EPSILON {
+                idx[leg] += 1;
+                remaining[leg] = ladder.get(idx[leg]).map(|lvl| lvl.size).unwrap_or(0.0);
+            }
+        }
+    }
+
+    (sets, notional, leg_notional)
+}
+
+// Check one event for a basket arbitrage on either side (FYI: needs a book for every leg)
+pub fn evaluate_event(
+    event: &ExclusiveEvent,
+    books: &HashMap<String, OrderbookSnapshot>,
+    max_sets: f64,
+) -> Option<EventArbitrage> {
+    let snapshots: Vec<&OrderbookSnapshot> = event
+        .outcomes
+        .iter()
+        .map(|o| books.get(&o.token_id))
+        .collect::<Option<Vec<_>>>()?;
+
+    let best_asks: Option<Vec<f64>> = snapshots.iter().map(|s| s.asks.first().map(|l| l.price)).collect();
+    let best_bids: Option<Vec<f64>> = snapshots.iter().map(|s| s.bids.first().map(|l| l.price)).collect();
+
+    let ask_sum: Option<f64> = best_asks.as_ref().map(|a| a.iter().sum());
+    let bid_sum: Option<f64> = best_bids.as_ref().map(|b| b.iter().sum());
+
+    let (side, price_sum, best_prices) = match (ask_sum, bid_sum) {
+        (Some(sum), _) if sum < 1.0 => (BasketSide::Buy, sum, best_asks.unwrap()),
+        (_, Some(sum)) if sum > 1.0 => (BasketSide::Sell, sum, best_bids.unwrap()),
+        _ => return None,
+    };
+
+    let ladders: Vec<&[OrderbookLevel]> = snapshots
+        .iter()
+        .map(|s| match side {
+            BasketSide::Buy => s.asks.as_slice(),
+            BasketSide::Sell => s.bids.as_slice(),
+        })
+        .collect();
+    let (basket_size, basket_notional, leg_notional) = size_basket(&ladders, side, max_sets);
+    if basket_size <= 0.0 {
+        return None;
+    }
+
+    let legs = event
+        .outcomes
+        .iter()
+        .zip(best_prices.iter())
+        .zip(leg_notional.iter())
+        .map(|((outcome, best), notional)| BasketLeg {
+            label: outcome.label.clone(),
+            token_id: outcome.token_id.clone(),
+            market_slug: outcome.market_slug.clone(),
+            best_price: *best,
+            avg_price: notional / basket_size,
+        })
+        .collect();
+
+    Some(EventArbitrage {
+        event_slug: event.slug.clone(),
+        title: event.title.clone(),
+        neg_risk: event.neg_risk,
+        end_date: event.end_date.clone(),
+        side,
+        price_sum,
+        edge: (1.0 - price_sum).abs(),
+        basket_size,
+        basket_notional,
+        legs,
+    })
+}
+
+// One full scan: discover events, pull books, evaluate (BTW: detection only, no orders are sent)
+pub async fn scan_events(env: &Env) -> Result<Vec<EventArbitrage>> {
+    let events = discover_exclusive_events(env.event_scan_limit).await?;
+    let token_ids: Vec<String> = events
+        .iter()
+        .flat_map(|e| e.outcomes.iter().map(|o| o.token_id.clone()))
+        .collect();
+    let books = get_orderbooks(env, &token_ids).await?;
+
+    Ok(events
+        .iter()
+        .filter_map(|e| evaluate_event(e, &books, env.event_scan_max_sets))
+        .collect())
+}
+
+pub fn print_event_arbitrage(arb: &EventArbitrage) {
+    let (label, sum_label) = match arb.side {
+        BasketSide::Buy => ("BUY ALL OUTCOMES", "ASK_SUM"),
+        BasketSide::Sell => ("SELL ALL OUTCOMES", "BID_SUM"),
+    };
+    println!(
+        "{}",
+        format!(
+            "\n⚡ EVENT ARBITRAGE ({}) - {}{}\n   Event: {} (ends {})\n   {}: {:.4} across {} outcomes | Edge: {:.2}%\n   Basket: {:.2} sets, ${:.2} USDC",
+            label,
+            arb.title,
+            if arb.neg_risk { " [neg-risk]" } else { "" },
+            arb.event_slug,
+            if arb.end_date.is_empty() { "unknown" } else { &arb.end_date },
+            sum_label,
+            arb.price_sum,
+            arb.legs.len(),
+            arb.edge * 100.0,
+            arb.basket_size,
+            arb.basket_notional
+        )
+        .green()
+        .bold()
+    );
+    for leg in &arb.legs {
+        println!(
+            "{}",
+            format!(
+                "     • {:40.40} best {:.4} avg {:.4} ({})",
+                leg.label, leg.best_price, leg.avg_price, leg.market_slug
+            ).bright_black()
+        );
+    }
+}
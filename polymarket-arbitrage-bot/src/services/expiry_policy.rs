@@ -0,0 +1,93 @@
+use crate::config::Env;
+use anyhow::{anyhow, Result};
+
+// One step of the policy (FYI: applies once remaining time is at or below `remaining_secs`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpiryTier {
+    pub remaining_secs: i64,
+    pub edge_multiplier: f64, // Min net edge is multiplied by this
+    pub size_factor: f64, // Max sets is multiplied by this
+}
+
+// What the policy allows right now
+#[derive(Debug, Clone, Copy)]
+pub struct ExpiryRule {
+    pub min_net_edge_bps: f64,
+    pub max_sets: f64,
+}
+
+// Time-to-expiry policy (IMO: late in the window a lone leg has no time to recover, so demand more and risk less)
+#[derive(Debug, Clone)]
+pub struct ExpiryPolicy {
+    pub cutoff_secs: i64, // No new sets with this little time left
+    pub tiers: Vec<ExpiryTier>, // Sorted loosest first (largest remaining_secs)
+}
+
+impl ExpiryPolicy {
+    pub fn from_env(env: &Env) -> Self {
+        let tiers = match Self::parse_tiers(&env.expiry_tiers) {
+            Ok(tiers) => tiers,
+            Err(e) => {
+                crate::utils::logger::log_error(&format!("Ignoring EXPIRY_TIERS: {}", e), Some("expiryPolicy"));
+                Vec::new()
+            }
+        };
+        Self {
+            cutoff_secs: env.expiry_cutoff_secs,
+            tiers,
+        }
+    }
+
+    // `secs:edge_multiplier:size_factor`, comma separated, e.g. "180:1.5:0.5,60:3:0.25"
+    pub fn parse_tiers(value: &str) -> Result<Vec<ExpiryTier>> {
+        let mut tiers = Vec::new();
+        for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let fields: Vec<&str> = part.split(':').map(str::trim).collect();
+            if fields.len() != 3 {
+                return Err(anyhow!("Bad tier '{}' (expected secs:edge_multiplier:size_factor)", part));
+            }
+            let tier = ExpiryTier {
+                remaining_secs: fields[0].parse()?,
+                edge_multiplier: fields[1].parse()?,
+                size_factor: fields[2].parse()?,
+            };
+            if tier.edge_multiplier < 0.0 || tier.size_factor <= 0.0 {
+                return Err(anyhow!("Bad tier '{}' (multipliers must be positive)", part));
+            }
+            tiers.push(tier);
+        }
+        tiers.sort_by_key(|t| std::cmp::Reverse(t.remaining_secs));
+        Ok(tiers)
+    }
+
+    // Tightest tier that applies (BTW: None means the full base edge and size)
+    pub fn tier(&self, remaining_secs: i64) -> Option<&ExpiryTier> {
+        self.tiers.iter().rev().find(|t| remaining_secs <= t.remaining_secs)
+    }
+
+    // Edge and size for a new set, or why we won't open one
+    // `remaining_ms` is None when the market's end time is unknown
+    pub fn evaluate(&self, remaining_ms: Option<i64>, base_edge_bps: f64, base_sets: f64) -> Result<ExpiryRule, String> {
+        let remaining_secs = match remaining_ms {
+            Some(ms) => ms / 1000,
+            None => return Err("Market end time unknown, not opening new sets".to_string()),
+        };
+        if remaining_secs <= self.cutoff_secs {
+            return Err(format!(
+                "{}s to market close, past the {}s cutoff for new sets",
+                remaining_secs, self.cutoff_secs
+            ));
+        }
+
+        Ok(match self.tier(remaining_secs) {
+            Some(tier) => ExpiryRule {
+                min_net_edge_bps: base_edge_bps * tier.edge_multiplier,
+                max_sets: base_sets * tier.size_factor,
+            },
+            None => ExpiryRule {
+                min_net_edge_bps: base_edge_bps,
+                max_sets: base_sets,
+            },
+        })
+    }
+}
@@ -0,0 +1,169 @@
+use crate::config::{Env, CTF_EXCHANGE_ADDRESS, USDC_CONTRACT_ADDRESS};
+use crate::services::ctf::Erc20;
+use anyhow::{anyhow, Result};
+use ethers::prelude::*;
+use std::sync::Mutex;
+
+// USDC uses 6 decimals (FYI: same as ctf.rs)
+const USDC_UNIT: f64 = 1_000_000.0;
+
+// Last on-chain read of the USDC we can spend
+#[derive(Debug, Clone, Copy)]
+pub struct FundsSnapshot {
+    pub balance_usdc: f64, // Funder balance, debited locally as legs fill
+    pub allowance_usdc: f64, // Funder allowance to the CLOB exchange
+    pub fetched_at: i64, // ms
+}
+
+// What we've spent so far (BTW: resets on restart, the ledger has the full history)
+#[derive(Debug, Default)]
+struct SpendState {
+    day: String,
+    day_usdc: f64,
+    window: String, // Market slug, one per 15-min window
+    window_usdc: f64,
+}
+
+// Pre-trade checks on balance, allowance and spend caps (IMO: cheaper to skip than to leave a lone leg)
+pub struct FundsGuard {
+    usdc: Erc20<Provider<Http>>,
+    funder: Address, // PROXY_WALLET if set, else the signer (AFAIK: CLOB buys and CtfClient splits both spend the funder's USDC)
+    exchange: Address,
+    max_per_window: f64, // 0 = no cap
+    max_per_day: f64, // 0 = no cap
+    stale_after_ms: i64, // Refuse to trade on a snapshot older than this
+    snapshot: Mutex<Option<FundsSnapshot>>,
+    spend: Mutex<SpendState>,
+}
+
+impl FundsGuard {
+    pub fn new(env: &Env) -> Result<Self> {
+        let funder = match (env.proxy_wallet.as_ref(), env.private_key.as_ref()) {
+            (Some(proxy), _) => proxy.parse::<Address>()?,
+            (None, Some(key)) => key.trim_start_matches("0x").parse::<LocalWallet>()?.address(),
+            (None, None) => return Err(anyhow!("PRIVATE_KEY or PROXY_WALLET is required")),
+        };
+
+        let provider = Provider::<Http>::try_from(&env.rpc_url)?;
+        let usdc_address = env
+            .usdc_contract_address
+            .as_deref()
+            .unwrap_or(USDC_CONTRACT_ADDRESS)
+            .parse::<Address>()?;
+
+        Ok(Self {
+            usdc: Erc20::new(usdc_address, std::sync::Arc::new(provider)),
+            funder,
+            exchange: CTF_EXCHANGE_ADDRESS.parse::<Address>()?,
+            max_per_window: env.max_spend_per_window_usdc,
+            max_per_day: env.max_spend_per_day_usdc,
+            stale_after_ms: (env.funds_refresh_secs * 3 * 1000) as i64,
+            snapshot: Mutex::new(None),
+            spend: Mutex::new(SpendState::default()),
+        })
+    }
+
+    // Read balance and allowance from chain (FYI: called on a timer by the engine)
+    pub async fn refresh(&self) -> Result<FundsSnapshot> {
+        let balance = self.usdc.balance_of(self.funder).call().await?;
+        let allowance = self.usdc.allowance(self.funder, self.exchange).call().await?;
+        let snapshot = FundsSnapshot {
+            balance_usdc: to_usdc(balance),
+            allowance_usdc: to_usdc(allowance),
+            fetched_at: chrono::Utc::now().timestamp_millis(),
+        };
+        *self.snapshot.lock().unwrap() = Some(snapshot);
+        Ok(snapshot)
+    }
+
+    pub fn snapshot(&self) -> Option<FundsSnapshot> {
+        *self.snapshot.lock().unwrap()
+    }
+
+    // Can the funder cover `cost_usdc` right now? (BTW: `exchange` = CLOB buy, which also needs the exchange allowance; else a split)
+    pub fn can_fund(&self, cost_usdc: f64, exchange: bool) -> Result<(), String> {
+        let snapshot = self
+            .snapshot()
+            .ok_or_else(|| "USDC balance unknown (no successful RPC read yet)".to_string())?;
+
+        let age_ms = chrono::Utc::now().timestamp_millis() - snapshot.fetched_at;
+        if age_ms > self.stale_after_ms {
+            return Err(format!("USDC balance is stale (last read {}s ago)", age_ms / 1000));
+        }
+        if snapshot.balance_usdc < cost_usdc {
+            return Err(format!(
+                "Insufficient USDC: ${:.2} available, ${:.2} needed",
+                snapshot.balance_usdc, cost_usdc
+            ));
+        }
+        if exchange && snapshot.allowance_usdc < cost_usdc {
+            return Err(format!(
+                "Exchange allowance too low: ${:.2} approved, ${:.2} needed",
+                snapshot.allowance_usdc, cost_usdc
+            ));
+        }
+        Ok(())
+    }
+
+    // Full pre-trade check: funds plus per-window and per-day caps
+    pub fn check(&self, window: &str, cost_usdc: f64, exchange: bool) -> Result<(), String> {
+        {
+            let spend = self.spend.lock().unwrap();
+            let today = today();
+            let window_spent = if spend.window == window { spend.window_usdc } else { 0.0 };
+            let day_spent = if spend.day == today { spend.day_usdc } else { 0.0 };
+
+            if self.max_per_window > 0.0 && window_spent + cost_usdc > self.max_per_window {
+                return Err(format!(
+                    "Window spend cap: ${:.2} spent + ${:.2} > ${:.2}",
+                    window_spent, cost_usdc, self.max_per_window
+                ));
+            }
+            if self.max_per_day > 0.0 && day_spent + cost_usdc > self.max_per_day {
+                return Err(format!(
+                    "Daily spend cap: ${:.2} spent + ${:.2} > ${:.2}",
+                    day_spent, cost_usdc, self.max_per_day
+                ));
+            }
+        }
+        self.can_fund(cost_usdc, exchange)
+    }
+
+    // A leg filled, take it out of the cached balance (FYI: so the next leg sees it before the next refresh)
+    pub fn debit(&self, usdc: f64, exchange: bool) {
+        if let Some(ref mut snapshot) = *self.snapshot.lock().unwrap() {
+            snapshot.balance_usdc -= usdc;
+            if exchange {
+                snapshot.allowance_usdc -= usdc;
+            }
+        }
+    }
+
+    // Count spend against the window and day caps
+    pub fn record_spend(&self, window: &str, usdc: f64) {
+        let mut spend = self.spend.lock().unwrap();
+        let today = today();
+        if spend.day != today {
+            spend.day = today;
+            spend.day_usdc = 0.0;
+        }
+        if spend.window != window {
+            spend.window = window.to_string();
+            spend.window_usdc = 0.0;
+        }
+        spend.day_usdc += usdc;
+        spend.window_usdc += usdc;
+    }
+}
+
+fn to_usdc(amount: U256) -> f64 {
+    // Allowances are often U256::MAX (AFAIK: as_u128 would panic on those)
+    if amount > U256::from(u128::MAX) {
+        return f64::MAX;
+    }
+    amount.as_u128() as f64 / USDC_UNIT
+}
+
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
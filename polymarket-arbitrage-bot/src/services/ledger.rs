@@ -0,0 +1,183 @@
+use crate::services::market_discovery::CoinMarket;
+use crate::utils::logger::log_error;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OpenFlags};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+// One table, one row per thing that happened (IMO: easy to query ad hoc with the sqlite3 CLI)
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS ledger (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts          INTEGER NOT NULL,
+    day         TEXT NOT NULL,
+    coin        TEXT NOT NULL,
+    market_slug TEXT NOT NULL,
+    window_end  TEXT NOT NULL,
+    kind        TEXT NOT NULL,
+    trade_id    TEXT,
+    leg         TEXT,
+    price       REAL,
+    size        REAL,
+    usdc        REAL,
+    edge_bps    REAL,
+    pnl_usdc    REAL,
+    success     INTEGER,
+    error       TEXT,
+    tx_hash     TEXT
+);
+CREATE INDEX IF NOT EXISTS ledger_coin_day ON ledger (coin, day);
+CREATE INDEX IF NOT EXISTS ledger_trade ON ledger (trade_id);
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerKind {
+    Detection, // Opportunity that cleared the net-edge gate (FYI: once per distinct price pair)
+    Order,     // One leg sent to the CLOB, success = accepted
+    Fill,      // Tokens we actually got (or sold) on a leg
+    Unwind,    // Sold the filled leg back after the other leg failed
+    Split,     // splitPosition for split-and-sell
+    Merge,     // mergePositions back to USDC
+    Redeem,    // redeemPositions after resolution
+    Skip,      // Opportunity we passed on, `error` holds the reason
+}
+
+impl LedgerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerKind::Detection => "detection",
+            LedgerKind::Order => "order",
+            LedgerKind::Fill => "fill",
+            LedgerKind::Unwind => "unwind",
+            LedgerKind::Split => "split",
+            LedgerKind::Merge => "merge",
+            LedgerKind::Redeem => "redeem",
+            LedgerKind::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LedgerEntry {
+    pub kind: LedgerKind,
+    pub coin: String,
+    pub market_slug: String,
+    pub window_end: String, // Market end_date, identifies the 15-min window
+    pub trade_id: Option<String>, // Groups the legs of one attempt
+    pub leg: Option<String>, // "UP" / "DOWN"
+    pub price: Option<f64>,
+    pub size: Option<f64>, // Tokens or sets
+    pub usdc: Option<f64>, // Cash moved by this row
+    pub edge_bps: Option<f64>,
+    pub pnl_usdc: Option<f64>, // Realized PnL, only on rows that close something out
+    pub success: Option<bool>,
+    pub error: Option<String>,
+    pub tx_hash: Option<String>,
+}
+
+impl LedgerEntry {
+    pub fn new(kind: LedgerKind, coin: &str, market_slug: &str, window_end: &str) -> Self {
+        Self {
+            kind,
+            coin: coin.to_string(),
+            market_slug: market_slug.to_string(),
+            window_end: window_end.to_string(),
+            trade_id: None,
+            leg: None,
+            price: None,
+            size: None,
+            usdc: None,
+            edge_bps: None,
+            pnl_usdc: None,
+            success: None,
+            error: None,
+            tx_hash: None,
+        }
+    }
+
+    pub fn for_market(kind: LedgerKind, market: &CoinMarket) -> Self {
+        Self::new(kind, &market.coin, &market.slug, &market.end_date)
+    }
+}
+
+// SQLite-backed trade ledger (FYI: one writer thread owns the connection, so tokio workers never block on disk)
+pub struct Ledger {
+    writes: Option<mpsc::Sender<(DateTime<Utc>, LedgerEntry)>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Ledger {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        let (writes, queue) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("ledger-writer".to_string())
+            .spawn(move || run_writer(conn, queue))?;
+        Ok(Self { writes: Some(writes), writer: Some(writer) })
+    }
+
+    // Query-only connection for the report (BTW: no pragma, no DDL, safe next to a running bot)
+    pub fn open_read_only(path: &Path) -> Result<Connection> {
+        Ok(Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?)
+    }
+
+    // Queue one row (BTW: a ledger failure is logged, never allowed to stop trading)
+    pub fn record(&self, entry: LedgerEntry) {
+        let Some(ref writes) = self.writes else {
+            return;
+        };
+        if writes.send((Utc::now(), entry)).is_err() {
+            log_error("Ledger writer has stopped, dropping row", Some("ledger"));
+        }
+    }
+}
+
+impl Drop for Ledger {
+    // Flush what's queued before exit (AFAIK: the writer ends once the channel closes)
+    fn drop(&mut self) {
+        self.writes.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+fn run_writer(conn: Connection, queue: mpsc::Receiver<(DateTime<Utc>, LedgerEntry)>) {
+    for (at, entry) in queue {
+        if let Err(e) = insert(&conn, at, &entry) {
+            log_error(&format!("Ledger write failed: {}", e), Some("ledger"));
+        }
+    }
+}
+
+fn insert(conn: &Connection, at: DateTime<Utc>, entry: &LedgerEntry) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO ledger (ts, day, coin, market_slug, window_end, kind, trade_id, leg, price, size, usdc, edge_bps, pnl_usdc, success, error, tx_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        params![
+            at.timestamp_millis(),
+            at.format("%Y-%m-%d").to_string(),
+            entry.coin,
+            entry.market_slug,
+            entry.window_end,
+            entry.kind.as_str(),
+            entry.trade_id,
+            entry.leg,
+            entry.price,
+            entry.size,
+            entry.usdc,
+            entry.edge_bps,
+            entry.pnl_usdc,
+            entry.success,
+            entry.error,
+            entry.tx_hash,
+        ],
+    )
+}
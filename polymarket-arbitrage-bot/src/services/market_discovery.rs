@@ -1,5 +1,6 @@
 use crate::config::{coin_slug, GAMMA_API_HOST};
 use anyhow::{anyhow, Result};
+use chrono::Timelike;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,22 +10,37 @@ pub struct CoinMarket {
     pub coin: String, // Coin ticker (BTC, ETH, etc.)
     pub up_token_id: String, // UP token contract address
     pub down_token_id: String, // DOWN token contract address
+    pub condition_id: String, // CTF condition ID (needed for split/merge/redeem)
     pub slug: String, // Market slug (e.g., "btc-updown-15m-1234567890")
     pub question: String, // Market question text
     pub end_date: String, // ISO 8601 end date
     pub accepting_orders: bool, // Whether market is still open
+    #[serde(default)]
+    pub fee_rate_bps: f64, // Taker fee rate, filled in from the CLOB after discovery
 }
 
 // Raw Gamma API response (AFAIK: matches Polymarket's API format)
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct GammaMarket {
-    slug: String,
-    question: String,
-    end_date: String,
-    accepting_orders: bool,
+pub(crate) struct GammaMarket {
+    pub(crate) slug: String,
+    pub(crate) question: String,
+    #[serde(rename = "conditionId", default)]
+    pub(crate) condition_id: String,
+    #[serde(alias = "endDate", default)]
+    pub(crate) end_date: String,
+    #[serde(alias = "acceptingOrders")]
+    pub(crate) accepting_orders: Option<bool>, // Missing = open (FYI: only an explicit false closes it)
     #[serde(rename = "clobTokenIds")]
-    clob_token_ids: serde_json::Value, // Can be string or array
-    outcomes: serde_json::Value, // Can be string or array
+    pub(crate) clob_token_ids: serde_json::Value, // Can be string or array
+    pub(crate) outcomes: serde_json::Value, // Can be string or array
+    #[serde(rename = "negRisk", default)]
+    pub(crate) neg_risk: bool, // Part of a neg-risk (multi-candidate) event
+}
+
+impl GammaMarket {
+    pub(crate) fn is_accepting_orders(&self) -> bool {
+        self.accepting_orders.unwrap_or(true)
+    }
 }
 
 // Fetch market data from Gamma API (BTW: 10s timeout to avoid hanging)
@@ -45,7 +61,7 @@ async fn get_market_by_slug(slug: &str) -> Result<Option<GammaMarket>> {
 }
 
 // Parse JSON field that might be string or array (FYI: Polymarket API inconsistency)
-fn parse_json_field<T: for<'de> Deserialize<'de>>(value: &serde_json::Value) -> Result<Vec<T>> {
+pub(crate) fn parse_json_field<T: for<'de> Deserialize<'de>>(value: &serde_json::Value) -> Result<Vec<T>> {
     match value {
         serde_json::Value::String(s) => {
             // String format: parse as JSON string
@@ -62,7 +78,7 @@ fn parse_json_field<T: for<'de> Deserialize<'de>>(value: &serde_json::Value) ->
 }
 
 // Map outcomes to token IDs (AFAIK: creates lookup map like {"up": "0x123...", "down": "0x456..."})
-fn parse_token_ids(market: &GammaMarket) -> Result<HashMap<String, String>> {
+pub(crate) fn parse_token_ids(market: &GammaMarket) -> Result<HashMap<String, String>> {
     let clob_token_ids: Vec<String> = parse_json_field(&market.clob_token_ids)?;
     let outcomes: Vec<String> = parse_json_field(&market.outcomes)?;
     
@@ -77,6 +93,7 @@ fn parse_token_ids(market: &GammaMarket) -> Result<HashMap<String, String>> {
 
 fn parse_market_data(coin: &str, market: GammaMarket) -> Result<CoinMarket> {
     let token_ids = parse_token_ids(&market)?;
+    let accepting_orders = market.is_accepting_orders();
     let up_token_id = token_ids
         .get("up")
         .or_else(|| token_ids.get("yes"))
@@ -92,13 +109,37 @@ fn parse_market_data(coin: &str, market: GammaMarket) -> Result<CoinMarket> {
         coin: coin.to_string(),
         up_token_id,
         down_token_id,
+        condition_id: market.condition_id,
         slug: market.slug,
         question: market.question,
         end_date: market.end_date,
-        accepting_orders: market.accepting_orders,
+        accepting_orders,
+        fee_rate_bps: 0.0,
     })
 }
 
+// Length of one up/down window (FYI: slugs carry the window start, so end = start + 900)
+pub const WINDOW_SECS: i64 = 900;
+
+// When the market closes (AFAIK: Gamma sends RFC 3339, but older markets omit the offset)
+// Falls back to the slug's window start + 15 min, None if neither is usable
+pub fn market_end(market: &CoinMarket) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(end) = chrono::DateTime::parse_from_rfc3339(&market.end_date) {
+        return Some(end.with_timezone(&chrono::Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"] {
+        if let Ok(end) = chrono::NaiveDateTime::parse_from_str(&market.end_date, format) {
+            return Some(end.and_utc());
+        }
+    }
+    market
+        .slug
+        .rsplit('-')
+        .next()
+        .and_then(|ts| ts.parse::<i64>().ok())
+        .and_then(|start| chrono::DateTime::from_timestamp(start + WINDOW_SECS, 0))
+}
+
 // Find active 15-min market for coin (IMO: checks current/next/prev windows)
 pub async fn find_15_min_market(coin: &str) -> Result<Option<CoinMarket>> {
     let coin_upper = coin.to_uppercase();
@@ -117,7 +158,7 @@ pub async fn find_15_min_market(coin: &str) -> Result<Option<CoinMarket>> {
     // Try current window (AFAIK: most likely to be active)
     let mut slug = format!("{}-{}", prefix, current_ts);
     if let Some(market) = get_market_by_slug(&slug).await? {
-        if market.accepting_orders {
+        if market.is_accepting_orders() {
             return Ok(Some(parse_market_data(&coin_upper, market)?));
         }
     }
@@ -126,7 +167,7 @@ pub async fn find_15_min_market(coin: &str) -> Result<Option<CoinMarket>> {
     let next_ts = current_ts + 900; // 15 minutes = 900 seconds
     slug = format!("{}-{}", prefix, next_ts);
     if let Some(market) = get_market_by_slug(&slug).await? {
-        if market.accepting_orders {
+        if market.is_accepting_orders() {
             return Ok(Some(parse_market_data(&coin_upper, market)?));
         }
     }
@@ -135,7 +176,7 @@ pub async fn find_15_min_market(coin: &str) -> Result<Option<CoinMarket>> {
     let prev_ts = current_ts - 900;
     slug = format!("{}-{}", prefix, prev_ts);
     if let Some(market) = get_market_by_slug(&slug).await? {
-        if market.accepting_orders {
+        if market.is_accepting_orders() {
             return Ok(Some(parse_market_data(&coin_upper, market)?));
         }
     }
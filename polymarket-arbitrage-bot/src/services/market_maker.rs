@@ -0,0 +1,580 @@
+use crate::config::Env;
+use crate::services::arbitrage_executor::{execute_buy_order, ArbitrageOrderResult};
+use crate::services::create_clob_client::{ClobClient, OrderSide, OrderType};
+use crate::services::engine::{time_until_end_ms, ArbEngine, EngineEvent, MarketView};
+use crate::services::ledger::{LedgerEntry, LedgerKind};
+use crate::services::market_discovery::CoinMarket;
+use crate::services::position_manager::SettlementMode;
+use crate::services::websocket_client::OrderbookSnapshot;
+use crate::utils::logger::log_error;
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::{sleep, Duration, Instant};
+
+// Polymarket 15-min markets quote in cents (FYI: bids are floored to this)
+const TICK_SIZE: f64 = 0.01;
+// Smallest resting bid or hedge (AFAIK: same 5-token minimum as taker orders)
+const MIN_QUOTE_SHARES: f64 = 5.0;
+// Float noise threshold for share counts
+const SHARE_EPSILON: f64 = 0.000001;
+// Pause between hedge lifts after one fails (BTW: the book listener wakes us far more often than that)
+const LIFT_RETRY: Duration = Duration::from_secs(5);
+
+// Market-maker knobs (FYI: size comes from TOKEN_AMOUNT scaled by the expiry policy, like taker buys)
+#[derive(Debug, Clone)]
+pub struct MakerConfig {
+    pub target_edge_bps: f64, // UP bid + DOWN bid <= 1 - this
+    pub requote_ticks: f64, // Only cancel/replace once the quote moves this many ticks
+    pub hedge_max_sum: f64, // Max filled price + other leg's ask we'll pay to complete a set
+    pub poll_ms: u64, // Order status polling interval
+}
+
+impl MakerConfig {
+    pub fn from_env(env: &Env) -> Self {
+        Self {
+            target_edge_bps: env.mm_target_edge_bps,
+            requote_ticks: env.mm_requote_ticks.max(1.0),
+            hedge_max_sum: env.mm_hedge_max_sum,
+            poll_ms: env.mm_poll_ms.max(100),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quotes {
+    pub up_bid: f64,
+    pub down_bid: f64,
+}
+
+// How to complete a set once one leg filled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hedge {
+    Lift { price: f64 }, // Other ask is cheap enough, take it
+    Improve { price: f64 }, // Rest a better bid on the other leg and wait
+}
+
+fn floor_tick(price: f64) -> f64 {
+    let ticks = (price / TICK_SIZE + SHARE_EPSILON).floor();
+    (ticks * TICK_SIZE * 10000.0).round() / 10000.0
+}
+
+// Best bid and ask (BTW: an empty side counts as 0 bid / 1 ask so we can still quote into it)
+fn top_of_book(book: &OrderbookSnapshot) -> (f64, f64) {
+    let bid = book.bids.first().map(|l| l.price).unwrap_or(0.0);
+    let ask = book.asks.first().map(|l| l.price).unwrap_or(1.0);
+    (bid, ask)
+}
+
+// Passive bids on both legs that sum to at most 1 - target edge
+// One tick above the best bid when that fits, else the budget is split by mid price (IMO: keeps both legs equally likely to fill)
+pub fn compute_quotes(up: &OrderbookSnapshot, down: &OrderbookSnapshot, target_edge_bps: f64) -> Option<Quotes> {
+    let budget = 1.0 - target_edge_bps / 10000.0;
+    let (up_bid, up_ask) = top_of_book(up);
+    let (down_bid, down_ask) = top_of_book(down);
+
+    let mut up_quote = floor_tick((up_bid + TICK_SIZE).min(up_ask - TICK_SIZE));
+    let mut down_quote = floor_tick((down_bid + TICK_SIZE).min(down_ask - TICK_SIZE));
+
+    if up_quote + down_quote > budget + SHARE_EPSILON {
+        let up_mid = (up_bid + up_ask) / 2.0;
+        let down_mid = (down_bid + down_ask) / 2.0;
+        if up_mid + down_mid <= 0.0 {
+            return None;
+        }
+        up_quote = floor_tick((budget * up_mid / (up_mid + down_mid)).min(up_ask - TICK_SIZE));
+        down_quote = floor_tick((budget - up_quote).min(down_ask - TICK_SIZE));
+    }
+
+    if up_quote < TICK_SIZE || down_quote < TICK_SIZE {
+        return None;
+    }
+    Some(Quotes { up_bid: up_quote, down_bid: down_quote })
+}
+
+// Complete a set after `filled_price` filled on one leg (FYI: None = no price keeps us under hedge_max_sum)
+pub fn plan_hedge(filled_price: f64, other: &OrderbookSnapshot, hedge_max_sum: f64) -> Option<Hedge> {
+    let limit = hedge_max_sum - filled_price;
+    let (bid, ask) = top_of_book(other);
+    if !other.asks.is_empty() && ask <= limit + SHARE_EPSILON {
+        return Some(Hedge::Lift { price: ask });
+    }
+    let price = floor_tick((bid + TICK_SIZE).min(limit).min(ask - TICK_SIZE));
+    if price < TICK_SIZE {
+        return None;
+    }
+    Some(Hedge::Improve { price })
+}
+
+// One of our bids on the book
+#[derive(Debug, Clone)]
+struct Resting {
+    order_id: String,
+    price: f64,
+    size: f64, // Shares
+    matched: f64, // Shares filled so far (cumulative, as reported by the CLOB)
+}
+
+// Per-leg state (BTW: `filled` and `cost` only hold shares not yet paired into a set)
+#[derive(Debug, Clone, Default)]
+struct Leg {
+    order: Option<Resting>,
+    filled: f64,
+    cost: f64,
+}
+
+impl Leg {
+    fn avg_price(&self) -> f64 {
+        if self.filled <= SHARE_EPSILON {
+            return 0.0;
+        }
+        self.cost / self.filled
+    }
+}
+
+#[derive(Debug, Default)]
+struct MakerState {
+    market: Option<Arc<CoinMarket>>, // Market the legs and bids belong to
+    up: Leg,
+    down: Leg,
+    seq: u64, // Bumped each time the legs are flat, gives fills a shared trade_id
+    last_poll: Option<Instant>,
+    last_quotes: Option<Quotes>,
+    lift_retry_at: Option<Instant>, // After a failed lift, wait before taking the ask again
+}
+
+impl MakerState {
+    fn slug(&self) -> &str {
+        self.market.as_ref().map(|m| m.slug.as_str()).unwrap_or("")
+    }
+
+    fn trade_id(&self) -> String {
+        format!("{}-mm-{}", self.slug(), self.seq)
+    }
+
+    fn leg_mut(&mut self, side: &str) -> &mut Leg {
+        if side == "UP" {
+            &mut self.up
+        } else {
+            &mut self.down
+        }
+    }
+}
+
+fn token_for<'a>(market: &'a CoinMarket, side: &str) -> &'a str {
+    if side == "UP" {
+        &market.up_token_id
+    } else {
+        &market.down_token_id
+    }
+}
+
+// Passive quoting on both legs (IMO: earn the spread instead of racing other takers for it)
+pub struct MarketMaker {
+    pub config: MakerConfig,
+    wake: Notify,
+    state: Mutex<MakerState>,
+}
+
+impl MarketMaker {
+    pub fn new(config: MakerConfig) -> Self {
+        Self {
+            config,
+            wake: Notify::new(),
+            state: Mutex::new(MakerState::default()),
+        }
+    }
+
+    // Book moved (FYI: called from the WS listener, never blocks it)
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    // Runs for the engine's lifetime, on book updates and on the poll timer
+    pub async fn run(self: Arc<Self>, engine: Arc<ArbEngine>) {
+        loop {
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = sleep(Duration::from_millis(self.config.poll_ms)) => {}
+            }
+            self.step(&engine).await;
+        }
+    }
+
+    // Pull every resting bid (BTW: on shutdown and rollover, filled legs are kept and resolve like any position)
+    pub async fn cancel_all(&self, engine: &ArbEngine, client: &ClobClient) {
+        let mut state = self.state.lock().await;
+        self.pull_quotes(engine, client, &mut state).await;
+    }
+
+    // USDC in fills not yet paired into a set (FYI: the exposure metric)
+    pub async fn unhedged_usdc(&self) -> f64 {
+        let state = self.state.lock().await;
+        state.up.cost + state.down.cost
+    }
+
+    async fn step(&self, engine: &ArbEngine) {
+        let view = match engine.latest.lock().await.clone() {
+            Some(view) => view,
+            None => return,
+        };
+        let client = match engine.clob_client.lock().await.clone() {
+            Some(client) => client,
+            None => return,
+        };
+        let market = view.market.clone();
+        let mut state = self.state.lock().await;
+
+        // New window: old bids are worthless, start flat
+        if state.slug() != market.slug {
+            if state.market.is_some() {
+                self.pull_quotes(engine, &client, &mut state).await;
+                if state.up.filled > SHARE_EPSILON || state.down.filled > SHARE_EPSILON {
+                    log_error(
+                        &format!(
+                            "Market maker rolled over from {} with unhedged legs (UP {:.2}, DOWN {:.2}), left to resolve",
+                            state.slug(),
+                            state.up.filled,
+                            state.down.filled
+                        ),
+                        Some("marketMaker"),
+                    );
+                }
+            }
+            *state = MakerState { market: Some(market.clone()), ..Default::default() };
+        }
+
+        let poll_due = state
+            .last_poll
+            .is_none_or(|t| t.elapsed() >= Duration::from_millis(self.config.poll_ms));
+        if poll_due {
+            state.last_poll = Some(Instant::now());
+            for side in ["UP", "DOWN"] {
+                self.poll_leg(engine, &client, &mut state, side).await;
+            }
+        }
+        self.pair_fills(engine, &market, &mut state).await;
+
+        let controls = *engine.controls.lock().await;
+        let time_left = time_until_end_ms(&market);
+        let rule = engine.expiry.evaluate(time_left, controls.min_net_edge_bps, engine.env.token_amount);
+
+        // One leg is exposed: stop adding to it and complete the set
+        let lone = if state.up.filled >= MIN_QUOTE_SHARES {
+            Some("UP")
+        } else if state.down.filled >= MIN_QUOTE_SHARES {
+            Some("DOWN")
+        } else {
+            None
+        };
+        if let Some(side) = lone {
+            let past_cutoff = rule.is_err();
+            self.hedge(engine, &client, &view, &mut state, side, past_cutoff).await;
+            return;
+        }
+
+        let key = format!("make_{}", market.slug);
+        let rule = match rule {
+            _ if controls.paused => Err("Trading paused".to_string()),
+            rule => rule,
+        };
+        let rule = match rule {
+            Ok(rule) => rule,
+            Err(reason) => {
+                self.pull_quotes(engine, &client, &mut state).await;
+                engine.skip(&market, &key, reason).await;
+                return;
+            }
+        };
+
+        let quotes = match compute_quotes(&view.up_book, &view.down_book, self.config.target_edge_bps) {
+            Some(quotes) => quotes,
+            None => {
+                self.pull_quotes(engine, &client, &mut state).await;
+                engine.skip(&market, &key, "No quotes fit under the target edge".to_string()).await;
+                return;
+            }
+        };
+        let sets = ((rule.max_sets * 100.0).floor() / 100.0).max(MIN_QUOTE_SHARES);
+
+        // Both bids could fill, so budget and funds must cover the pair
+        let cost = sets * (quotes.up_bid + quotes.down_bid);
+        let funds = engine.funds.lock().await.clone();
+        let check = match engine.check_budget(cost).await {
+            Ok(()) => engine.check_funds(funds.as_deref(), &market, cost, true),
+            Err(reason) => Err(reason),
+        };
+        if let Err(reason) = check {
+            self.pull_quotes(engine, &client, &mut state).await;
+            engine.skip(&market, &key, reason).await;
+            return;
+        }
+
+        let up_size = (sets - state.up.filled).max(MIN_QUOTE_SHARES);
+        let down_size = (sets - state.down.filled).max(MIN_QUOTE_SHARES);
+        self.ensure_bid(engine, &client, &market, &mut state, "UP", quotes.up_bid, up_size).await;
+        self.ensure_bid(engine, &client, &market, &mut state, "DOWN", quotes.down_bid, down_size).await;
+
+        if state.last_quotes != Some(quotes) {
+            state.last_quotes = Some(quotes);
+            engine.emit(EngineEvent::QuotesUpdated {
+                coin: market.coin.clone(),
+                up_bid: quotes.up_bid,
+                down_bid: quotes.down_bid,
+                sets,
+            });
+        }
+    }
+
+    async fn pull_quotes(&self, engine: &ArbEngine, client: &ClobClient, state: &mut MakerState) {
+        for side in ["UP", "DOWN"] {
+            self.retire(engine, client, state, side).await;
+        }
+        state.last_quotes = None;
+    }
+
+    // Count new fills on one leg's resting bid
+    async fn poll_leg(&self, engine: &ArbEngine, client: &ClobClient, state: &mut MakerState, side: &str) {
+        let order_id = match state.leg_mut(side).order {
+            Some(ref order) => order.order_id.clone(),
+            None => return,
+        };
+        match client.get_order(&order_id).await {
+            Ok(status) => {
+                let usdc = self.apply_fill(engine, state, side, status.size_matched, status.live);
+                debit(engine, usdc).await;
+            }
+            Err(e) => log_error(&format!("Order status for {} failed: {}", order_id, e), Some("marketMaker")),
+        }
+    }
+
+    // Book the shares matched since the last read, returns their USDC cost
+    fn apply_fill(&self, engine: &ArbEngine, state: &mut MakerState, side: &str, size_matched: f64, live: bool) -> f64 {
+        let trade_id = state.trade_id();
+        let market = state.market.clone();
+        let leg = state.leg_mut(side);
+        let order = match leg.order.as_mut() {
+            Some(order) => order,
+            None => return 0.0,
+        };
+
+        let new_shares = size_matched - order.matched;
+        let price = order.price;
+        order.matched = size_matched;
+        if !live || order.matched >= order.size - SHARE_EPSILON {
+            leg.order = None;
+        }
+        if new_shares <= SHARE_EPSILON {
+            return 0.0;
+        }
+
+        let usdc = new_shares * price;
+        leg.filled += new_shares;
+        leg.cost += usdc;
+
+        if let Some(market) = market {
+            let mut fill = LedgerEntry::for_market(LedgerKind::Fill, &market);
+            fill.trade_id = Some(trade_id);
+            fill.leg = Some(side.to_string());
+            fill.price = Some(price);
+            fill.size = Some(new_shares);
+            fill.usdc = Some(usdc);
+            fill.success = Some(true);
+            engine.record(fill);
+        }
+        usdc
+    }
+
+    // Cancel a leg's bid, then read it once more so a fill racing the cancel isn't lost
+    async fn retire(&self, engine: &ArbEngine, client: &ClobClient, state: &mut MakerState, side: &str) {
+        let order_id = match state.leg_mut(side).order {
+            Some(ref order) => order.order_id.clone(),
+            None => return,
+        };
+        if let Err(e) = client.cancel_order(&order_id).await {
+            log_error(&format!("Cancel {} failed: {}", order_id, e), Some("marketMaker"));
+        }
+        match client.get_order(&order_id).await {
+            Ok(status) => {
+                let usdc = self.apply_fill(engine, state, side, status.size_matched, false);
+                debit(engine, usdc).await;
+            }
+            Err(_) => state.leg_mut(side).order = None,
+        }
+    }
+
+    // Keep one bid at `price` for `size` on a leg (BTW: small moves are ignored so we keep queue priority)
+    #[allow(clippy::too_many_arguments)]
+    async fn ensure_bid(
+        &self,
+        engine: &ArbEngine,
+        client: &ClobClient,
+        market: &CoinMarket,
+        state: &mut MakerState,
+        side: &str,
+        price: f64,
+        size: f64,
+    ) {
+        if let Some(ref order) = state.leg_mut(side).order {
+            let moved = (order.price - price).abs() >= self.config.requote_ticks * TICK_SIZE - SHARE_EPSILON;
+            let remaining = order.size - order.matched;
+            if !moved && remaining >= size - SHARE_EPSILON {
+                return;
+            }
+        }
+        self.retire(engine, client, state, side).await;
+
+        let size = (size * 100.0).floor() / 100.0;
+        let token_id = token_for(market, side);
+        let posted = match client.create_limit_order(OrderSide::Buy, token_id, size, price).await {
+            Ok(signed_order) => client.post_order(signed_order, OrderType::GTC).await,
+            Err(e) => Err(e),
+        };
+        let (success, error) = match posted {
+            Ok(resp) if resp.success => match resp.order_id {
+                Some(order_id) => {
+                    state.leg_mut(side).order = Some(Resting { order_id, price, size, matched: 0.0 });
+                    (true, None)
+                }
+                None => (false, Some("No order id returned".to_string())),
+            },
+            Ok(resp) => (false, Some(resp.error.unwrap_or_else(|| "Unknown error".to_string()))),
+            Err(e) => (false, Some(format!("Failed to post bid: {}", e))),
+        };
+        if let Some(ref error) = error {
+            log_error(&format!("[{}] {}", side, error), Some("marketMaker"));
+        }
+
+        let mut entry = LedgerEntry::for_market(LedgerKind::Order, market);
+        entry.trade_id = Some(state.trade_id());
+        entry.leg = Some(side.to_string());
+        entry.price = Some(price);
+        entry.size = Some(size);
+        entry.success = Some(success);
+        entry.error = error;
+        engine.record(entry);
+    }
+
+    // Matched UP + DOWN shares become a held set (FYI: the merge/redeem path takes it from there)
+    async fn pair_fills(&self, engine: &ArbEngine, market: &CoinMarket, state: &mut MakerState) {
+        let sets = (state.up.filled.min(state.down.filled) * 100.0).floor() / 100.0;
+        if sets < 0.01 {
+            return;
+        }
+        let up_usdc = sets * state.up.avg_price();
+        let down_usdc = sets * state.down.avg_price();
+        for (leg, usdc) in [(&mut state.up, up_usdc), (&mut state.down, down_usdc)] {
+            leg.filled -= sets;
+            leg.cost -= usdc;
+        }
+
+        let funds = engine.funds.lock().await.clone();
+        if let Some(ref funds) = funds {
+            funds.record_spend(&market.slug, up_usdc + down_usdc);
+        }
+        let mode = {
+            let mut manager = engine.positions.lock().await;
+            manager.record_buy(market, sets, sets, up_usdc + down_usdc);
+            manager.mode()
+        };
+        engine.emit(EngineEvent::TradeResult {
+            coin: market.coin.clone(),
+            kind: "make".to_string(),
+            success: true,
+            up_usdc,
+            down_usdc,
+            error: None,
+        });
+
+        // Flat again, the next fills start a new attempt
+        if state.up.filled < 0.01 && state.down.filled < 0.01 {
+            state.seq += 1;
+        }
+        if mode == SettlementMode::Merge {
+            engine.settle_positions().await;
+        }
+    }
+
+    // One leg filled: lift or improve the other, or unwind once there's no time left to wait
+    async fn hedge(&self, engine: &ArbEngine, client: &ClobClient, view: &MarketView, state: &mut MakerState, side: &str, past_cutoff: bool) {
+        let market = view.market.clone();
+        let other = if side == "UP" { "DOWN" } else { "UP" };
+        let other_book = if other == "UP" { &view.up_book } else { &view.down_book };
+        self.retire(engine, client, state, side).await;
+        state.last_quotes = None;
+
+        let filled_price = state.leg_mut(side).avg_price();
+        let can_lift = state.lift_retry_at.is_none_or(|t| Instant::now() >= t);
+        match plan_hedge(filled_price, other_book, self.config.hedge_max_sum) {
+            Some(Hedge::Lift { price }) if can_lift => {
+                // Our own bid could fill at the same time, pull it first and re-measure
+                self.retire(engine, client, state, other).await;
+                let needed = state.leg_mut(side).filled - state.leg_mut(other).filled;
+                if needed < MIN_QUOTE_SHARES {
+                    self.pair_fills(engine, &market, state).await;
+                    return;
+                }
+
+                let result = execute_buy_order(client, token_for(&market, other), other, needed * price, price).await;
+                let trade_id = state.trade_id();
+                engine.record_legs(&market, &trade_id, &[(&result, price)]);
+                if result.success {
+                    let leg = state.leg_mut(other);
+                    leg.filled += result.tokens_bought.unwrap_or(0.0);
+                    leg.cost += result.amount;
+                    debit(engine, result.amount).await;
+                    self.pair_fills(engine, &market, state).await;
+                } else {
+                    state.lift_retry_at = Some(Instant::now() + LIFT_RETRY);
+                    engine.emit(EngineEvent::TradeResult {
+                        coin: market.coin.clone(),
+                        kind: "hedge".to_string(),
+                        success: false,
+                        up_usdc: if side == "UP" { state.up.cost } else { 0.0 },
+                        down_usdc: if side == "DOWN" { state.down.cost } else { 0.0 },
+                        error: result.error,
+                    });
+                }
+            }
+            _ if past_cutoff => {
+                // No time left for a passive hedge, sell the lone leg back
+                self.retire(engine, client, state, other).await;
+                let leg = state.leg_mut(side).clone();
+                let filled = ArbitrageOrderResult {
+                    success: true,
+                    token_id: token_for(&market, side).to_string(),
+                    side: side.to_string(),
+                    amount: leg.cost,
+                    price: leg.avg_price(),
+                    tokens_bought: Some(leg.filled),
+                    tokens_sold: None,
+                    error: None,
+                };
+                let bid = if side == "UP" { view.price_data.up_bid } else { view.price_data.down_bid };
+                let trade_id = state.trade_id();
+                engine.unwind(client, &market, &trade_id, &filled, bid).await;
+                *state.leg_mut(side) = Leg::default();
+                state.seq += 1;
+            }
+            Some(Hedge::Improve { price }) => {
+                let needed = (state.leg_mut(side).filled - state.leg_mut(other).filled).max(MIN_QUOTE_SHARES);
+                self.ensure_bid(engine, client, &market, state, other, price, needed).await;
+            }
+            // Waiting out a failed lift
+            Some(Hedge::Lift { .. }) => {}
+            None => {
+                let reason = format!("No {} price keeps the set under {:.2}", other, self.config.hedge_max_sum);
+                engine.skip(&market, &format!("hedge_{}", side), reason).await;
+            }
+        }
+    }
+}
+
+// Filled bids come out of the cached balance right away (FYI: same as taker legs)
+async fn debit(engine: &ArbEngine, usdc: f64) {
+    if usdc <= 0.0 {
+        return;
+    }
+    if let Some(ref funds) = *engine.funds.lock().await {
+        funds.debit(usdc, true);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::services::engine::{ArbEngine, EngineEvent};
+use crate::utils::logger::log_error;
+use anyhow::Result;
+use prometheus::{
+    Encoder, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+// Latency buckets in seconds (FYI: 1ms .. 10s, order round trips sit in the 50-500ms range)
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(metric: T) -> T {
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("metric registered twice");
+    metric
+}
+
+// Process-wide metrics (BTW: global like the logger, so the WS client and executor don't need an engine handle)
+lazy_static::lazy_static! {
+    static ref REGISTRY: Registry = Registry::new();
+    static ref BOOK_SEEN: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new()); // Last local book update per asset
+
+    pub static ref WS_MESSAGES: IntCounter = register(
+        IntCounter::new("arb_ws_messages_total", "WebSocket text frames received (heartbeats excluded)").unwrap()
+    );
+    static ref BOOK_AGE: GaugeVec = register(
+        GaugeVec::new(Opts::new("arb_book_age_seconds", "Seconds since the last book update per subscribed asset"), &["asset"]).unwrap()
+    );
+    static ref DETECTIONS: IntCounterVec = register(
+        IntCounterVec::new(Opts::new("arb_detections_total", "Arbitrage detections, one per market and price pair"), &["coin", "side"]).unwrap()
+    );
+    static ref DETECTION_TO_ORDER: Histogram = register(
+        Histogram::with_opts(
+            HistogramOpts::new("arb_detection_to_order_seconds", "Book update received to orders sent")
+                .buckets(LATENCY_BUCKETS.to_vec())
+        )
+        .unwrap()
+    );
+    static ref ORDER_ROUNDTRIP: HistogramVec = register(
+        HistogramVec::new(
+            HistogramOpts::new("arb_order_roundtrip_seconds", "Order create + post until the CLOB answered")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["side"]
+        )
+        .unwrap()
+    );
+    static ref ORDERS: IntCounterVec = register(
+        IntCounterVec::new(Opts::new("arb_orders_total", "Orders sent, by outcome"), &["result"]).unwrap()
+    );
+    static ref FILL_RATIO: Gauge = register(
+        Gauge::new("arb_fill_ratio", "Filled orders / orders sent since start").unwrap()
+    );
+    static ref LEG_FAILURES: IntCounterVec = register(
+        IntCounterVec::new(Opts::new("arb_leg_failures_total", "Trades where only one leg filled"), &["coin"]).unwrap()
+    );
+    static ref EXPOSURE: GaugeVec = register(
+        GaugeVec::new(Opts::new("arb_exposure_usdc", "USDC at risk: held sets and unhedged legs"), &["kind"]).unwrap()
+    );
+}
+
+// Book update for an asset (FYI: local receive time, not the exchange timestamp)
+pub fn record_book(asset_id: &str) {
+    BOOK_SEEN.lock().unwrap().insert(asset_id.to_string(), Instant::now());
+}
+
+// Unsubscribed assets drop out of the staleness gauge
+pub fn forget_books(asset_ids: &[String]) {
+    let mut seen = BOOK_SEEN.lock().unwrap();
+    for asset_id in asset_ids {
+        seen.remove(asset_id);
+    }
+}
+
+pub fn observe_detection_to_order(elapsed: Duration) {
+    DETECTION_TO_ORDER.observe(elapsed.as_secs_f64());
+}
+
+pub fn observe_order_roundtrip(side: &str, elapsed: Duration) {
+    ORDER_ROUNDTRIP.with_label_values(&[side]).observe(elapsed.as_secs_f64());
+}
+
+pub fn record_order(filled: bool) {
+    ORDERS.with_label_values(&[if filled { "filled" } else { "failed" }]).inc();
+}
+
+pub fn set_exposure(held_sets_usdc: f64, unhedged_usdc: f64) {
+    EXPOSURE.with_label_values(&["held_sets"]).set(held_sets_usdc);
+    EXPOSURE.with_label_values(&["unhedged"]).set(unhedged_usdc);
+}
+
+// Counters that fall straight out of engine events (BTW: called from ArbEngine::emit)
+pub fn observe_event(event: &EngineEvent) {
+    match event {
+        // Once per market and price pair, like the ledger (FYI: detection events repeat on every book tick)
+        EngineEvent::ArbitrageDetected { coin, new_prices: true, .. } => DETECTIONS.with_label_values(&[coin, "buy"]).inc(),
+        EngineEvent::SellArbitrageDetected { coin, new_prices: true, .. } => DETECTIONS.with_label_values(&[coin, "sell"]).inc(),
+        EngineEvent::TradeResult { coin, kind, success: false, up_usdc, down_usdc, .. }
+            if kind != "unwind" && ((*up_usdc > 0.0) != (*down_usdc > 0.0)) =>
+        {
+            LEG_FAILURES.with_label_values(&[coin]).inc();
+        }
+        _ => {}
+    }
+}
+
+// Text exposition format (FYI: point-in-time gauges are refreshed here, right before encoding)
+pub fn render() -> Result<String> {
+    BOOK_AGE.reset();
+    for (asset_id, seen) in BOOK_SEEN.lock().unwrap().iter() {
+        BOOK_AGE.with_label_values(&[asset_id]).set(seen.elapsed().as_secs_f64());
+    }
+
+    let filled = ORDERS.with_label_values(&["filled"]).get();
+    let failed = ORDERS.with_label_values(&["failed"]).get();
+    if filled + failed > 0 {
+        FILL_RATIO.set(filled as f64 / (filled + failed) as f64);
+    }
+
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+// Minimal HTTP server for GET /metrics (IMO: a scrape endpoint doesn't justify a web framework)
+pub async fn serve_metrics(engine: Arc<ArbEngine>, addr: String) -> Result<()> {
+    let listener = TcpListener::bind(&addr).await?;
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        let engine = engine.clone();
+        tokio::spawn(async move {
+            let mut request = [0u8; 1024];
+            let read = match socket.read(&mut request).await {
+                Ok(read) => read,
+                Err(_) => return,
+            };
+            let request_line = String::from_utf8_lossy(&request[..read]);
+            let path = request_line.split_whitespace().nth(1).unwrap_or("");
+
+            let (status, body) = if path == "/metrics" {
+                engine.refresh_metrics().await;
+                match render() {
+                    Ok(body) => ("200 OK", body),
+                    Err(e) => {
+                        log_error(&format!("Metrics encoding failed: {}", e), Some("metrics"));
+                        ("500 Internal Server Error", String::new())
+                    }
+                }
+            } else {
+                ("404 Not Found", "Not found, try /metrics\n".to_string())
+            };
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+    }
+}
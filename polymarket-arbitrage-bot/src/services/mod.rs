@@ -1,12 +1,40 @@
+pub mod alerts;
 pub mod arbitrage_executor;
 pub mod create_clob_client;
+pub mod ctf;
+pub mod engine;
+pub mod event_scanner;
+pub mod expiry_policy;
+pub mod funds_guard;
+pub mod ledger;
 pub mod market_discovery;
+pub mod market_maker;
+pub mod metrics;
+pub mod position_manager;
 pub mod price_monitor;
+pub mod profitability;
+pub mod recorder;
+pub mod replay;
+pub mod report;
 pub mod websocket_client;
 
+pub use alerts::*;
 pub use arbitrage_executor::*;
 pub use create_clob_client::*;
+pub use ctf::*;
+pub use engine::*;
+pub use event_scanner::*;
+pub use expiry_policy::*;
+pub use funds_guard::*;
+pub use ledger::*;
 pub use market_discovery::*;
+pub use market_maker::*;
+pub use metrics::*;
+pub use position_manager::*;
 pub use price_monitor::*;
+pub use profitability::*;
+pub use recorder::*;
+pub use replay::*;
+pub use report::*;
 pub use websocket_client::*;
 
@@ -0,0 +1,295 @@
+use crate::services::ctf::CtfClient;
+use crate::services::market_discovery::CoinMarket;
+use crate::utils::logger::log_error;
+use colored::*;
+use std::collections::HashSet;
+
+// Smallest set size worth an on-chain tx (FYI: below this gas always wins)
+const MIN_SETTLE_SETS: f64 = 0.01;
+// One token unit (BTW: anything below this left on a row is float noise, not tokens)
+const SETS_EPSILON: f64 = 0.000001;
+
+// How held complete sets turn back into USDC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettlementMode {
+    Merge,  // mergePositions right after the buy
+    Redeem, // redeemPositions once the market resolves
+    Off,    // Hold tokens, settle manually
+}
+
+impl SettlementMode {
+    // Unknown values fall back to Off (IMO: a typo must never send on-chain txs) and the problem is returned for the caller to log
+    pub fn parse(value: &str) -> (Self, Option<String>) {
+        match value.to_lowercase().as_str() {
+            "merge" => (SettlementMode::Merge, None),
+            "redeem" => (SettlementMode::Redeem, None),
+            "off" | "none" => (SettlementMode::Off, None),
+            _ => (
+                SettlementMode::Off,
+                Some(format!(
+                    "Unknown POSITION_SETTLEMENT '{}' (expected merge, redeem or off), settlement is off",
+                    value
+                )),
+            ),
+        }
+    }
+}
+
+// Complete sets bought by execute_arbitrage_trade and not yet settled
+#[derive(Debug, Clone)]
+pub struct HeldSet {
+    pub coin: String,
+    pub market_slug: String,
+    pub window_end: String, // Market end_date (FYI: ledger rows are keyed by slug + window)
+    pub condition_id: String,
+    pub up_token_id: String,
+    pub down_token_id: String,
+    pub sets: f64,
+    pub cost_usdc: f64,
+}
+
+// Tracks held sets and the USDC budget they draw from (IMO: recovered collateral must be spendable again)
+pub struct PositionManager {
+    mode: SettlementMode,
+    available_budget: f64,
+    positions: Vec<HeldSet>,
+    settling: HashSet<String>, // Condition IDs with a merge/redeem in flight (IMO: never send two for the same sets)
+    realized_pnl: f64, // Settled + split-and-sell profit, in USDC
+    exhausted: bool, // A buy was refused since the budget last grew (BTW: so running out is reported once)
+}
+
+impl PositionManager {
+    pub fn new(budget_usdc: f64, mode: SettlementMode) -> Self {
+        Self {
+            mode,
+            available_budget: budget_usdc,
+            positions: Vec::new(),
+            settling: HashSet::new(),
+            realized_pnl: 0.0,
+            exhausted: false,
+        }
+    }
+
+    pub fn mode(&self) -> SettlementMode {
+        self.mode
+    }
+
+    // Engine turns settlement off when no CTF client can be built
+    pub fn set_mode(&mut self, mode: SettlementMode) {
+        self.mode = mode;
+    }
+
+    pub fn available_budget(&self) -> f64 {
+        self.available_budget
+    }
+
+    pub fn positions(&self) -> &[HeldSet] {
+        &self.positions
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        self.realized_pnl
+    }
+
+    // USDC still locked in unsettled sets
+    pub fn deployed_usdc(&self) -> f64 {
+        self.positions.iter().map(|p| p.cost_usdc).sum()
+    }
+
+    // Profit from trades that never become a held set (BTW: e.g. split-and-sell)
+    pub fn record_realized(&mut self, pnl_usdc: f64) {
+        self.realized_pnl += pnl_usdc;
+    }
+
+    pub fn can_afford(&self, cost_usdc: f64) -> bool {
+        cost_usdc <= self.available_budget
+    }
+
+    // True for the first refused buy since the budget last grew
+    pub fn note_exhausted(&mut self) -> bool {
+        !std::mem::replace(&mut self.exhausted, true)
+    }
+
+    // Record a completed two-leg buy (FYI: only the matched quantity is a complete set)
+    // With settlement off nothing ever credits the budget back, so it isn't debited (IMO: it'd only shrink until trading stops)
+    pub fn record_buy(&mut self, market: &CoinMarket, up_tokens: f64, down_tokens: f64, cost_usdc: f64) {
+        if self.mode != SettlementMode::Off {
+            self.available_budget -= cost_usdc;
+        }
+
+        let sets = (up_tokens.min(down_tokens) * 100.0).round() / 100.0;
+        if sets < MIN_SETTLE_SETS {
+            return;
+        }
+
+        // Same market twice just grows the position (AFAIK: one merge tx per condition)
+        if let Some(held) = self
+            .positions
+            .iter_mut()
+            .find(|p| p.condition_id == market.condition_id)
+        {
+            held.sets += sets;
+            held.cost_usdc += cost_usdc;
+            return;
+        }
+
+        self.positions.push(HeldSet {
+            coin: market.coin.clone(),
+            market_slug: market.slug.clone(),
+            window_end: market.end_date.clone(),
+            condition_id: market.condition_id.clone(),
+            up_token_id: market.up_token_id.clone(),
+            down_token_id: market.down_token_id.clone(),
+            sets,
+            cost_usdc,
+        });
+    }
+
+    // Positions to act on this round, marked in flight until release() (BTW: cloned so the lock isn't held across tx waits)
+    // A condition another caller is already settling is left out, the post-buy call and the timer can overlap
+    pub fn claim_pending(&mut self) -> Vec<HeldSet> {
+        let pending: Vec<HeldSet> = self
+            .positions
+            .iter()
+            .filter(|p| !self.settling.contains(&p.condition_id))
+            .cloned()
+            .collect();
+        for held in &pending {
+            self.settling.insert(held.condition_id.clone());
+        }
+        pending
+    }
+
+    // Done with a claimed condition, settled or not
+    pub fn release(&mut self, condition_id: &str) {
+        self.settling.remove(condition_id);
+    }
+
+    // Book `sets_settled` sets of a position and return their collateral to the budget
+    // Cost comes off pro rata, the row only goes once no sets are left (FYI: partial merges keep the rest held)
+    // Returns the realized PnL of the settlement
+    pub fn mark_settled(&mut self, condition_id: &str, sets_settled: f64, recovered_usdc: f64) -> f64 {
+        let Some(index) = self.positions.iter().position(|p| p.condition_id == condition_id) else {
+            return 0.0;
+        };
+        let held = &mut self.positions[index];
+        let fraction = if held.sets > 0.0 { (sets_settled / held.sets).min(1.0) } else { 1.0 };
+        let cost = held.cost_usdc * fraction;
+        held.sets -= sets_settled.min(held.sets);
+        held.cost_usdc -= cost;
+        if held.sets < SETS_EPSILON {
+            self.positions.remove(index);
+        }
+
+        let pnl = recovered_usdc - cost;
+        self.realized_pnl += pnl;
+        self.available_budget += recovered_usdc;
+        self.exhausted = false;
+        pnl
+    }
+}
+
+// One merge or redeem that went through
+#[derive(Debug, Clone)]
+pub struct Settlement {
+    pub held: HeldSet,
+    pub mode: SettlementMode,
+    pub sets: f64, // Sets taken off the position (BTW: may be less than held.sets after a partial merge)
+    pub recovered_usdc: f64,
+    pub pnl_usdc: f64, // Recovered minus what the set cost
+    pub tx_hash: String,
+}
+
+// Merge or redeem one held set (FYI: caps at on-chain balance since FAK fills can be partial)
+// Balances are read from the funder, which is where CtfClient sends every merge and redeem from
+// Returns sets settled, recovered USDC and the tx hash, or None if nothing was settled yet
+pub async fn settle_position(ctf: &CtfClient, held: &HeldSet, mode: SettlementMode) -> Option<(f64, f64, String)> {
+    if mode == SettlementMode::Off {
+        return None;
+    }
+    let up_balance = ctf.token_balance(&held.up_token_id).await;
+    let down_balance = ctf.token_balance(&held.down_token_id).await;
+    let complete = match (up_balance, down_balance) {
+        (Ok(up), Ok(down)) => held.sets.min(up).min(down),
+        (Err(e), _) | (_, Err(e)) => {
+            log_error(&format!("Balance check failed for {}: {}", held.market_slug, e), Some("settlePosition"));
+            return None;
+        }
+    };
+    if complete < MIN_SETTLE_SETS {
+        return None;
+    }
+
+    let result = match mode {
+        SettlementMode::Off => return None,
+        SettlementMode::Merge => ctf
+            .merge_positions(&held.condition_id, complete)
+            .await
+            .map(|tx| (tx, complete, complete)),
+        SettlementMode::Redeem => {
+            match ctf.is_resolved(&held.condition_id).await {
+                Ok(true) => {}
+                Ok(false) => return None, // Not resolved yet, try again next round
+                Err(e) => {
+                    log_error(&format!("Resolution check failed for {}: {}", held.market_slug, e), Some("settlePosition"));
+                    return None;
+                }
+            }
+            // One side pays 1.0 per token, so a complete set always redeems for 1 USDC
+            // Redeeming burns every token of the condition, so the whole position is done
+            ctf.redeem_positions(&held.condition_id)
+                .await
+                .map(|tx| (tx, held.sets, complete))
+        }
+    };
+
+    match result {
+        Ok((tx_hash, sets, recovered)) => {
+            crate::console_println!(
+                "{}",
+                format!(
+                    "\n💱 {} {:.2} sets for {} → ${:.2} USDC back in budget (tx: {:?})\n",
+                    if mode == SettlementMode::Merge { "Merged" } else { "Redeemed" },
+                    sets,
+                    held.market_slug,
+                    recovered,
+                    tx_hash
+                )
+                .green()
+            );
+            Some((sets, recovered, format!("{:?}", tx_hash)))
+        }
+        Err(e) => {
+            log_error(&format!("Settlement failed for {}: {}", held.market_slug, e), Some("settlePosition"));
+            None
+        }
+    }
+}
+
+// Settle everything currently held (IMO: called right after a buy in merge mode, and on a timer)
+// Returns each settled position with the USDC it recovered
+pub async fn settle_all(manager: &tokio::sync::Mutex<PositionManager>, ctf: &CtfClient) -> Vec<Settlement> {
+    let (pending, mode) = {
+        let mut guard = manager.lock().await;
+        (guard.claim_pending(), guard.mode())
+    };
+
+    let mut settled = Vec::new();
+    for held in pending {
+        let result = settle_position(ctf, &held, mode).await;
+        let mut guard = manager.lock().await;
+        if let Some((sets, recovered, tx_hash)) = result {
+            let pnl_usdc = guard.mark_settled(&held.condition_id, sets, recovered);
+            settled.push(Settlement {
+                held: held.clone(),
+                mode,
+                sets,
+                recovered_usdc: recovered,
+                pnl_usdc,
+                tx_hash,
+            });
+        }
+        guard.release(&held.condition_id);
+    }
+    settled
+}
@@ -1,7 +1,7 @@
 use crate::config::Env;
 use crate::services::market_discovery::CoinMarket;
 use crate::services::websocket_client::OrderbookSnapshot;
-use crate::utils::logger::{log_monitor_data, MonitorData};
+use crate::utils::logger::{format_local, log_monitor_data, monitor_log_path, MonitorData};
 use chrono::{DateTime, Utc};
 use colored::*;
 use std::collections::HashMap;
@@ -72,6 +72,18 @@ pub fn create_price_data(
     }
 }
 
+impl PriceData {
+    // Re-derive spread/has_arbitrage for a threshold changed at runtime (FYI: dashboard controls)
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.spread = threshold - self.ask_sum;
+        self.has_arbitrage = self.ask_sum < threshold && self.spread > 0.0;
+        self
+    }
+}
+
+// Updates kept per coin (BTW: enough for a dashboard sparkline)
+const PRICE_HISTORY_LIMIT: usize = 200;
+
 pub struct PriceMonitor {
     price_history: HashMap<String, Vec<PriceData>>,
     arbitrage_history: HashMap<String, Vec<ArbitrageDetection>>,
@@ -111,7 +123,7 @@ impl PriceMonitor {
     pub fn add_to_history(&mut self, coin: &str, price_data: PriceData, env: &Env) {
         let history = self.price_history.entry(coin.to_string()).or_insert_with(Vec::new);
         history.push(price_data.clone());
-        if history.len() > 10 {
+        if history.len() > PRICE_HISTORY_LIMIT {
             history.remove(0);
         }
 
@@ -134,10 +146,8 @@ impl PriceMonitor {
 }
 
 fn format_timestamp(timestamp: i64) -> String {
-    let dt = DateTime::from_timestamp_millis(timestamp).unwrap_or_else(|| Utc::now());
-    let est_offset = chrono::Duration::hours(-5);
-    let est_time = dt + est_offset;
-    est_time.format("%H:%M:%S EST").to_string()
+    let dt = DateTime::from_timestamp_millis(timestamp).unwrap_or_else(Utc::now);
+    format_local(dt, "%H:%M:%S %Z")
 }
 
 pub fn display_coin_details(
@@ -154,38 +164,40 @@ pub fn display_coin_details(
     println!("{}", format!("Market: {}", market.slug).bright_black());
 
     // End date and countdown
-    let end_date = DateTime::parse_from_rfc3339(&market.end_date)
-        .unwrap_or_else(|_| Utc::now().into())
-        .with_timezone(&Utc);
-    let now = Utc::now();
-    let time_until_end = (end_date - now).num_milliseconds();
-    let mins = time_until_end / 60000;
-    let secs = (time_until_end % 60000) / 1000;
-
-    let end_date_str = end_date.format("%m/%d/%Y %I:%M:%S %p").to_string();
-
-    if time_until_end <= 0 {
-        println!("{}", format!("Ends: {} (MARKET CLOSED)", end_date_str).red().bold());
-    } else if time_until_end < 60000 {
-        println!(
-            "{}",
-            format!("Ends: {} ({}s remaining - CLOSING SOON!)", end_date_str, secs)
-                .yellow()
-                .bold()
-        );
-    } else if time_until_end < 300000 {
-        println!(
-            "{}",
-            format!("Ends: {} ({}m {}s remaining)", end_date_str, mins, secs).yellow()
-        );
-    } else {
-        println!(
-            "{}",
-            format!("Ends: {} ({}m {}s remaining)", end_date_str, mins, secs).bright_black()
-        );
+    match crate::services::market_discovery::market_end(market) {
+        Some(end_date) => {
+            let now = Utc::now();
+            let time_until_end = (end_date - now).num_milliseconds();
+            let mins = time_until_end / 60000;
+            let secs = (time_until_end % 60000) / 1000;
+
+            let end_date_str = end_date.format("%m/%d/%Y %I:%M:%S %p").to_string();
+
+            if time_until_end <= 0 {
+                println!("{}", format!("Ends: {} (MARKET CLOSED)", end_date_str).red().bold());
+            } else if time_until_end < 60000 {
+                println!(
+                    "{}",
+                    format!("Ends: {} ({}s remaining - CLOSING SOON!)", end_date_str, secs)
+                        .yellow()
+                        .bold()
+                );
+            } else if time_until_end < 300000 {
+                println!(
+                    "{}",
+                    format!("Ends: {} ({}m {}s remaining)", end_date_str, mins, secs).yellow()
+                );
+            } else {
+                println!(
+                    "{}",
+                    format!("Ends: {} ({}m {}s remaining)", end_date_str, mins, secs).bright_black()
+                );
+            }
+        }
+        None => println!("{}", format!("Ends: unknown (unparseable end date '{}')", market.end_date).yellow()),
     }
     println!();
-    println!("{}", "All price data is being logged to monitor.log".bright_black());
+    println!("{}", format!("All price data is being logged to {}", monitor_log_path().display()).bright_black());
     println!();
 
     // Display recent arbitrage detections
@@ -0,0 +1,122 @@
+use crate::config::Env;
+use crate::services::arbitrage_executor::{calculate_trade_amounts, plan_buy_leg};
+use crate::services::websocket_client::OrderbookSnapshot;
+use anyhow::Result;
+
+// Gas units for one mergePositions / redeemPositions tx (AFAIK: ~120-150k on Polygon, rounded up)
+const SETTLEMENT_GAS_UNITS: f64 = 150_000.0;
+
+// Expected economics of buying one UP+DOWN basket at current asks
+#[derive(Debug, Clone)]
+pub struct ProfitEstimate {
+    pub sets: f64, // Complete sets we'd end up holding after rounding
+    pub cost_usdc: f64, // USDC spent on both legs
+    pub fees_usdc: f64, // Taker fees on both legs
+    pub gas_usdc: f64, // Merge/redeem gas for the basket
+    pub gross_edge_per_set: f64, // 1 - ask_sum, before costs
+    pub net_edge_per_set: f64, // What a set actually earns after everything
+    pub net_edge_bps: f64,
+    pub reason: Option<String>, // Why the basket can't be traded at all
+}
+
+impl ProfitEstimate {
+    fn rejected(reason: String) -> Self {
+        Self {
+            sets: 0.0,
+            cost_usdc: 0.0,
+            fees_usdc: 0.0,
+            gas_usdc: 0.0,
+            gross_edge_per_set: 0.0,
+            net_edge_per_set: 0.0,
+            net_edge_bps: f64::NEG_INFINITY,
+            reason: Some(reason),
+        }
+    }
+
+    // Only trigger when net edge clears the configured minimum (FYI: MIN_NET_EDGE_BPS)
+    pub fn is_tradeable(&self, min_net_edge_bps: f64) -> bool {
+        self.reason.is_none() && self.net_edge_bps >= min_net_edge_bps
+    }
+}
+
+// Polymarket taker fee (AFAIK: fee_rate * min(p, 1 - p) * shares, symmetric around 0.5)
+pub fn taker_fee_usdc(fee_rate_bps: f64, price: f64, shares: f64) -> f64 {
+    (fee_rate_bps / 10_000.0) * price.min(1.0 - price) * shares
+}
+
+// USD cost of one settlement tx at the configured gas price
+pub fn settlement_gas_usdc(env: &Env) -> f64 {
+    SETTLEMENT_GAS_UNITS * env.gas_price_gwei * 1e-9 * env.pol_usd_price
+}
+
+// Net edge per set for the two-leg buy as execute_arbitrage_trade would place it
+// (IMO: reuse the executor's own sizing so rounding here matches what actually gets sent)
+pub fn evaluate_buy(
+    up: &OrderbookSnapshot,
+    down: &OrderbookSnapshot,
+    fee_rate_bps: f64,
+    token_amount: f64, // Tokens per leg, as the executor would size it
+    env: &Env,
+) -> ProfitEstimate {
+    let (up_level, down_level) = match (up.asks.first(), down.asks.first()) {
+        (Some(u), Some(d)) => (u, d),
+        _ => return ProfitEstimate::rejected("Missing ask on one leg".to_string()),
+    };
+
+    let (_, up_amount_usdc, down_amount_usdc) = calculate_trade_amounts(token_amount, up_level.price, down_level.price);
+    let (up_price, _, up_usdc) = match plan_buy_leg(up_amount_usdc, up_level.price) {
+        Ok(plan) => plan,
+        Err(e) => return ProfitEstimate::rejected(format!("UP leg: {}", e)),
+    };
+    let (down_price, _, down_usdc) = match plan_buy_leg(down_amount_usdc, down_level.price) {
+        Ok(plan) => plan,
+        Err(e) => return ProfitEstimate::rejected(format!("DOWN leg: {}", e)),
+    };
+
+    // Tokens each leg actually buys (BTW: same formula the executor reports as tokens_bought)
+    let up_tokens = up_usdc / up_price;
+    let down_tokens = down_usdc / down_price;
+
+    // FAK only takes what's on the best level (FYI: anything beyond it would be a worse price)
+    if up_tokens > up_level.size || down_tokens > down_level.size {
+        return ProfitEstimate::rejected(format!(
+            "Not enough depth at best ask: UP {:.2}/{:.2}, DOWN {:.2}/{:.2}",
+            up_tokens, up_level.size, down_tokens, down_level.size
+        ));
+    }
+
+    // Only matched quantity is a complete set; the excess leg is unhedged and valued at zero
+    let sets = up_tokens.min(down_tokens);
+    let cost_usdc = up_usdc + down_usdc;
+    let fees_usdc = taker_fee_usdc(fee_rate_bps, up_price, up_tokens) + taker_fee_usdc(fee_rate_bps, down_price, down_tokens);
+    let gas_usdc = settlement_gas_usdc(env);
+
+    let net_edge_per_set = (sets - cost_usdc - fees_usdc - gas_usdc) / sets;
+    ProfitEstimate {
+        sets,
+        cost_usdc,
+        fees_usdc,
+        gas_usdc,
+        gross_edge_per_set: 1.0 - (up_level.price + down_level.price),
+        net_edge_per_set,
+        net_edge_bps: net_edge_per_set * 10_000.0,
+        reason: None,
+    }
+}
+
+// Taker fee rate for a token from the CLOB (FYI: falls back to FEE_RATE_BPS if the call fails)
+pub async fn fetch_fee_rate_bps(env: &Env, token_id: &str) -> Result<f64> {
+    let url = format!("{}/fee-rate?token_id={}", env.clob_http_url.trim_end_matches('/'), token_id);
+    let client = reqwest::Client::new();
+    let response = client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await?;
+    let body: serde_json::Value = response.json().await?;
+    Ok(body
+        .get("base_fee")
+        .or_else(|| body.get("fee_rate_bps"))
+        .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+        .unwrap_or(env.fee_rate_bps))
+}
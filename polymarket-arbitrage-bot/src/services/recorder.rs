@@ -0,0 +1,149 @@
+use crate::services::market_discovery::CoinMarket;
+use crate::utils::logger::log_error;
+use anyhow::Result;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+pub const RECORDING_PREFIX: &str = "ws-";
+pub const RECORDING_SUFFIX: &str = ".jsonl.gz";
+
+// One line of a recording (FYI: markets are logged so replay knows which tokens pair up)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    Message { ts: i64, raw: String }, // Raw WS text frame, as received
+    Market { ts: i64, market: CoinMarket }, // Market we subscribed to
+}
+
+// Cheap handle for recording raw WS traffic (BTW: all file I/O happens on a writer thread)
+#[derive(Clone)]
+pub struct MessageRecorder {
+    tx: Sender<RecordedEvent>,
+}
+
+impl MessageRecorder {
+    // Start the writer thread (IMO: sync gzip I/O doesn't belong on the tokio runtime)
+    pub fn start(dir: PathBuf, rotate_bytes: u64, max_files: usize) -> Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let (tx, rx) = channel();
+        std::thread::Builder::new()
+            .name("ws-recorder".to_string())
+            .spawn(move || writer_loop(rx, dir, rotate_bytes, max_files))?;
+        Ok(Self { tx })
+    }
+
+    pub fn record_message(&self, raw: &str) {
+        let _ = self.tx.send(RecordedEvent::Message {
+            ts: chrono::Utc::now().timestamp_millis(),
+            raw: raw.to_string(),
+        });
+    }
+
+    pub fn record_market(&self, market: &CoinMarket) {
+        let _ = self.tx.send(RecordedEvent::Market {
+            ts: chrono::Utc::now().timestamp_millis(),
+            market: market.clone(),
+        });
+    }
+}
+
+fn open_recording(dir: &Path) -> Result<GzEncoder<File>> {
+    let name = format!(
+        "{}{}{}",
+        RECORDING_PREFIX,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S%.3f"),
+        RECORDING_SUFFIX
+    );
+    let file = File::create(dir.join(name))?;
+    Ok(GzEncoder::new(file, Compression::fast()))
+}
+
+// Recording files in chronological order (AFAIK: timestamped names sort correctly)
+pub fn list_recordings(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(RECORDING_PREFIX) && n.ends_with(RECORDING_SUFFIX))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// Keep only the newest `max_files` recordings
+fn prune_recordings(dir: &Path, max_files: usize) {
+    if let Ok(files) = list_recordings(dir) {
+        if files.len() > max_files {
+            for old in &files[..files.len() - max_files] {
+                let _ = std::fs::remove_file(old);
+            }
+        }
+    }
+}
+
+// Writer thread: append JSON lines, rotate on size (FYI: size is counted on uncompressed bytes)
+fn writer_loop(rx: Receiver<RecordedEvent>, dir: PathBuf, rotate_bytes: u64, max_files: usize) {
+    let mut encoder: Option<GzEncoder<File>> = None;
+    let mut written: u64 = 0;
+    let mut markets: Vec<RecordedEvent> = Vec::new(); // Re-emitted at the top of each new file
+
+    while let Ok(event) = rx.recv() {
+        if let RecordedEvent::Market { ref market, .. } = event {
+            markets.retain(|m| !matches!(m, RecordedEvent::Market { market: old, .. } if old.slug == market.slug));
+            markets.push(event.clone());
+        }
+
+        if encoder.is_none() {
+            match open_recording(&dir) {
+                Ok(enc) => {
+                    encoder = Some(enc);
+                    written = 0;
+                    prune_recordings(&dir, max_files);
+                    // Each file must replay on its own (BTW: so carry current markets forward)
+                    for market in &markets {
+                        if let (Some(enc), Ok(line)) = (encoder.as_mut(), serde_json::to_string(market)) {
+                            let _ = writeln!(enc, "{}", line);
+                        }
+                    }
+                    if matches!(event, RecordedEvent::Market { .. }) {
+                        continue; // Already written above
+                    }
+                }
+                Err(e) => {
+                    log_error(&format!("Failed to open recording file: {}", e), Some("recorder"));
+                    continue;
+                }
+            }
+        }
+
+        let line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(_) => continue,
+        };
+        if let Some(enc) = encoder.as_mut() {
+            if writeln!(enc, "{}", line).is_err() {
+                log_error("Failed to write recording line", Some("recorder"));
+            }
+            written += line.len() as u64 + 1;
+        }
+
+        if written >= rotate_bytes {
+            if let Some(enc) = encoder.take() {
+                let _ = enc.finish();
+            }
+        }
+    }
+
+    // Channel closed: flush the gzip trailer so the last file is readable
+    if let Some(enc) = encoder.take() {
+        let _ = enc.finish();
+    }
+}
@@ -0,0 +1,290 @@
+use crate::config::Env;
+use crate::services::market_discovery::CoinMarket;
+use crate::services::price_monitor::create_price_data;
+use crate::services::profitability::evaluate_buy;
+use crate::services::recorder::{list_recordings, RecordedEvent};
+use crate::services::websocket_client::{MarketWebSocket, OrderbookLevel, OrderbookSnapshot};
+use anyhow::{anyhow, Result};
+use colored::*;
+use flate2::read::MultiGzDecoder;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+// Knobs we want to evaluate offline (FYI: defaults come from the live Env)
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    pub path: PathBuf, // Recording file or directory of recordings
+    pub threshold: f64, // Ask sum below this is an opportunity
+    pub token_amount: f64, // Target sets per trade
+    pub latency_ms: i64, // Simulated detection-to-fill delay
+}
+
+impl ReplayConfig {
+    // Parse `replay <path> [--threshold X] [--token-amount N] [--latency-ms M]`
+    pub fn from_args(args: &[String], env: &Env) -> Result<Self> {
+        let path = args
+            .first()
+            .ok_or_else(|| anyhow!("Usage: arb-rust replay <path> [--threshold X] [--token-amount N] [--latency-ms M]"))?;
+        let mut config = Self {
+            path: PathBuf::from(path),
+            threshold: env.arbitrage_threshold,
+            token_amount: env.token_amount,
+            latency_ms: 0,
+        };
+
+        let mut iter = args[1..].iter();
+        while let Some(flag) = iter.next() {
+            let value = iter
+                .next()
+                .ok_or_else(|| anyhow!("Missing value for {}", flag))?;
+            match flag.as_str() {
+                "--threshold" => config.threshold = value.parse()?,
+                "--token-amount" => config.token_amount = value.parse()?,
+                "--latency-ms" => config.latency_ms = value.parse()?,
+                _ => return Err(anyhow!("Unknown replay flag: {}", flag)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// Order the simulated executor is waiting to fill
+#[derive(Debug, Clone)]
+struct SimOrder {
+    up_token_id: String,
+    down_token_id: String,
+    up_limit: f64,
+    down_limit: f64,
+    size: f64,
+    net_edge_per_set: f64, // From the profitability model at detection time
+    fill_at: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct ReplayStats {
+    pub messages: u64,
+    pub book_updates: u64,
+    pub detections: u64,
+    pub skipped_busy: u64, // Detected while another simulated trade was in flight
+    pub captured: u64,
+    pub missed: u64, // Book moved away before the simulated fill
+    pub sets_filled: f64,
+    pub profit_usdc: f64,
+    pub first_ts: Option<i64>,
+    pub last_ts: Option<i64>,
+    processing_us: Vec<u128>,
+}
+
+impl ReplayStats {
+    fn percentile_us(&self, pct: f64) -> u128 {
+        if self.processing_us.is_empty() {
+            return 0;
+        }
+        let mut sorted = self.processing_us.clone();
+        sorted.sort_unstable();
+        let idx = ((sorted.len() - 1) as f64 * pct).round() as usize;
+        sorted[idx]
+    }
+}
+
+// Size available at or better than `limit` (BTW: asks are sorted best first)
+fn fillable(asks: &[OrderbookLevel], limit: f64) -> f64 {
+    asks.iter()
+        .take_while(|level| level.price <= limit + f64::EPSILON)
+        .map(|level| level.size)
+        .sum()
+}
+
+// Simulated FAK fill against the book as it looks `latency_ms` after detection
+fn try_fill(order: &SimOrder, books: &HashMap<String, OrderbookSnapshot>, stats: &mut ReplayStats) {
+    let (up, down) = match (books.get(&order.up_token_id), books.get(&order.down_token_id)) {
+        (Some(up), Some(down)) => (up, down),
+        _ => {
+            stats.missed += 1;
+            return;
+        }
+    };
+
+    let filled = order
+        .size
+        .min(fillable(&up.asks, order.up_limit))
+        .min(fillable(&down.asks, order.down_limit));
+    if filled <= 0.0 {
+        stats.missed += 1;
+        return;
+    }
+
+    stats.captured += 1;
+    stats.sets_filled += filled;
+    stats.profit_usdc += filled * order.net_edge_per_set;
+}
+
+fn open_events(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_dir() {
+        list_recordings(path)
+    } else {
+        Ok(vec![path.to_path_buf()])
+    }
+}
+
+// Replay recorded WS traffic through the live parser and detection logic (IMO: real code paths, fake executor)
+pub async fn run_replay(config: &ReplayConfig, env: &Env) -> Result<ReplayStats> {
+    let mut env = env.clone();
+    env.arbitrage_threshold = config.threshold;
+
+    let ws = MarketWebSocket::new(env.clob_ws_url.clone()); // Never connects, only parses
+    let mut markets: HashMap<String, CoinMarket> = HashMap::new(); // token_id -> market
+    let mut books: HashMap<String, OrderbookSnapshot> = HashMap::new();
+    let mut pending: Option<SimOrder> = None; // Mirrors the live single-trade lock
+    let mut stats = ReplayStats::default();
+
+    for file in open_events(&config.path)? {
+        let reader = BufReader::new(MultiGzDecoder::new(File::open(&file)?));
+        for line in reader.lines() {
+            // Truncated tail of a crashed recording (FYI: stop this file, keep going)
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            let event: RecordedEvent = match serde_json::from_str(&line) {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+
+            let (ts, raw) = match event {
+                RecordedEvent::Market { market, .. } => {
+                    markets.insert(market.up_token_id.clone(), market.clone());
+                    markets.insert(market.down_token_id.clone(), market);
+                    continue;
+                }
+                RecordedEvent::Message { ts, raw } => (ts, raw),
+            };
+
+            stats.messages += 1;
+            stats.first_ts.get_or_insert(ts);
+            stats.last_ts = Some(ts);
+
+            // Fill anything whose simulated latency has elapsed, against books as of now
+            if let Some(order) = pending.as_ref() {
+                if ts >= order.fill_at {
+                    try_fill(order, &books, &mut stats);
+                    pending = None;
+                }
+            }
+
+            let started = Instant::now();
+            let updated = match ws.handle_message(&raw) {
+                Ok(updated) => updated,
+                Err(_) => continue,
+            };
+
+            for snapshot in updated {
+                stats.book_updates += 1;
+                let asset_id = snapshot.asset_id.clone();
+                books.insert(asset_id.clone(), snapshot);
+
+                let market = match markets.get(&asset_id) {
+                    Some(m) => m,
+                    None => continue,
+                };
+                let price_data = create_price_data(
+                    &market.coin,
+                    books.get(&market.up_token_id),
+                    books.get(&market.down_token_id),
+                    &env,
+                );
+                if !price_data.has_arbitrage || price_data.up_ask <= 0.0 || price_data.down_ask <= 0.0 {
+                    continue;
+                }
+
+                // Same net-edge gate as live trading (FYI: fee rate comes from the recorded market)
+                let (up_book, down_book) = match (books.get(&market.up_token_id), books.get(&market.down_token_id)) {
+                    (Some(up), Some(down)) => (up, down),
+                    _ => continue,
+                };
+                let estimate = evaluate_buy(up_book, down_book, market.fee_rate_bps, config.token_amount, &env);
+                if !estimate.is_tradeable(env.min_net_edge_bps) {
+                    continue;
+                }
+
+                stats.detections += 1;
+                if pending.is_some() {
+                    stats.skipped_busy += 1;
+                    continue;
+                }
+
+                pending = Some(SimOrder {
+                    up_token_id: market.up_token_id.clone(),
+                    down_token_id: market.down_token_id.clone(),
+                    up_limit: price_data.up_ask,
+                    down_limit: price_data.down_ask,
+                    size: config.token_amount,
+                    net_edge_per_set: estimate.net_edge_per_set,
+                    fill_at: ts + config.latency_ms,
+                });
+
+                // Zero latency fills against the very book that triggered it
+                if config.latency_ms <= 0 {
+                    if let Some(order) = pending.take() {
+                        try_fill(&order, &books, &mut stats);
+                    }
+                }
+            }
+            stats.processing_us.push(started.elapsed().as_micros());
+        }
+    }
+
+    // Anything still in flight at the end of the tape never got a chance
+    if pending.is_some() {
+        stats.missed += 1;
+    }
+
+    Ok(stats)
+}
+
+pub fn print_replay_report(config: &ReplayConfig, stats: &ReplayStats) {
+    let span_secs = match (stats.first_ts, stats.last_ts) {
+        (Some(first), Some(last)) => (last - first) as f64 / 1000.0,
+        _ => 0.0,
+    };
+    let attempts = stats.captured + stats.missed;
+    let capture_rate = if attempts > 0 {
+        stats.captured as f64 / attempts as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    println!("{}", "\n╔════════════════════════════════════════════════════════════════╗".cyan().bold());
+    println!("{}", "║                      REPLAY RESULTS                            ║".cyan().bold());
+    println!("{}", "╚════════════════════════════════════════════════════════════════╝".cyan().bold());
+    println!("  Source: {}", config.path.display());
+    println!(
+        "  Threshold: {:.4} | Token amount: {:.2} | Simulated latency: {} ms",
+        config.threshold, config.token_amount, config.latency_ms
+    );
+    println!(
+        "  Messages: {} | Book updates: {} | Span: {:.1}s",
+        stats.messages, stats.book_updates, span_secs
+    );
+    println!(
+        "  Detections: {} | Skipped (busy): {} | Captured: {} | Missed: {} | Capture rate: {:.1}%",
+        stats.detections, stats.skipped_busy, stats.captured, stats.missed, capture_rate
+    );
+    println!(
+        "{}",
+        format!(
+            "  Sets filled: {:.2} | Simulated net profit: ${:.4} USDC",
+            stats.sets_filled, stats.profit_usdc
+        )
+        .green()
+        .bold()
+    );
+    println!(
+        "  Processing latency per message: p50 {} µs | p99 {} µs\n",
+        stats.percentile_us(0.5),
+        stats.percentile_us(0.99)
+    );
+}
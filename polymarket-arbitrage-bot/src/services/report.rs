@@ -0,0 +1,228 @@
+use crate::config::Env;
+use crate::services::ledger::Ledger;
+use anyhow::{anyhow, Result};
+use colored::*;
+use rusqlite::params;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// What to summarize (FYI: defaults to the live ledger, last 7 days, every coin)
+#[derive(Debug, Clone)]
+pub struct ReportConfig {
+    pub db: PathBuf, // Ledger SQLite file
+    pub days: i64, // Days back from today (UTC), today included
+    pub coin: Option<String>, // Only this coin
+}
+
+impl ReportConfig {
+    // Parse `report [--db <path>] [--days N] [--coin COIN]`
+    pub fn from_args(args: &[String], env: &Env) -> Result<Self> {
+        let mut config = Self {
+            db: PathBuf::from(&env.ledger_path),
+            days: 7,
+            coin: None,
+        };
+
+        let mut iter = args.iter();
+        while let Some(flag) = iter.next() {
+            let value = iter
+                .next()
+                .ok_or_else(|| anyhow!("Missing value for {}", flag))?;
+            match flag.as_str() {
+                "--db" => config.db = PathBuf::from(value),
+                "--days" => config.days = value.parse::<i64>()?.max(1),
+                "--coin" => config.coin = Some(value.to_uppercase()),
+                _ => return Err(anyhow!("Unknown report flag: {}", flag)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// One coin on one day (BTW: the totals rows use the same shape with day = "all")
+#[derive(Debug, Clone, Default)]
+pub struct ReportRow {
+    pub coin: String,
+    pub day: String,
+    pub detections: u64,
+    pub captured: u64, // Attempts where both legs filled
+    pub orders: u64,
+    pub failed_orders: u64,
+    pub unwinds: u64,
+    pub edge_bps_sum: f64, // Summed so totals can be re-averaged
+    pub realized_pnl: f64,
+}
+
+impl ReportRow {
+    pub fn capture_rate(&self) -> f64 {
+        ratio(self.captured, self.detections)
+    }
+
+    pub fn leg_failure_rate(&self) -> f64 {
+        ratio(self.failed_orders, self.orders)
+    }
+
+    pub fn avg_edge_bps(&self) -> f64 {
+        if self.detections == 0 {
+            return 0.0;
+        }
+        self.edge_bps_sum / self.detections as f64
+    }
+
+    fn add(&mut self, other: &ReportRow) {
+        self.detections += other.detections;
+        self.captured += other.captured;
+        self.orders += other.orders;
+        self.failed_orders += other.failed_orders;
+        self.unwinds += other.unwinds;
+        self.edge_bps_sum += other.edge_bps_sum;
+        self.realized_pnl += other.realized_pnl;
+    }
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    part as f64 / whole as f64 * 100.0
+}
+
+// Per coin per day, oldest first (AFAIK: PnL lands on the day it was realized, e.g. the merge)
+pub fn build_report(config: &ReportConfig) -> Result<Vec<ReportRow>> {
+    if !config.db.exists() {
+        return Err(anyhow!("Ledger not found: {}", config.db.display()));
+    }
+    let conn = Ledger::open_read_only(&config.db)?;
+
+    let since = (chrono::Utc::now().date_naive() - chrono::Duration::days(config.days - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    let coin = config.coin.clone().unwrap_or_default();
+
+    let mut rows: HashMap<(String, String), ReportRow> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT coin, day,
+                SUM(kind = 'detection'),
+                COALESCE(SUM(CASE WHEN kind = 'detection' THEN edge_bps END), 0),
+                SUM(kind = 'order'),
+                SUM(kind = 'order' AND success = 0),
+                SUM(kind = 'unwind'),
+                COALESCE(SUM(pnl_usdc), 0)
+         FROM ledger
+         WHERE day >= ?1 AND (?2 = '' OR coin = ?2)
+         GROUP BY coin, day",
+    )?;
+    let mapped = stmt.query_map(params![since, coin], |r| {
+        Ok(ReportRow {
+            coin: r.get(0)?,
+            day: r.get(1)?,
+            detections: r.get::<_, i64>(2)? as u64,
+            edge_bps_sum: r.get(3)?,
+            orders: r.get::<_, i64>(4)? as u64,
+            failed_orders: r.get::<_, i64>(5)? as u64,
+            unwinds: r.get::<_, i64>(6)? as u64,
+            realized_pnl: r.get(7)?,
+            captured: 0,
+        })
+    })?;
+    for row in mapped {
+        let row = row?;
+        rows.insert((row.day.clone(), row.coin.clone()), row);
+    }
+
+    // Captured = an attempt with a fill on both legs (FYI: counted on the day of its first fill)
+    let mut stmt = conn.prepare(
+        "SELECT coin, day, COUNT(*) FROM (
+             SELECT coin, MIN(day) AS day, trade_id
+             FROM ledger
+             WHERE kind = 'fill' AND success = 1 AND trade_id IS NOT NULL
+             GROUP BY trade_id
+             HAVING COUNT(DISTINCT leg) >= 2
+         )
+         WHERE day >= ?1 AND (?2 = '' OR coin = ?2)
+         GROUP BY coin, day",
+    )?;
+    let captured = stmt.query_map(params![since, coin], |r| {
+        Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, i64>(2)?))
+    })?;
+    for entry in captured {
+        let (coin, day, count) = entry?;
+        rows.entry((day.clone(), coin.clone()))
+            .or_insert_with(|| ReportRow { coin, day, ..Default::default() })
+            .captured = count as u64;
+    }
+
+    let mut rows: Vec<ReportRow> = rows.into_values().collect();
+    rows.sort_by(|a, b| a.day.cmp(&b.day).then_with(|| a.coin.cmp(&b.coin)));
+    Ok(rows)
+}
+
+// Roll the daily rows up per coin
+pub fn totals_by_coin(rows: &[ReportRow]) -> Vec<ReportRow> {
+    let mut totals: HashMap<String, ReportRow> = HashMap::new();
+    for row in rows {
+        totals
+            .entry(row.coin.clone())
+            .or_insert_with(|| ReportRow { coin: row.coin.clone(), day: "all".to_string(), ..Default::default() })
+            .add(row);
+    }
+    let mut totals: Vec<ReportRow> = totals.into_values().collect();
+    totals.sort_by(|a, b| a.coin.cmp(&b.coin));
+    totals
+}
+
+fn print_row(row: &ReportRow) {
+    let pnl = format!("{:>+10.4}", row.realized_pnl);
+    let pnl = if row.realized_pnl >= 0.0 { pnl.green() } else { pnl.red() };
+    println!(
+        "  {:<10} {:<5} {:>6} {:>6} {:>7.1}% {:>8.1} {:>6} {:>7.1}% {:>6}  {}",
+        row.day,
+        row.coin,
+        row.detections,
+        row.captured,
+        row.capture_rate(),
+        row.avg_edge_bps(),
+        row.orders,
+        row.leg_failure_rate(),
+        row.unwinds,
+        pnl
+    );
+}
+
+pub fn print_report(config: &ReportConfig, rows: &[ReportRow]) {
+    println!("{}", "\n╔════════════════════════════════════════════════════════════════╗".cyan().bold());
+    println!("{}", "║                       LEDGER REPORT                            ║".cyan().bold());
+    println!("{}", "╚════════════════════════════════════════════════════════════════╝".cyan().bold());
+    println!(
+        "  Source: {} | Last {} day(s) (UTC) | Coin: {}\n",
+        config.db.display(),
+        config.days,
+        config.coin.as_deref().unwrap_or("all")
+    );
+
+    if rows.is_empty() {
+        println!("{}", "  No ledger entries in this range.\n".yellow());
+        return;
+    }
+
+    let header = format!(
+        "  {:<10} {:<5} {:>6} {:>6} {:>8} {:>8} {:>6} {:>8} {:>6}  {:>10}",
+        "Day", "Coin", "Detect", "Capt", "Capture", "Edge bp", "Orders", "Leg fail", "Unwnd", "PnL (USDC)"
+    );
+    println!("{}", header.bold());
+    for row in rows {
+        print_row(row);
+    }
+
+    println!("\n{}", header.bold());
+    let totals = totals_by_coin(rows);
+    for row in &totals {
+        print_row(row);
+    }
+
+    let total_pnl: f64 = totals.iter().map(|r| r.realized_pnl).sum();
+    println!(
+        "{}",
+        format!("\n  Realized PnL: ${:.4} USDC\n", total_pnl).green().bold()
+    );
+}
@@ -1,11 +1,47 @@
+use crate::services::metrics::WS_MESSAGES;
+use crate::services::recorder::MessageRecorder;
+use crate::utils::logger::log_error;
 use anyhow::{anyhow, Result};
-use futures_util::{SinkExt, StreamExt};
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
 use serde_json::json;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::time::{interval, sleep, Duration, Instant};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
+// Book updates buffered per receiver before it starts lagging (FYI: a slow consumer skips, never blocks the socket)
+const BOOK_CHANNEL_CAPACITY: usize = 1024;
+// Polymarket drops idle market sockets, so we PING on this cadence (AFAIK: they suggest ~10s)
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+// Nothing received for this long = dead connection, reconnect
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+// Reconnect backoff: 1s, 2s, 4s ... capped (BTW: reset after a connection that actually delivered data)
+const RECONNECT_BASE: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+// Heartbeat and reconnect timings (FYI: the constants above in production, shrunk in tests)
+#[derive(Debug, Clone, Copy)]
+struct Timings {
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+    reconnect_base: Duration,
+    reconnect_max: Duration,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: HEARTBEAT_INTERVAL,
+            heartbeat_timeout: HEARTBEAT_TIMEOUT,
+            reconnect_base: RECONNECT_BASE,
+            reconnect_max: RECONNECT_MAX,
+        }
+    }
+}
+
 // Single price level in orderbook (FYI: price + size)
 #[derive(Debug, Clone)]
 pub struct OrderbookLevel {
@@ -24,46 +60,96 @@ pub struct OrderbookSnapshot {
     pub hash: Option<String>, // Optional hash for validation
 }
 
-// Callback type for orderbook updates (BTW: Arc allows sharing across threads)
-pub type BookCallback = Arc<dyn Fn(OrderbookSnapshot) + Send + Sync>;
+// Connection health as seen by the run loop (IMO: dashboards and alerts read this, not log lines)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConnectionState {
+    Idle,
+    Connecting,
+    Connected,
+    Reconnecting { attempt: u32, delay_ms: u64 },
+    Stopped,
+}
+
+// Requests for the live connection (BTW: sent over a channel so callers never touch the socket)
+#[derive(Debug)]
+enum Command {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+type WsStream = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
 
 pub struct MarketWebSocket {
     url: String,
-    subscribed_assets: Arc<Mutex<Vec<String>>>,
-    orderbooks: Arc<Mutex<HashMap<String, OrderbookSnapshot>>>,
-    on_book_callback: Arc<Mutex<Option<BookCallback>>>,
-    is_running: Arc<Mutex<bool>>,
+    subscribed_assets: Mutex<HashSet<String>>, // Re-sent in full after every reconnect
+    orderbooks: RwLock<HashMap<String, OrderbookSnapshot>>, // Latest snapshot per asset (FYI: sync lock, never held across .await)
+    books_tx: broadcast::Sender<OrderbookSnapshot>,
+    state_tx: watch::Sender<ConnectionState>,
+    commands_tx: mpsc::UnboundedSender<Command>,
+    commands_rx: tokio::sync::Mutex<mpsc::UnboundedReceiver<Command>>, // Only the run loop reads this
+    is_running: AtomicBool,
+    recorder: Option<MessageRecorder>, // Raw message capture for replay (optional)
+    timings: Timings,
 }
 
 impl MarketWebSocket {
     pub fn new(url: String) -> Self {
+        let (books_tx, _) = broadcast::channel(BOOK_CHANNEL_CAPACITY);
+        let (state_tx, _) = watch::channel(ConnectionState::Idle);
+        let (commands_tx, commands_rx) = mpsc::unbounded_channel();
         Self {
             url,
-            subscribed_assets: Arc::new(Mutex::new(Vec::new())),
-            orderbooks: Arc::new(Mutex::new(HashMap::new())),
-            on_book_callback: Arc::new(Mutex::new(None)),
-            is_running: Arc::new(Mutex::new(false)),
+            subscribed_assets: Mutex::new(HashSet::new()),
+            orderbooks: RwLock::new(HashMap::new()),
+            books_tx,
+            state_tx,
+            commands_tx,
+            commands_rx: tokio::sync::Mutex::new(commands_rx),
+            is_running: AtomicBool::new(false),
+            recorder: None,
+            timings: Timings::default(),
         }
     }
 
-    // Register callback for orderbook updates (FYI: called whenever we get new data)
-    pub fn on_book<F>(&self, callback: F)
-    where
-        F: Fn(OrderbookSnapshot) + Send + Sync + 'static,
-    {
-        *self.on_book_callback.blocking_lock() = Some(Arc::new(callback));
+    // Capture every raw text frame to disk (FYI: see services::recorder)
+    pub fn with_recorder(mut self, recorder: MessageRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn recorder(&self) -> Option<&MessageRecorder> {
+        self.recorder.as_ref()
+    }
+
+    // Stream of book updates (FYI: each receiver sees every update from the moment it subscribes)
+    pub fn book_updates(&self) -> broadcast::Receiver<OrderbookSnapshot> {
+        self.books_tx.subscribe()
+    }
+
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state_tx.borrow().clone()
+    }
+
+    pub fn watch_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state_tx.subscribe()
     }
 
     // Get cached orderbook for asset (AFAIK: returns latest snapshot we received)
     pub fn get_orderbook(&self, asset_id: &str) -> Option<OrderbookSnapshot> {
-        self.orderbooks.blocking_lock().get(asset_id).cloned()
+        self.orderbooks.read().unwrap().get(asset_id).cloned()
+    }
+
+    pub fn subscribed(&self) -> Vec<String> {
+        self.subscribed_assets.lock().unwrap().iter().cloned().collect()
     }
 
     // Parse orderbook from JSON (IMO: handles Polymarket's WS message format)
-    fn parse_orderbook_snapshot(data: &serde_json::Value) -> Result<OrderbookSnapshot> {
+    pub(crate) fn parse_orderbook_snapshot(data: &serde_json::Value) -> Result<OrderbookSnapshot> {
         let mut bids: Vec<OrderbookLevel> = data
             .get("bids")
             .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
             .unwrap_or(&[])
             .iter()
             .filter_map(|b| {
@@ -78,6 +164,7 @@ impl MarketWebSocket {
         let mut asks: Vec<OrderbookLevel> = data
             .get("asks")
             .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
             .unwrap_or(&[])
             .iter()
             .filter_map(|a| {
@@ -111,17 +198,17 @@ impl MarketWebSocket {
         })
     }
 
-    // Handle incoming WS message (FYI: can be single msg or array of msgs)
-    async fn handle_message(&self, message: &str) -> Result<()> {
+    // Handle incoming WS message (FYI: can be single msg or array of msgs, returns the books it updated)
+    pub(crate) fn handle_message(&self, message: &str) -> Result<Vec<OrderbookSnapshot>> {
         let data: serde_json::Value = serde_json::from_str(message)?;
 
         // Handle both single msg and array formats (AFAIK: Polymarket sends both)
-        let messages = if data.is_array() {
-            data.as_array().unwrap().clone()
-        } else {
-            vec![data]
+        let messages = match data {
+            serde_json::Value::Array(items) => items,
+            other => vec![other],
         };
 
+        let mut updated = Vec::new();
         for msg in messages {
             let event_type = msg
                 .get("event_type")
@@ -130,109 +217,388 @@ impl MarketWebSocket {
 
             if event_type == Some("book") {
                 let snapshot = Self::parse_orderbook_snapshot(&msg)?;
-                let asset_id = snapshot.asset_id.clone();
-                
+
                 // Cache orderbook (IMO: allows quick lookups without WS roundtrip)
-                {
-                    let mut orderbooks = self.orderbooks.blocking_lock();
-                    orderbooks.insert(asset_id.clone(), snapshot.clone());
-                }
+                self.orderbooks
+                    .write()
+                    .unwrap()
+                    .insert(snapshot.asset_id.clone(), snapshot.clone());
 
-                // Call registered callback (FYI: triggers arbitrage detection)
-                let callback_guard = self.on_book_callback.blocking_lock();
-                if let Some(ref callback) = *callback_guard {
-                    callback(snapshot.clone());
-                }
+                // Fan out to listeners (FYI: Err just means nobody is listening right now)
+                let _ = self.books_tx.send(snapshot.clone());
+                updated.push(snapshot);
             }
         }
 
-        Ok(())
+        Ok(updated)
     }
 
-    // Subscribe to asset orderbooks (FYI: stores IDs, actual sub happens in run loop)
-    pub async fn subscribe(&self, asset_ids: Vec<String>) -> Result<()> {
+    // Add assets to the subscription (FYI: takes effect on the live connection, and survives reconnects)
+    pub fn subscribe(&self, asset_ids: Vec<String>) -> Result<()> {
         if asset_ids.is_empty() {
             return Err(anyhow!("No asset IDs provided"));
         }
 
-        {
-            let mut subscribed = self.subscribed_assets.blocking_lock();
-            *subscribed = asset_ids.clone(); // Store for later subscription
+        let new_ids: Vec<String> = {
+            let mut subscribed = self.subscribed_assets.lock().unwrap();
+            asset_ids.into_iter().filter(|id| subscribed.insert(id.clone())).collect()
+        };
+        if !new_ids.is_empty() {
+            let _ = self.commands_tx.send(Command::Subscribe(new_ids));
+        }
+        Ok(())
+    }
+
+    // Drop assets from the subscription and forget their cached books
+    pub fn unsubscribe(&self, asset_ids: Vec<String>) -> Result<()> {
+        let removed: Vec<String> = {
+            let mut subscribed = self.subscribed_assets.lock().unwrap();
+            asset_ids.into_iter().filter(|id| subscribed.remove(id)).collect()
+        };
+        if removed.is_empty() {
+            return Ok(());
         }
 
-        // Subscription will be handled in the run loop (BTW: after WS connects)
+        {
+            let mut orderbooks = self.orderbooks.write().unwrap();
+            for id in &removed {
+                orderbooks.remove(id);
+            }
+        }
+        let _ = self.commands_tx.send(Command::Unsubscribe(removed));
         Ok(())
     }
 
-    // Main WS loop with auto-reconnect (IMO: keeps connection alive)
+    // Main WS loop with heartbeats and exponential reconnect backoff (IMO: keeps connection alive)
     pub async fn run(&self, auto_reconnect: bool) -> Result<()> {
-        *self.is_running.blocking_lock() = true;
+        let mut commands = self
+            .commands_rx
+            .try_lock()
+            .map_err(|_| anyhow!("WebSocket run loop is already running"))?;
+        self.is_running.store(true, Ordering::SeqCst);
 
-        loop {
-            if !*self.is_running.blocking_lock() {
-                break; // Stop requested
-            }
+        let mut attempt: u32 = 0;
+        while self.is_running.load(Ordering::SeqCst) {
+            self.state_tx.send_replace(ConnectionState::Connecting);
 
-            // Connect to WS endpoint
-            match self.connect().await {
-                Ok((mut ws_stream, _)) => {
-                    // Subscribe to assets (AFAIK: sends sub msg after connection)
-                    {
-                        let subscribed = self.subscribed_assets.blocking_lock();
-                        if !subscribed.is_empty() {
-                            let subscribe_msg = json!({
-                                "assets_ids": subscribed.clone(),
-                                "type": "MARKET"
-                            });
-                            let _ = ws_stream.send(Message::Text(subscribe_msg.to_string())).await;
-                        }
+            match connect_async(&self.url).await {
+                Ok((ws_stream, _)) => {
+                    self.state_tx.send_replace(ConnectionState::Connected);
+                    let mut received_data = false;
+                    if let Err(e) = self.serve(ws_stream, &mut commands, &mut received_data).await {
+                        log_error(&format!("WebSocket session ended: {}", e), Some("websocket"));
+                    }
+                    // Only a session that delivered books resets the backoff, however it ended
+                    if received_data {
+                        attempt = 0;
                     }
+                }
+                Err(e) => log_error(&format!("Connection error: {}", e), Some("websocket")),
+            }
 
-                    // Handle incoming messages (FYI: processes orderbook updates)
-                    while *self.is_running.blocking_lock() {
-                        match ws_stream.next().await {
-                            Some(Ok(Message::Text(text))) => {
-                                if let Err(e) = self.handle_message(&text).await {
-                                    eprintln!("Error handling message: {}", e);
-                                }
-                            }
-                            Some(Ok(Message::Ping(data))) => {
-                                let _ = ws_stream.send(Message::Pong(data)).await;
+            if !auto_reconnect || !self.is_running.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let delay = backoff_delay(attempt, self.timings.reconnect_base, self.timings.reconnect_max);
+            attempt = attempt.saturating_add(1);
+            self.state_tx.send_replace(ConnectionState::Reconnecting {
+                attempt,
+                delay_ms: delay.as_millis() as u64,
+            });
+            sleep(delay).await;
+        }
+
+        self.state_tx.send_replace(ConnectionState::Stopped);
+        Ok(())
+    }
+
+    // One connected session (BTW: sets received_data once a book arrives, so flapping connections keep backing off)
+    async fn serve(
+        &self,
+        mut ws_stream: WsStream,
+        commands: &mut mpsc::UnboundedReceiver<Command>,
+        received_data: &mut bool,
+    ) -> Result<()> {
+        // Full subscription goes out on connect, so anything queued while offline is already covered
+        while commands.try_recv().is_ok() {}
+        let assets = self.subscribed();
+        if !assets.is_empty() {
+            let subscribe_msg = json!({
+                "assets_ids": assets,
+                "type": "market"
+            });
+            ws_stream.send(Message::Text(subscribe_msg.to_string())).await?;
+        }
+
+        let mut heartbeat = interval(self.timings.heartbeat_interval);
+        let mut last_seen = Instant::now();
+
+        while self.is_running.load(Ordering::SeqCst) {
+            tokio::select! {
+                frame = ws_stream.next() => {
+                    last_seen = Instant::now();
+                    match frame {
+                        Some(Ok(Message::Text(text))) => {
+                            if text == "PONG" {
+                                continue; // Heartbeat reply, not JSON (IMO: keeps the socket alive, doesn't count as data)
                             }
-                            Some(Ok(Message::Close(_))) => {
-                                break;
+                            WS_MESSAGES.inc();
+                            if let Some(ref recorder) = self.recorder {
+                                recorder.record_message(&text);
                             }
-                            Some(Err(e)) => {
-                                eprintln!("WebSocket error: {}", e);
-                                break;
+                            match self.handle_message(&text) {
+                                Ok(books) if !books.is_empty() => *received_data = true,
+                                Ok(_) => {}
+                                Err(e) => log_error(&format!("Error handling message: {}", e), Some("websocket")),
                             }
-                            None => break,
-                            _ => {}
                         }
+                        Some(Ok(Message::Ping(data))) => {
+                            ws_stream.send(Message::Pong(data)).await?;
+                        }
+                        Some(Ok(Message::Close(_))) | None => return Ok(()),
+                        Some(Err(e)) => return Err(e.into()),
+                        _ => {}
                     }
                 }
-                Err(e) => {
-                    eprintln!("Connection error: {}", e);
+                Some(command) = commands.recv() => {
+                    let msg = match command {
+                        Command::Subscribe(ids) => json!({ "assets_ids": ids, "operation": "subscribe" }),
+                        Command::Unsubscribe(ids) => json!({ "assets_ids": ids, "operation": "unsubscribe" }),
+                    };
+                    ws_stream.send(Message::Text(msg.to_string())).await?;
+                }
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > self.timings.heartbeat_timeout {
+                        return Err(anyhow!(
+                            "No data for {}s, reconnecting",
+                            self.timings.heartbeat_timeout.as_secs_f64()
+                        ));
+                    }
+                    ws_stream.send(Message::Text("PING".to_string())).await?;
                 }
             }
+        }
 
-            if !auto_reconnect || !*self.is_running.blocking_lock() {
-                break;
+        let _ = ws_stream.close(None).await;
+        Ok(())
+    }
+
+    // Ask the run loop to exit (FYI: takes effect on the next frame, command or heartbeat)
+    pub fn stop(&self) {
+        self.is_running.store(false, Ordering::SeqCst);
+    }
+}
+
+fn backoff_delay(attempt: u32, base: Duration, max: Duration) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt.min(16))).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio::time::timeout;
+    use tokio_tungstenite::accept_async;
+
+    const BOOK: &str = r#"{"event_type":"book","asset_id":"a1","market":"m1","timestamp":"1","bids":[{"price":"0.48","size":"10"}],"asks":[{"price":"0.52","size":"10"}]}"#;
+    // Whole-test guard (FYI: a hung socket fails the test instead of the suite)
+    const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    impl MarketWebSocket {
+        fn with_timings(mut self, timings: Timings) -> Self {
+            self.timings = timings;
+            self
+        }
+    }
+
+    fn fast_timings() -> Timings {
+        Timings {
+            heartbeat_interval: Duration::from_millis(50),
+            heartbeat_timeout: Duration::from_millis(300),
+            reconnect_base: Duration::from_millis(50),
+            reconnect_max: Duration::from_millis(400),
+        }
+    }
+
+    // How the mock server treats every connection
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Behavior {
+        Silent,        // Reads frames, never answers (BTW: not even PONG)
+        PongThenClose, // Answers PING with PONG, closes after the second one
+        BookThenClose, // Answers the first frame with a book, then closes
+    }
+
+    // Local WS server, forwards every text frame it receives as (connection index, text)
+    async fn mock_server(behavior: Behavior) -> (String, mpsc::UnboundedReceiver<(usize, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (frames_tx, frames_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut connections = 0;
+            while let Ok((stream, _)) = listener.accept().await {
+                let frames_tx = frames_tx.clone();
+                let index = connections;
+                connections += 1;
+                tokio::spawn(async move {
+                    let Ok(mut ws) = accept_async(stream).await else {
+                        return;
+                    };
+                    let mut pongs = 0;
+                    while let Some(Ok(frame)) = ws.next().await {
+                        let Message::Text(text) = frame else {
+                            continue;
+                        };
+                        let _ = frames_tx.send((index, text.clone()));
+                        match behavior {
+                            Behavior::Silent => {}
+                            Behavior::PongThenClose if text == "PING" => {
+                                let _ = ws.send(Message::Text("PONG".to_string())).await;
+                                pongs += 1;
+                                if pongs == 2 {
+                                    let _ = ws.close(None).await;
+                                }
+                            }
+                            Behavior::PongThenClose => {}
+                            Behavior::BookThenClose => {
+                                let _ = ws.send(Message::Text(BOOK.to_string())).await;
+                                let _ = ws.close(None).await;
+                            }
+                        }
+                    }
+                });
             }
+        });
+        (url, frames_rx)
+    }
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    // Next JSON frame from the client, skipping heartbeats
+    async fn next_json(frames: &mut mpsc::UnboundedReceiver<(usize, String)>) -> serde_json::Value {
+        loop {
+            let (_, text) = frames.recv().await.unwrap();
+            if text != "PING" {
+                return serde_json::from_str(&text).unwrap();
+            }
         }
+    }
 
-        Ok(())
+    // Attempt numbers of the first `count` Reconnecting states
+    async fn reconnect_attempts(state: &mut watch::Receiver<ConnectionState>, count: usize) -> Vec<u32> {
+        let mut attempts = Vec::new();
+        while attempts.len() < count {
+            state.changed().await.unwrap();
+            if let ConnectionState::Reconnecting { attempt, .. } = *state.borrow_and_update() {
+                attempts.push(attempt);
+            }
+        }
+        attempts
     }
 
-    async fn connect(&self) -> Result<(tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>, tokio_tungstenite::tungstenite::handshake::client::Response)> {
-        let result = connect_async(&self.url).await?;
-        Ok(result)
+    fn start(ws: &Arc<MarketWebSocket>, auto_reconnect: bool) {
+        let ws = ws.clone();
+        tokio::spawn(async move { ws.run(auto_reconnect).await });
     }
 
-    pub fn stop(&self) {
-        *self.is_running.blocking_lock() = false;
+    #[tokio::test]
+    async fn subscribe_and_unsubscribe_reach_the_live_connection() {
+        timeout(TEST_TIMEOUT, async {
+            let (url, mut frames) = mock_server(Behavior::Silent).await;
+            let ws = Arc::new(MarketWebSocket::new(url).with_timings(fast_timings()));
+            ws.subscribe(vec!["a1".to_string()]).unwrap();
+            start(&ws, false);
+
+            // Full set on connect
+            assert_eq!(next_json(&mut frames).await, json!({ "assets_ids": ["a1"], "type": "market" }));
+
+            // Repeats are dropped, so the next frame after the subscribe is the unsubscribe
+            ws.subscribe(vec!["a2".to_string()]).unwrap();
+            ws.subscribe(vec!["a2".to_string()]).unwrap();
+            assert_eq!(next_json(&mut frames).await, json!({ "assets_ids": ["a2"], "operation": "subscribe" }));
+            ws.unsubscribe(vec!["a1".to_string()]).unwrap();
+            assert_eq!(next_json(&mut frames).await, json!({ "assets_ids": ["a1"], "operation": "unsubscribe" }));
+            assert_eq!(ws.subscribed(), vec!["a2".to_string()]);
+
+            ws.stop();
+        })
+        .await
+        .unwrap();
     }
-}
 
+    #[tokio::test]
+    async fn silent_socket_hits_the_heartbeat_timeout_and_reconnects() {
+        timeout(TEST_TIMEOUT, async {
+            let (url, mut frames) = mock_server(Behavior::Silent).await;
+            let ws = Arc::new(MarketWebSocket::new(url).with_timings(fast_timings()));
+            let mut state = ws.watch_state();
+            let started = Instant::now();
+            start(&ws, true);
+
+            assert_eq!(reconnect_attempts(&mut state, 1).await, vec![1]);
+            assert!(started.elapsed() >= fast_timings().heartbeat_timeout);
+
+            // First connection got heartbeats, then a second connection came in
+            let mut pings = 0;
+            loop {
+                let (index, text) = frames.recv().await.unwrap();
+                if index == 1 {
+                    break;
+                }
+                if text == "PING" {
+                    pings += 1;
+                }
+            }
+            assert!(pings >= 2, "expected heartbeats before the timeout, got {}", pings);
+
+            ws.stop();
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn heartbeat_only_sessions_keep_backing_off() {
+        timeout(TEST_TIMEOUT, async {
+            let (url, _frames) = mock_server(Behavior::PongThenClose).await;
+            let ws = Arc::new(MarketWebSocket::new(url).with_timings(fast_timings()));
+            let mut state = ws.watch_state();
+            start(&ws, true);
+
+            assert_eq!(reconnect_attempts(&mut state, 3).await, vec![1, 2, 3]);
+            assert!(ws.get_orderbook("a1").is_none());
+
+            ws.stop();
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sessions_with_books_reset_the_backoff() {
+        timeout(TEST_TIMEOUT, async {
+            let (url, _frames) = mock_server(Behavior::BookThenClose).await;
+            let ws = Arc::new(MarketWebSocket::new(url).with_timings(fast_timings()));
+            let mut state = ws.watch_state();
+            let mut books = ws.book_updates();
+            start(&ws, true);
+
+            assert_eq!(reconnect_attempts(&mut state, 3).await, vec![1, 1, 1]);
+            let book = books.recv().await.unwrap();
+            assert_eq!(book.asset_id, "a1");
+            assert_eq!(ws.get_orderbook("a1").unwrap().bids[0].price, 0.48);
+
+            ws.stop();
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let delays: Vec<u64> = (0..8)
+            .map(|attempt| backoff_delay(attempt, RECONNECT_BASE, RECONNECT_MAX).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(backoff_delay(u32::MAX, RECONNECT_BASE, RECONNECT_MAX), RECONNECT_MAX);
+    }
+}
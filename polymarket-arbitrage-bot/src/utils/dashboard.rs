@@ -0,0 +1,424 @@
+use crate::services::engine::{ArbEngine, EngineEvent, Frontend, MarketView, TradingControls};
+use crate::services::position_manager::HeldSet;
+use crate::services::price_monitor::{ArbitrageDetection, PriceData};
+use crate::services::websocket_client::{ConnectionState, OrderbookLevel};
+use crate::utils::logger::format_local;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Sparkline, Table};
+use ratatui::{Frame, Terminal};
+use std::collections::VecDeque;
+use std::io::{stdout, Stdout};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, Duration};
+
+const REDRAW_INTERVAL_MS: u64 = 250; // 4 fps is plenty for a human (FYI: keys are polled on the same tick)
+const LADDER_DEPTH: usize = 5; // Levels shown per side
+const EVENT_LOG_LEN: usize = 200;
+const FEED_STALE_MS: i64 = 5_000; // No book update for this long = feed looks stale
+const THRESHOLD_STEP: f64 = 0.001;
+const EDGE_STEP_BPS: f64 = 5.0;
+
+// Full-screen ratatui dashboard (IMO: events go to a log pane, everything else is read from engine state)
+pub struct Dashboard {
+    log: Mutex<VecDeque<String>>,
+}
+
+impl Dashboard {
+    pub fn new() -> Self {
+        Self {
+            log: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn push(&self, line: String) {
+        let mut log = self.log.lock().unwrap();
+        log.push_back(format!("{} {}", format_local(chrono::Utc::now(), "%H:%M:%S"), line));
+        if log.len() > EVENT_LOG_LEN {
+            log.pop_front();
+        }
+    }
+
+    fn recent(&self, n: usize) -> Vec<String> {
+        let log = self.log.lock().unwrap();
+        log.iter().rev().take(n).cloned().collect()
+    }
+}
+
+impl Frontend for Dashboard {
+    fn event(&self, event: &EngineEvent) {
+        let line = match event {
+            EngineEvent::Discovering { coin } => format!("Discovering market for {}", coin),
+            EngineEvent::ClientReady { client } => format!("{} client ready", client),
+            EngineEvent::ClientUnavailable { client, error, .. } => format!("{} client unavailable: {}", client, error),
+            EngineEvent::RecorderStarted { dir } => format!("Recording to {}/", dir),
+            EngineEvent::MarketFound { slug, fee_rate_bps, .. } => format!("Market found: {} (fee {} bps)", slug, fee_rate_bps),
+            EngineEvent::NoMarket { coin } => format!("No active market for {}, retrying", coin),
+            EngineEvent::MarketClosed { slug, .. } => format!("Market closed: {}", slug),
+            EngineEvent::ClosingSoon { seconds_remaining, .. } => format!("Closing in {}s", seconds_remaining),
+            EngineEvent::ArbitrageDetected { ask_sum, net_edge_bps, .. } => {
+                format!("Arbitrage: ask sum {:.4}, net edge {:.1} bps", ask_sum, net_edge_bps)
+            }
+            EngineEvent::SellArbitrageDetected { bid_sum, .. } => format!("Sell arbitrage: bid sum {:.4}", bid_sum),
+            EngineEvent::QuotesUpdated { up_bid, down_bid, .. } => {
+                format!("Quoting UP {:.2} / DOWN {:.2} (sum {:.2})", up_bid, down_bid, up_bid + down_bid)
+            }
+            EngineEvent::TradeSkipped { reason, .. } => format!("Skipped: {}", reason),
+            EngineEvent::TradeResult { kind, success, up_usdc, down_usdc, error, .. } => format!(
+                "{} {}: UP ${:.2} DOWN ${:.2}{}",
+                kind,
+                if *success { "filled" } else { "failed" },
+                up_usdc,
+                down_usdc,
+                error.as_ref().map(|e| format!(" ({})", e)).unwrap_or_default()
+            ),
+            EngineEvent::PositionSettled { market_slug, recovered_usdc, .. } => {
+                format!("Settled {} → ${:.2}", market_slug, recovered_usdc)
+            }
+            EngineEvent::ControlsChanged { paused, arbitrage_threshold, min_net_edge_bps } => format!(
+                "{} | threshold {:.4} | min edge {:.0} bps",
+                if *paused { "Paused" } else { "Resumed" },
+                arbitrage_threshold,
+                min_net_edge_bps
+            ),
+            EngineEvent::ConnectionChanged { state } => format!("WebSocket: {:?}", state),
+            EngineEvent::EventArbitrage(arb) => format!("Event arbitrage: {} ({:.2}% edge)", arb.title, arb.edge * 100.0),
+            EngineEvent::Error { message } => format!("Error: {}", message),
+            EngineEvent::Shutdown => "Shutting down".to_string(),
+        };
+        self.push(line);
+    }
+}
+
+// Everything one frame needs (BTW: copied out so no engine lock is held while drawing)
+struct DashboardState {
+    view: Option<MarketView>,
+    history: Vec<PriceData>,
+    detections: Vec<ArbitrageDetection>,
+    positions: Vec<HeldSet>,
+    available_budget: f64,
+    deployed_usdc: f64,
+    realized_pnl: f64,
+    controls: TradingControls,
+    trading_enabled: bool,
+    connection: Option<ConnectionState>,
+    book_updates: u64,
+    events: Vec<String>,
+}
+
+impl DashboardState {
+    async fn capture(engine: &ArbEngine, dashboard: &Dashboard) -> Self {
+        let view = engine.latest.lock().await.clone();
+        let (history, detections) = match view {
+            Some(ref v) => {
+                let monitor = engine.monitor.lock().await;
+                (monitor.get_price_history(&v.coin), monitor.get_arbitrage_history(&v.coin))
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+        let (positions, available_budget, deployed_usdc, realized_pnl) = {
+            let manager = engine.positions.lock().await;
+            (
+                manager.positions().to_vec(),
+                manager.available_budget(),
+                manager.deployed_usdc(),
+                manager.realized_pnl(),
+            )
+        };
+
+        Self {
+            view,
+            history,
+            detections,
+            positions,
+            available_budget,
+            deployed_usdc,
+            realized_pnl,
+            controls: *engine.controls.lock().await,
+            trading_enabled: engine.clob_client.lock().await.is_some(),
+            connection: engine.connection_state().await,
+            book_updates: engine.book_updates.load(Ordering::Relaxed),
+            events: dashboard.recent(EVENT_LOG_LEN),
+        }
+    }
+}
+
+// Run until the user quits (FYI: restores the terminal even if drawing fails)
+pub async fn run_dashboard(engine: Arc<ArbEngine>, dashboard: Arc<Dashboard>) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+
+    let result = dashboard_loop(&mut terminal, &engine, &dashboard).await;
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+async fn dashboard_loop(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    engine: &ArbEngine,
+    dashboard: &Dashboard,
+) -> Result<()> {
+    loop {
+        let state = DashboardState::capture(engine, dashboard).await;
+        terminal.draw(|f| render(f, &state))?;
+
+        // Drain pending keys without blocking the runtime (AFAIK: poll(0) never waits)
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if !handle_key(engine, key, &state.controls).await {
+                    return Ok(());
+                }
+            }
+        }
+
+        sleep(Duration::from_millis(REDRAW_INTERVAL_MS)).await;
+    }
+}
+
+// Returns false when the user wants out
+async fn handle_key(engine: &ArbEngine, key: KeyEvent, controls: &TradingControls) -> bool {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+        KeyCode::Char('q') | KeyCode::Esc => return false,
+        KeyCode::Char('p') | KeyCode::Char(' ') => engine.set_paused(!controls.paused).await,
+        KeyCode::Char('+') | KeyCode::Char('=') => engine.adjust_thresholds(THRESHOLD_STEP, 0.0).await,
+        KeyCode::Char('-') => engine.adjust_thresholds(-THRESHOLD_STEP, 0.0).await,
+        KeyCode::Char(']') => engine.adjust_thresholds(0.0, EDGE_STEP_BPS).await,
+        KeyCode::Char('[') => engine.adjust_thresholds(0.0, -EDGE_STEP_BPS).await,
+        _ => {}
+    }
+    true
+}
+
+fn render(f: &mut Frame, state: &DashboardState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),  // Header
+            Constraint::Length(LADDER_DEPTH as u16 * 2 + 3), // Ladders + sums
+            Constraint::Length(6),  // Sparkline
+            Constraint::Min(8),     // Detections + positions
+            Constraint::Length(8),  // Event log
+            Constraint::Length(1),  // Key help
+        ])
+        .split(f.size());
+
+    render_header(f, rows[0], state);
+
+    let book_cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(35), Constraint::Percentage(30)])
+        .split(rows[1]);
+    match state.view {
+        Some(ref view) => {
+            render_ladder(f, book_cols[0], "UP", &view.up_book.asks, &view.up_book.bids);
+            render_ladder(f, book_cols[1], "DOWN", &view.down_book.asks, &view.down_book.bids);
+            render_sums(f, book_cols[2], &view.price_data, &state.controls);
+        }
+        None => {
+            let waiting = Paragraph::new("Waiting for the first order book update...")
+                .block(Block::default().borders(Borders::ALL).title(" Order books "));
+            f.render_widget(waiting, rows[1]);
+        }
+    }
+
+    render_sparkline(f, rows[2], &state.history);
+
+    let bottom_cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[3]);
+    render_detections(f, bottom_cols[0], &state.detections);
+    render_positions(f, bottom_cols[1], state);
+
+    let events: Vec<ListItem> = state
+        .events
+        .iter()
+        .take(rows[4].height.saturating_sub(2) as usize)
+        .map(|e| ListItem::new(e.as_str()))
+        .collect();
+    f.render_widget(List::new(events).block(Block::default().borders(Borders::ALL).title(" Events ")), rows[4]);
+
+    let help = Paragraph::new(" p: pause/resume   +/-: threshold ±0.001   ]/[: min edge ±5 bps   q: quit")
+        .style(Style::default().fg(Color::DarkGray));
+    f.render_widget(help, rows[5]);
+}
+
+fn render_header(f: &mut Frame, area: Rect, state: &DashboardState) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let market_line = match state.view {
+        Some(ref view) => {
+            let ends = match crate::services::engine::time_until_end_ms(&view.market) {
+                Some(remaining) => {
+                    let remaining = remaining.max(0);
+                    format!("ends in {}m {:02}s", remaining / 60000, (remaining % 60000) / 1000)
+                }
+                None => "end time unknown".to_string(),
+            };
+            format!("{} - {} | {}", view.coin, view.market.slug, ends)
+        }
+        None => "Discovering market...".to_string(),
+    };
+
+    // Socket state first, then book age (BTW: a connected socket can still go quiet)
+    let feed = match (&state.connection, &state.view) {
+        (Some(ConnectionState::Reconnecting { attempt, delay_ms }), _) => Span::styled(
+            format!("● RECONNECTING (attempt {}, {:.0}s backoff)", attempt, *delay_ms as f64 / 1000.0),
+            Style::default().fg(Color::Red),
+        ),
+        (Some(ConnectionState::Stopped), _) => Span::styled("● STOPPED", Style::default().fg(Color::Red)),
+        (Some(ConnectionState::Connected), Some(view)) => {
+            let age = now - view.updated_at;
+            if age > FEED_STALE_MS {
+                Span::styled(format!("● STALE ({:.0}s)", age as f64 / 1000.0), Style::default().fg(Color::Red))
+            } else {
+                Span::styled(format!("● LIVE ({:.1}s)", age as f64 / 1000.0), Style::default().fg(Color::Green))
+            }
+        }
+        (Some(ConnectionState::Connected), None) => Span::styled("● CONNECTED", Style::default().fg(Color::Green)),
+        _ => Span::styled("● CONNECTING", Style::default().fg(Color::Yellow)),
+    };
+
+    let trading = if !state.trading_enabled {
+        Span::styled("DETECTION ONLY", Style::default().fg(Color::Yellow))
+    } else if state.controls.paused {
+        Span::styled("PAUSED", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+    } else {
+        Span::styled("ACTIVE", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(market_line, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+        Line::from(vec![
+            Span::raw("Feed: "),
+            feed,
+            Span::raw(format!(" {} updates | Trading: ", state.book_updates)),
+            trading,
+            Span::raw(format!(
+                " | Threshold {:.4} | Min net edge {:.0} bps",
+                state.controls.arbitrage_threshold, state.controls.min_net_edge_bps
+            )),
+        ]),
+    ];
+    let header = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Polymarket Arbitrage Bot "));
+    f.render_widget(header, area);
+}
+
+// Asks on top (worst to best), bids below (best to worst), like a normal depth ladder
+fn render_ladder(f: &mut Frame, area: Rect, label: &str, asks: &[OrderbookLevel], bids: &[OrderbookLevel]) {
+    let ask_rows = asks.iter().take(LADDER_DEPTH).rev().map(|l| {
+        Row::new(vec![Cell::from(format!("{:.4}", l.price)), Cell::from(format!("{:.2}", l.size))])
+            .style(Style::default().fg(Color::Red))
+    });
+    let bid_rows = bids.iter().take(LADDER_DEPTH).map(|l| {
+        Row::new(vec![Cell::from(format!("{:.4}", l.price)), Cell::from(format!("{:.2}", l.size))])
+            .style(Style::default().fg(Color::Green))
+    });
+
+    let table = Table::new(ask_rows.chain(bid_rows), [Constraint::Percentage(50), Constraint::Percentage(50)])
+        .header(Row::new(vec!["Price", "Size"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(format!(" {} ", label)));
+    f.render_widget(table, area);
+}
+
+fn render_sums(f: &mut Frame, area: Rect, price_data: &PriceData, controls: &TradingControls) {
+    let ask_style = if price_data.ask_sum < controls.arbitrage_threshold {
+        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let bid_style = if price_data.bid_sum > 1.0 {
+        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+
+    let lines = vec![
+        Line::from(format!("UP   bid {:.4}  ask {:.4}", price_data.up_bid, price_data.up_ask)),
+        Line::from(format!("DOWN bid {:.4}  ask {:.4}", price_data.down_bid, price_data.down_ask)),
+        Line::from(""),
+        Line::from(Span::styled(format!("ASK SUM {:.4}", price_data.ask_sum), ask_style)),
+        Line::from(Span::styled(format!("BID SUM {:.4}", price_data.bid_sum), bid_style)),
+        Line::from(format!("Spread  {:+.2}%", price_data.spread * 100.0)),
+    ];
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Sums ")), area);
+}
+
+// Ask-sum history, offset by its minimum (BTW: otherwise 0.98 vs 1.02 is a flat line)
+fn render_sparkline(f: &mut Frame, area: Rect, history: &[PriceData]) {
+    let sums: Vec<f64> = history.iter().map(|p| p.ask_sum).filter(|s| *s > 0.0).collect();
+    let min = sums.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = sums.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let data: Vec<u64> = sums.iter().map(|s| ((s - min) * 10_000.0) as u64 + 1).collect();
+
+    let title = if sums.is_empty() {
+        " Ask sum ".to_string()
+    } else {
+        format!(" Ask sum ({} updates, {:.4} - {:.4}) ", sums.len(), min, max)
+    };
+    let sparkline = Sparkline::default()
+        .data(&data)
+        .style(Style::default().fg(Color::Cyan))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(sparkline, area);
+}
+
+fn render_detections(f: &mut Frame, area: Rect, detections: &[ArbitrageDetection]) {
+    let rows = detections.iter().rev().map(|d| {
+        let time = chrono::DateTime::from_timestamp_millis(d.timestamp)
+            .map(|t| format_local(t, "%H:%M:%S"))
+            .unwrap_or_default();
+        Row::new(vec![
+            time,
+            format!("{:.4}", d.up_ask),
+            format!("{:.4}", d.down_ask),
+            format!("{:.4}", d.ask_sum),
+            format!("{:.2}%", d.spread_percent),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Min(7),
+        ],
+    )
+    .header(Row::new(vec!["Time", "UP", "DOWN", "Sum", "Spread"]).style(Style::default().add_modifier(Modifier::BOLD)))
+    .block(Block::default().borders(Borders::ALL).title(" Recent detections "));
+    f.render_widget(table, area);
+}
+
+fn render_positions(f: &mut Frame, area: Rect, state: &DashboardState) {
+    let rows = state.positions.iter().map(|p| {
+        Row::new(vec![p.market_slug.clone(), format!("{:.2}", p.sets), format!("${:.2}", p.cost_usdc)])
+    });
+    let pnl_color = if state.realized_pnl >= 0.0 { Color::Green } else { Color::Red };
+    let title = Line::from(vec![
+        Span::raw(format!(
+            " Positions | budget ${:.2} | deployed ${:.2} | PnL ",
+            state.available_budget, state.deployed_usdc
+        )),
+        Span::styled(format!("${:+.2} ", state.realized_pnl), Style::default().fg(pnl_color)),
+    ]);
+    let table = Table::new(rows, [Constraint::Min(20), Constraint::Length(8), Constraint::Length(10)])
+        .header(Row::new(vec!["Market", "Sets", "Cost"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(table, area);
+}
@@ -0,0 +1,186 @@
+use crate::config::Env;
+use crate::services::engine::{EngineEvent, Frontend};
+use crate::services::event_scanner::print_event_arbitrage;
+use crate::services::market_discovery::CoinMarket;
+use crate::services::price_monitor::{display_coin_details, PriceData, PriceMonitor};
+use crate::services::websocket_client::ConnectionState;
+use crate::utils::logger::format_local;
+use colored::*;
+
+// Colored banners + live coin view (FYI: the original interactive output)
+pub struct TerminalFrontend {
+    env: Env,
+    redraw: bool, // Clear and redraw the coin view on every book update
+}
+
+impl TerminalFrontend {
+    pub fn new(env: Env, redraw: bool) -> Self {
+        Self { env, redraw }
+    }
+}
+
+fn client_name(client: &str) -> &'static str {
+    match client {
+        "clob" => "ClobClient",
+        "funds" => "Funds guard",
+        _ => "CTF client",
+    }
+}
+
+impl Frontend for TerminalFrontend {
+    fn event(&self, event: &EngineEvent) {
+        match event {
+            EngineEvent::Discovering { coin } => {
+                println!("{}", format!("\n🔍 Discovering market for {}...\n", coin).cyan());
+            }
+            EngineEvent::ClientReady { client } => {
+                let name = client_name(client);
+                println!("{}", format!("✓ {} initialized\n", name).green());
+            }
+            EngineEvent::ClientUnavailable { client, error, impact } => {
+                let name = client_name(client);
+                println!("{}", format!("⚠️  Warning: Failed to initialize {}: {}\n", name, error).yellow());
+                println!("{}", format!("{}\n", impact).yellow());
+            }
+            EngineEvent::RecorderStarted { dir } => {
+                println!("{}", format!("Recording WebSocket traffic to {}/\n", dir).bright_black());
+            }
+            EngineEvent::MarketFound { slug, .. } => {
+                println!("{}", format!("✓ Market found: {}\n", slug).green());
+            }
+            EngineEvent::NoMarket { coin } => {
+                println!("{}", format!("⚠️  No active market found for {}. Will retry in 10 seconds...\n", coin).yellow());
+            }
+            EngineEvent::MarketClosed { coin, slug, end_date } => {
+                println!(
+                    "{}",
+                    format!(
+                        "\n\n╔════════════════════════════════════════════════════════════════╗\n║                    MARKET CLOSED                                 ║\n╚════════════════════════════════════════════════════════════════╝\n  Market: {}\n  Coin: {}\n  End Time: {}\n  Status: Searching for next market...\n\n",
+                        slug, coin, end_date
+                    )
+                    .yellow()
+                    .bold()
+                );
+            }
+            EngineEvent::ClosingSoon { coin, seconds_remaining } => {
+                println!(
+                    "{}",
+                    format!(
+                        "\n⚠️  MARKET CLOSING SOON - {}\n   {} seconds remaining. Last chance to trade!\n",
+                        coin, seconds_remaining
+                    )
+                    .yellow()
+                    .bold()
+                );
+            }
+            EngineEvent::ArbitrageDetected {
+                coin,
+                up_ask,
+                down_ask,
+                ask_sum,
+                spread_pct,
+                net_edge_bps,
+                sets,
+                fees_usdc,
+                gas_usdc,
+                ..
+            } => {
+                let timestamp = format_local(chrono::Utc::now(), "%H:%M:%S %Z");
+                println!(
+                    "{}",
+                    format!(
+                        "\n⚡ [{}] ARBITRAGE DETECTED - {}\n   UP_ASK: {:.4} + DOWN_ASK: {:.4} = {:.4}\n   Spread: {:.2}%\n   Net edge: {:.1} bps/set ({:.2} sets, fees ${:.4}, gas ${:.4})\n",
+                        timestamp, coin, up_ask, down_ask, ask_sum, spread_pct, net_edge_bps, sets, fees_usdc, gas_usdc
+                    )
+                    .green()
+                    .bold()
+                );
+            }
+            EngineEvent::SellArbitrageDetected { coin, up_bid, down_bid, bid_sum, spread_pct, .. } => {
+                let timestamp = format_local(chrono::Utc::now(), "%H:%M:%S %Z");
+                println!(
+                    "{}",
+                    format!(
+                        "\n⚡ [{}] SELL ARBITRAGE DETECTED - {}\n   UP_BID: {:.4} + DOWN_BID: {:.4} = {:.4}\n   Spread: {:.2}%\n",
+                        timestamp, coin, up_bid, down_bid, bid_sum, spread_pct
+                    )
+                    .green()
+                    .bold()
+                );
+            }
+            EngineEvent::QuotesUpdated { coin, up_bid, down_bid, sets } => {
+                println!(
+                    "{}",
+                    format!(
+                        "📌 {} quotes: UP ${:.2} + DOWN ${:.2} = ${:.2} ({:.2} sets each)",
+                        coin,
+                        up_bid,
+                        down_bid,
+                        up_bid + down_bid,
+                        sets
+                    )
+                    .bright_black()
+                );
+            }
+            EngineEvent::TradeSkipped { reason, .. } => {
+                println!("{}", format!("⚠️  Trade skipped: {}", reason).yellow());
+            }
+            // Executor already printed the order-by-order details
+            EngineEvent::TradeResult { .. } => {}
+            EngineEvent::PositionSettled { available_budget, .. } => {
+                println!("{}", format!("   Available budget: ${:.2} USDC\n", available_budget).bright_black());
+            }
+            EngineEvent::ControlsChanged { paused, arbitrage_threshold, min_net_edge_bps } => {
+                println!(
+                    "{}",
+                    format!(
+                        "Trading {} | Threshold: {:.4} | Min net edge: {:.0} bps",
+                        if *paused { "PAUSED" } else { "ACTIVE" },
+                        arbitrage_threshold,
+                        min_net_edge_bps
+                    )
+                    .cyan()
+                );
+            }
+            EngineEvent::ConnectionChanged { state } => match state {
+                ConnectionState::Connected => println!("{}", "✓ WebSocket connected".green()),
+                ConnectionState::Reconnecting { attempt, delay_ms } => println!(
+                    "{}",
+                    format!("⚠️  WebSocket disconnected, reconnect attempt {} in {}ms", attempt, delay_ms).yellow()
+                ),
+                ConnectionState::Stopped => eprintln!("{}", "WebSocket stopped".red()),
+                _ => {}
+            },
+            EngineEvent::EventArbitrage(arb) => print_event_arbitrage(arb),
+            EngineEvent::Error { message } => {
+                eprintln!("{}", format!("Error: {}", message).red());
+            }
+            EngineEvent::Shutdown => {
+                println!("{}", "\nShutting down...".yellow());
+            }
+        }
+    }
+
+    fn book_update(&self, coin: &str, price_data: &PriceData, market: &CoinMarket, monitor: &PriceMonitor) {
+        if self.redraw {
+            display_coin_details(coin, price_data, market, monitor, &self.env);
+        }
+    }
+}
+
+// One JSON object per line on stdout (IMO: what log shippers and systemd want)
+pub struct JsonFrontend;
+
+impl Frontend for JsonFrontend {
+    fn event(&self, event: &EngineEvent) {
+        let mut line = serde_json::json!({
+            "ts": chrono::Utc::now().to_rfc3339(),
+            "level": event.level(),
+        });
+        // Merge the event's own fields next to ts/level (FYI: keeps the line flat for jq)
+        if let (Some(obj), Ok(serde_json::Value::Object(fields))) = (line.as_object_mut(), serde_json::to_value(event)) {
+            obj.extend(fields);
+        }
+        println!("{}", line);
+    }
+}
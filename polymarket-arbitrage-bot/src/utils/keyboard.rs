@@ -42,24 +42,14 @@ impl KeyboardHandler {
             Event::Key(KeyEvent {
                 code,
                 kind: KeyEventKind::Press,
+                modifiers,
                 ..
             }) => match code {
                 KeyCode::Up => Ok(KeyAction::Up),
                 KeyCode::Down => Ok(KeyAction::Down),
                 KeyCode::Enter => Ok(KeyAction::Enter),
-                KeyCode::Char('c') => {
-                    // Check for Ctrl+C
-                    if crossterm::event::read()? == Event::Key(KeyEvent {
-                        code: KeyCode::Char('c'),
-                        kind: KeyEventKind::Press,
-                        modifiers: event::KeyModifiers::CONTROL,
-                        ..
-                    }) {
-                        Ok(KeyAction::Exit)
-                    } else {
-                        Ok(KeyAction::None)
-                    }
-                }
+                // Ctrl+C (FYI: raw mode swallows SIGINT, so it arrives as a key)
+                KeyCode::Char('c') if modifiers.contains(event::KeyModifiers::CONTROL) => Ok(KeyAction::Exit),
                 _ => Ok(KeyAction::None),
             },
             _ => Ok(KeyAction::None),
@@ -1,105 +1,286 @@
-use chrono::{DateTime, Utc};
+use crate::config::Env;
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
 
-// Lazy static file paths and handles (FYI: created once, reused)
-lazy_static::lazy_static! {
-    static ref MONITOR_LOG_PATH: PathBuf = std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join("monitor.log");
-    static ref ERROR_LOG_PATH: PathBuf = std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join("error.log");
-    static ref MONITOR_FILE: Mutex<Option<File>> = Mutex::new(None); // Cached file handle
-    static ref ERROR_FILE: Mutex<Option<File>> = Mutex::new(None); // Cached file handle
+// Console banner switch (FYI: headless mode turns it off so stdout stays pure JSON)
+static CONSOLE_ENABLED: AtomicBool = AtomicBool::new(true);
+
+pub fn set_console_enabled(enabled: bool) {
+    CONSOLE_ENABLED.store(enabled, Ordering::Relaxed);
 }
 
-// Ensure log files exist (AFAIK: creates empty files if missing)
-fn ensure_log_files() {
-    let _ = std::fs::File::create(&*MONITOR_LOG_PATH);
-    let _ = std::fs::File::create(&*ERROR_LOG_PATH);
+pub fn console_enabled() -> bool {
+    CONSOLE_ENABLED.load(Ordering::Relaxed)
 }
 
-// Format timestamp in EST (BTW: UTC-5, no DST handling)
-fn format_log_timestamp() -> String {
-    let now = Utc::now();
-    // Convert to EST (UTC-5)
-    let est_offset = chrono::Duration::hours(-5);
-    let est_time = now + est_offset;
-    est_time.format("%Y-%m-%d %H:%M:%S EST").to_string()
+// println! that respects the console switch (BTW: use for banners, not for structured output)
+#[macro_export]
+macro_rules! console_println {
+    ($($arg:tt)*) => {
+        if $crate::utils::logger::console_enabled() {
+            println!($($arg)*);
+        }
+    };
 }
 
-// Log price data to monitor.log (FYI: CSV format for easy parsing)
-pub fn log_monitor_data(data: MonitorData) {
-    ensure_log_files();
-    
-    let mut file_guard = MONITOR_FILE.lock().unwrap();
-    if file_guard.is_none() {
-        if let Ok(file) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&*MONITOR_LOG_PATH)
-        {
-            *file_guard = Some(file);
+const MONITOR_HEADER: &str = "Time,Bid UP,Bid DOWN,Bid Sum,Ask UP,Ask DOWN,Ask Sum\n";
+
+// Where and how log files are written (FYI: defaults apply until init_logging runs)
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub dir: PathBuf, // monitor.log and error.log live here
+    pub timezone: Tz, // Timestamps in files and banners
+    pub rotate_bytes: u64, // Rotate once a file would grow past this (0 = no size limit)
+    pub rotate_daily: bool, // Rotate when the local date changes
+    pub max_files: usize, // Rotated files kept per log (0 = keep all)
+    pub json: bool, // One JSON object per line instead of text/CSV
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            timezone: Tz::UTC,
+            rotate_bytes: 0,
+            rotate_daily: false,
+            max_files: 0,
+            json: false,
         }
     }
-    
-    if let Some(ref mut file) = *file_guard {
-        let log_line = format!(
-            "{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4}\n",
-            data.time, data.bid_up, data.bid_down, data.bid_sum,
-            data.ask_up, data.ask_down, data.ask_sum
-        );
-        let _ = file.write_all(log_line.as_bytes());
-        let _ = file.flush();
+}
+
+impl LogConfig {
+    // Bad values fall back to UTC / text and are returned so the caller can log them
+    pub fn from_env(env: &Env) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let timezone = env.log_timezone.parse::<Tz>().unwrap_or_else(|_| {
+            problems.push(format!(
+                "Unknown LOG_TIMEZONE '{}' (expected an IANA name like America/New_York), using UTC",
+                env.log_timezone
+            ));
+            Tz::UTC
+        });
+        let json = match env.log_file_format.to_lowercase().as_str() {
+            "json" => true,
+            "text" => false,
+            _ => {
+                problems.push(format!("Unknown LOG_FILE_FORMAT '{}' (expected text or json), using text", env.log_file_format));
+                false
+            }
+        };
+        let config = Self {
+            dir: PathBuf::from(&env.log_dir),
+            timezone,
+            rotate_bytes: env.log_rotate_mb * 1024 * 1024,
+            rotate_daily: env.log_rotate_daily,
+            max_files: env.log_max_files,
+            json,
+        };
+        (config, problems)
     }
 }
 
-pub fn log_error(error: &str, context: Option<&str>) {
-    ensure_log_files();
-    
-    let mut file_guard = ERROR_FILE.lock().unwrap();
-    if file_guard.is_none() {
-        if let Ok(file) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&*ERROR_LOG_PATH)
-        {
-            *file_guard = Some(file);
+// One append-only log file plus what rotation needs to know about it
+struct LogFile {
+    name: &'static str, // File stem, e.g. "monitor" -> monitor.log, monitor-<ts>.log
+    file: Option<File>,
+    size: u64,
+    day: String, // Local date the current file was started on
+}
+
+impl LogFile {
+    const fn new(name: &'static str) -> Self {
+        Self { name, file: None, size: 0, day: String::new() }
+    }
+
+    fn path(&self, config: &LogConfig) -> PathBuf {
+        config.dir.join(format!("{}.log", self.name))
+    }
+
+    // Open for append, never truncate (BTW: size and day come from the existing file so rotation survives restarts)
+    fn open(&mut self, config: &LogConfig) {
+        let _ = std::fs::create_dir_all(&config.dir);
+        let path = self.path(config);
+        let file = match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+        let metadata = file.metadata().ok();
+        self.size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        self.day = metadata
+            .and_then(|m| m.modified().ok())
+            .filter(|_| self.size > 0)
+            .map(|t| local_date(DateTime::<Utc>::from(t), config))
+            .unwrap_or_else(|| local_date(Utc::now(), config));
+        self.file = Some(file);
+
+        // CSV needs its header at the top of every file
+        if self.size == 0 && self.name == "monitor" && !config.json {
+            self.append(MONITOR_HEADER);
         }
     }
-    
-    if let Some(ref mut file) = *file_guard {
-        let timestamp = format_log_timestamp();
-        let context_str = context.map(|c| format!(" [{}]", c)).unwrap_or_default();
-        let log_line = format!("[{}]{} {}\n", timestamp, context_str, error);
-        let _ = file.write_all(log_line.as_bytes());
-        let _ = file.flush();
+
+    fn append(&mut self, line: &str) {
+        if let Some(ref mut file) = self.file {
+            let _ = file.write_all(line.as_bytes());
+            let _ = file.flush();
+            self.size += line.len() as u64;
+        }
+    }
+
+    fn write_line(&mut self, config: &LogConfig, line: &str) {
+        if self.file.is_none() {
+            self.open(config);
+        }
+        let new_day = config.rotate_daily && self.day != local_date(Utc::now(), config);
+        let too_big = config.rotate_bytes > 0 && self.size + line.len() as u64 > config.rotate_bytes;
+        if self.size > 0 && (new_day || too_big) {
+            self.rotate(config);
+        }
+        self.append(line);
+    }
+
+    // monitor.log -> monitor-20250101-120000.000.log, then a fresh monitor.log
+    fn rotate(&mut self, config: &LogConfig) {
+        self.file = None;
+        let stamp = Utc::now().with_timezone(&config.timezone).format("%Y%m%d-%H%M%S%.3f");
+        let rotated = config.dir.join(format!("{}-{}.log", self.name, stamp));
+        let _ = std::fs::rename(self.path(config), rotated);
+        if config.max_files > 0 {
+            prune_rotated(&config.dir, self.name, config.max_files);
+        }
+        self.open(config);
     }
 }
 
-pub fn clear_log_files() {
-    let header = "Time,Bid UP,Bid DOWN,Bid Sum,Ask UP,Ask DOWN,Ask Sum\n";
-    let _ = std::fs::write(&*MONITOR_LOG_PATH, header);
-    let _ = std::fs::write(&*ERROR_LOG_PATH, "");
-    
-    *MONITOR_FILE.lock().unwrap() = None;
-    *ERROR_FILE.lock().unwrap() = None;
+// Rotated files for one log, oldest first (AFAIK: timestamped names sort correctly)
+fn list_rotated(dir: &Path, name: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}-", name);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".log"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
 }
 
-pub fn init_monitor_log() {
-    ensure_log_files();
-    
-    let header = "Time,Bid UP,Bid DOWN,Bid Sum,Ask UP,Ask DOWN,Ask Sum\n";
-    if let Ok(content) = std::fs::read_to_string(&*MONITOR_LOG_PATH) {
-        if content.trim().is_empty() {
-            let _ = std::fs::write(&*MONITOR_LOG_PATH, header);
+fn prune_rotated(dir: &Path, name: &str, max_files: usize) {
+    let files = list_rotated(dir, name);
+    if files.len() > max_files {
+        for old in &files[..files.len() - max_files] {
+            let _ = std::fs::remove_file(old);
         }
     }
 }
 
+// Lazy static config and handles (FYI: created once, reused)
+lazy_static::lazy_static! {
+    static ref LOG_CONFIG: RwLock<LogConfig> = RwLock::new(LogConfig::default());
+    static ref MONITOR_FILE: Mutex<LogFile> = Mutex::new(LogFile::new("monitor"));
+    static ref ERROR_FILE: Mutex<LogFile> = Mutex::new(LogFile::new("error"));
+}
+
+// Apply LOG_* settings (BTW: call once at startup, before anything is logged)
+pub fn init_logging(env: &Env) {
+    let (config, problems) = LogConfig::from_env(env);
+    *LOG_CONFIG.write().unwrap() = config;
+    *MONITOR_FILE.lock().unwrap() = LogFile::new("monitor");
+    *ERROR_FILE.lock().unwrap() = LogFile::new("error");
+    for problem in problems {
+        log_error(&problem, Some("logger"));
+    }
+}
+
+pub fn log_config() -> LogConfig {
+    LOG_CONFIG.read().unwrap().clone()
+}
+
+pub fn monitor_log_path() -> PathBuf {
+    log_config().dir.join("monitor.log")
+}
+
+pub fn error_log_path() -> PathBuf {
+    log_config().dir.join("error.log")
+}
+
+fn local_date(time: DateTime<Utc>, config: &LogConfig) -> String {
+    time.with_timezone(&config.timezone).format("%Y-%m-%d").to_string()
+}
+
+// Format a UTC time in the configured timezone (FYI: %Z gives the abbreviation, e.g. EST/EDT)
+pub fn format_local(time: DateTime<Utc>, format: &str) -> String {
+    let timezone = LOG_CONFIG.read().unwrap().timezone;
+    time.with_timezone(&timezone).format(format).to_string()
+}
+
+fn format_log_timestamp(config: &LogConfig) -> String {
+    let now = Utc::now().with_timezone(&config.timezone);
+    if config.json {
+        return now.to_rfc3339_opts(SecondsFormat::Millis, false);
+    }
+    now.format("%Y-%m-%d %H:%M:%S %Z").to_string()
+}
+
+// Log price data to monitor.log (FYI: CSV format for easy parsing, or JSON lines)
+pub fn log_monitor_data(data: MonitorData) {
+    let config = log_config();
+    let log_line = if config.json {
+        format!(
+            "{}\n",
+            serde_json::json!({
+                "ts": format_log_timestamp(&config),
+                "time": data.time,
+                "bid_up": data.bid_up,
+                "bid_down": data.bid_down,
+                "bid_sum": data.bid_sum,
+                "ask_up": data.ask_up,
+                "ask_down": data.ask_down,
+                "ask_sum": data.ask_sum,
+            })
+        )
+    } else {
+        format!(
+            "{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4}\n",
+            data.time, data.bid_up, data.bid_down, data.bid_sum,
+            data.ask_up, data.ask_down, data.ask_sum
+        )
+    };
+    MONITOR_FILE.lock().unwrap().write_line(&config, &log_line);
+}
+
+pub fn log_error(error: &str, context: Option<&str>) {
+    let config = log_config();
+    let timestamp = format_log_timestamp(&config);
+    let log_line = if config.json {
+        format!(
+            "{}\n",
+            serde_json::json!({
+                "ts": timestamp,
+                "level": "error",
+                "context": context,
+                "message": error,
+            })
+        )
+    } else {
+        let context_str = context.map(|c| format!(" [{}]", c)).unwrap_or_default();
+        format!("[{}]{} {}\n", timestamp, context_str, error)
+    };
+    ERROR_FILE.lock().unwrap().write_line(&config, &log_line);
+}
+
 #[derive(Debug, Clone)]
 pub struct MonitorData {
     pub time: String,
@@ -110,4 +291,3 @@ pub struct MonitorData {
     pub ask_down: f64,
     pub ask_sum: f64,
 }
-
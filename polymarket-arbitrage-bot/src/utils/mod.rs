@@ -1,8 +1,12 @@
 pub mod coin_selector;
+pub mod dashboard;
+pub mod frontend;
 pub mod keyboard;
 pub mod logger;
 
 pub use coin_selector::*;
+pub use dashboard::*;
+pub use frontend::*;
 pub use keyboard::*;
 pub use logger::*;
 
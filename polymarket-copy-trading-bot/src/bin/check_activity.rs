@@ -1,5 +1,5 @@
 use anyhow::Result;
-use polymarket_copy_rust::{fetch_data, EnvConfig};
+use polymarket_copy_rust::{fetch_data, Db, EnvConfig, Logger};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -25,6 +25,57 @@ struct Activity {
     outcome: Option<String>,
 }
 
+// Trades the bot saw but didn't copy (market filters, per-trader settings), newest first
+async fn print_skipped_trades(config: &EnvConfig) {
+    let db = match Db::connect(&config.mongo_uri).await {
+        Ok(db) => db,
+        Err(e) => {
+            println!("⚠️  Cannot load skipped trades from MongoDB: {}\n", e);
+            return;
+        }
+    };
+
+    println!("═══════════════════════════════════════════════════════════════");
+    println!("⏭️  SKIPPED TRADES (not copied)");
+    println!("═══════════════════════════════════════════════════════════════\n");
+
+    let mut any = false;
+    for addr in &config.user_addresses {
+        let skipped = match db.find_skipped_trades(addr, 10).await {
+            Ok(s) => s,
+            Err(e) => {
+                println!("⚠️  {}: {}\n", Logger::format_address(addr), e);
+                continue;
+            }
+        };
+        for trade in skipped {
+            any = true;
+            let ts = trade.timestamp.unwrap_or(0);
+            let ts = if ts > 1_000_000_000_000 { ts / 1000 } else { ts };
+            let date = chrono::DateTime::from_timestamp(ts, 0)
+                .unwrap_or_default()
+                .format("%Y-%m-%d %H:%M:%S");
+            println!(
+                "⏭️  {} {} ${:.2} @ ${:.4} - {}",
+                Logger::format_address(addr),
+                trade.side.as_deref().unwrap_or("?"),
+                trade.usdc_size.unwrap_or(0.0),
+                trade.price.unwrap_or(0.0),
+                trade
+                    .title
+                    .as_deref()
+                    .or(trade.slug.as_deref())
+                    .unwrap_or("Unknown")
+            );
+            println!("   🚫 Reason: {}", trade.skip_reason.as_deref().unwrap_or("-"));
+            println!("   📅 Date: {}\n", date);
+        }
+    }
+    if !any {
+        println!("✅ No skipped trades\n");
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
@@ -32,6 +83,8 @@ async fn main() -> Result<()> {
     let config = EnvConfig::from_env().await?;
     let wallet = &config.proxy_wallet;
 
+    print_skipped_trades(&config).await;
+
     let client = reqwest::Client::new();
     let url = format!(
         "https://data-api.polymarket.com/activity?user={}&type=TRADE",
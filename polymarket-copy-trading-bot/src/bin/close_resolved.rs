@@ -0,0 +1,59 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::{arg_value, fetch_positions, is_dry_run, sell_positions, theme::colors};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Sell positions whose outcome is effectively decided (price within --threshold of 0 or 1)
+// while the book is still open; markets already redeemable are left to redeem_resolved
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let dry_run = is_dry_run();
+
+    let threshold: f64 = arg_value("--threshold")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.01);
+    if !(0.0..0.5).contains(&threshold) {
+        anyhow::bail!("--threshold must be in [0, 0.5), got {}", threshold);
+    }
+
+    println!();
+    println!(
+        "{} Close resolved positions (price ≥ {:.2} or ≤ {:.2}){}{}",
+        colors::ACCENT,
+        1.0 - threshold,
+        threshold,
+        if dry_run { " (dry run)" } else { "" },
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let positions = fetch_positions(&config, &http_client, &config.proxy_wallet).await?;
+
+    let mut redeemable = 0;
+    let mut plan = Vec::new();
+    for p in positions {
+        if p.redeemable.unwrap_or(false) {
+            redeemable += 1;
+            continue;
+        }
+        let price = p.cur_price.unwrap_or(0.5);
+        if price >= 1.0 - threshold || price <= threshold {
+            let size = p.size.unwrap_or(0.0);
+            plan.push((p, size));
+        }
+    }
+    if redeemable > 0 {
+        Logger::info(&format!(
+            "{} position(s) already redeemable - run redeem_resolved for those",
+            redeemable
+        ));
+    }
+
+    if plan.is_empty() {
+        Logger::success("No resolved positions to close");
+        return Ok(());
+    }
+    sell_positions(&config, &http_client, &plan, dry_run).await
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::{
+    arg_value, fetch_positions, is_dry_run, position_label, sell_positions, theme::colors,
+};
+use polymarket_copy_rust::{EnvConfig, Logger};
+use std::collections::HashSet;
+
+// Close positions none of the copied traders hold any more (their exit was missed or skipped)
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let dry_run = is_dry_run();
+
+    // Ignore dust that can't be sold anyway
+    let min_value: f64 = arg_value("--min-value")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1.0);
+
+    println!();
+    println!(
+        "{} Close stale positions{}{}",
+        colors::ACCENT,
+        if dry_run { " (dry run)" } else { "" },
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+
+    // Every trader has to load, or a failed fetch would make their positions look stale
+    let mut held_by_traders = HashSet::new();
+    for trader in &config.user_addresses {
+        let positions = fetch_positions(&config, &http_client, trader).await.map_err(|e| {
+            anyhow::anyhow!("Failed to fetch positions for {}: {}", Logger::format_address(trader), e)
+        })?;
+        held_by_traders.extend(positions.into_iter().filter_map(|p| p.asset));
+    }
+
+    let mine = fetch_positions(&config, &http_client, &config.proxy_wallet).await?;
+    let mut plan = Vec::new();
+    for p in mine {
+        let Some(ref asset) = p.asset else {
+            continue;
+        };
+        if held_by_traders.contains(asset) {
+            continue;
+        }
+        if p.redeemable.unwrap_or(false) {
+            Logger::info(&format!(
+                "{} is resolved - use redeem_resolved instead",
+                position_label(&p)
+            ));
+            continue;
+        }
+        if p.current_value.unwrap_or(0.0) < min_value {
+            continue;
+        }
+        let size = p.size.unwrap_or(0.0);
+        plan.push((p, size));
+    }
+
+    if plan.is_empty() {
+        Logger::success("No stale positions");
+        return Ok(());
+    }
+    sell_positions(&config, &http_client, &plan, dry_run).await
+}
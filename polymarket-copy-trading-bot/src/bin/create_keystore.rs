@@ -0,0 +1,59 @@
+use anyhow::Result;
+use polymarket_copy_rust::config::{
+    create_keystore, keystore_path, read_keystore_password, DEFAULT_KEYSTORE_PATH,
+};
+use polymarket_copy_rust::utils::theme::colors;
+use std::env;
+use std::path::Path;
+
+fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    println!();
+    println!(
+        "{}━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
+        colors::ACCENT
+    );
+    println!("     POLYMARKET BOT — CREATE KEYSTORE");
+    println!(
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━{}",
+        colors::RESET
+    );
+    println!();
+
+    let path = keystore_path();
+
+    // Key from .env if it's still there, otherwise ask for it (never echoed)
+    let private_key = match env::var("PRIVATE_KEY") {
+        Ok(k) if !k.trim().is_empty() => k,
+        _ => rpassword::prompt_password("Private key (hex): ")?,
+    };
+
+    let password = read_keystore_password("New keystore passphrase: ")?;
+    if env::var("KEYSTORE_PASSWORD").is_err() && env::var("KEYSTORE_PASSWORD_FILE").is_err() {
+        let confirm = rpassword::prompt_password("Repeat passphrase: ")?;
+        if confirm != password {
+            anyhow::bail!("Passphrases do not match");
+        }
+    }
+    if password.len() < 8 {
+        anyhow::bail!("Passphrase must be at least 8 characters");
+    }
+
+    let address = create_keystore(Path::new(&path), &private_key, &password)?;
+    println!(
+        "{} Keystore written to {} (signer {}){}",
+        colors::SUCCESS,
+        path,
+        address,
+        colors::RESET
+    );
+    println!();
+    println!("  Next steps:");
+    println!("  1. Remove PRIVATE_KEY from .env (and any copies of it)");
+    println!("  2. Set KEYSTORE_PATH={} (optional if it's ./{})", path, DEFAULT_KEYSTORE_PATH);
+    println!("  3. Provide the passphrase via KEYSTORE_PASSWORD / KEYSTORE_PASSWORD_FILE, or type it at startup");
+    println!("  4. Run: make scrub-private-keys to delete keys older versions stored in MongoDB");
+    println!();
+    Ok(())
+}
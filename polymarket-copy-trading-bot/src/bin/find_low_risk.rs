@@ -0,0 +1,69 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::scanner::{
+    analyze_traders, gather_candidates, print_traders, write_scan_result, ScanOptions, ScanResult,
+};
+use polymarket_copy_rust::utils::{arg_value, theme::colors};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Like find_traders, but only steady wallets: shallow drawdowns, spread over many markets
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let opts = ScanOptions::from_args();
+    let num = |name: &str, default: f64| {
+        arg_value(name)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(default)
+    };
+    let max_drawdown = num("--max-drawdown", 20.0);
+    let min_sharpe = num("--min-sharpe", 1.0);
+    let min_win_rate = num("--min-win-rate", 55.0);
+    let max_concentration = num("--max-concentration", 0.25);
+
+    println!();
+    println!(
+        "{} Find low-risk traders (drawdown ≤ {:.0}%, Sharpe ≥ {:.1}, win rate ≥ {:.0}%){}",
+        colors::ACCENT,
+        max_drawdown,
+        min_sharpe,
+        min_win_rate,
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let candidates = gather_candidates(&config, &http_client, &opts).await?;
+    Logger::info(&format!("Analyzing {} wallets...", candidates.len()));
+    let scanned = analyze_traders(&config, &http_client, &candidates, opts.history_days).await;
+
+    let mut best: Vec<_> = scanned
+        .into_iter()
+        .filter(|t| {
+            t.trades >= opts.min_trades
+                && t.closed_positions >= 5
+                && t.roi > 0.0
+                && t.max_drawdown <= max_drawdown
+                && t.sharpe >= min_sharpe
+                && t.win_rate >= min_win_rate
+                && t.market_concentration <= max_concentration
+        })
+        .collect();
+    best.sort_by(|a, b| b.sharpe.total_cmp(&a.sharpe));
+    best.truncate(opts.top);
+
+    Logger::separator();
+    if best.is_empty() {
+        Logger::warning("No trader passed the risk filters - loosen them or scan more --candidates");
+        return Ok(());
+    }
+    print_traders(&best, opts.top);
+
+    let path = write_scan_result(
+        "top_traders_results",
+        "low_risk",
+        &ScanResult::new("find_low_risk", opts.history_days, best),
+    )?;
+    Logger::success(&format!("Saved {}", path.display()));
+    Ok(())
+}
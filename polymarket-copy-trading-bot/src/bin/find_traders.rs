@@ -0,0 +1,57 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::scanner::{
+    analyze_traders, gather_candidates, print_traders, write_scan_result, ScanOptions, ScanResult,
+};
+use polymarket_copy_rust::utils::theme::colors;
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Score leaderboard & big-trade wallets and keep the best ones worth copying
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let opts = ScanOptions::from_args();
+
+    println!();
+    println!(
+        "{} Find traders ({} candidates, {} days){}",
+        colors::ACCENT,
+        opts.candidates,
+        opts.history_days,
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let candidates = gather_candidates(&config, &http_client, &opts).await?;
+    Logger::info(&format!("Analyzing {} wallets...", candidates.len()));
+    let scanned = analyze_traders(&config, &http_client, &candidates, opts.history_days).await;
+
+    // Enough closed positions to trust the win rate, and actually making money
+    let mut best: Vec<_> = scanned
+        .into_iter()
+        .filter(|t| t.trades >= opts.min_trades && t.closed_positions >= 5 && t.roi > 0.0)
+        .collect();
+    best.sort_by(|a, b| b.roi.total_cmp(&a.roi));
+    best.truncate(opts.top);
+
+    Logger::separator();
+    if best.is_empty() {
+        Logger::warning("No trader passed the filters - try more --candidates or fewer --min-trades");
+        return Ok(());
+    }
+    print_traders(&best, opts.top);
+    for t in &best {
+        if config.user_addresses.contains(&t.address) {
+            Logger::info(&format!("{} is already in USER_ADDRESSES", t.address));
+        }
+    }
+
+    let path = write_scan_result(
+        "top_traders_results",
+        "top_traders",
+        &ScanResult::new("find_traders", opts.history_days, best),
+    )?;
+    Logger::success(&format!("Saved {}", path.display()));
+    Ok(())
+}
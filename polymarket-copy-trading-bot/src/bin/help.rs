@@ -27,37 +27,40 @@ fn main() {
     println!("  {green}make set-token-allowance{reset} Set USDC approval (stub)");
     println!("  {green}make check-proxy{reset}        Check proxy wallet (stub)");
     println!("  {green}make check-both{reset}         Check both wallets (stub)");
+    println!("  {green}make create-keystore{reset}    Encrypt the private key into keystore.json");
+    println!("  {green}make scrub-private-keys{reset} Delete plaintext keys stored in MongoDB");
     println!();
 
     println!("{yellow}MONITORING & STATS{reset}\n");
     println!("  {green}make check-stats{reset}       Trading stats (stub)");
-    println!("  {green}make check-activity{reset}    Recent activity (stub)");
+    println!("  {green}make check-activity{reset}    Recent activity & skipped trades");
     println!("  {green}make check-pnl{reset}         PnL discrepancy (stub)");
     println!();
 
     println!("{yellow}POSITION MANAGEMENT{reset}\n");
-    println!("  {green}make manual-sell{reset}       Manually sell position (stub)");
-    println!("  {green}make sell-large{reset}         Sell large positions (stub)");
-    println!("  {green}make close-stale{reset}        Close stale positions (stub)");
-    println!("  {green}make close-resolved{reset}     Close resolved (stub)");
-    println!("  {green}make redeem-resolved{reset}   Redeem resolved (stub)");
+    println!("  {green}make manual-sell{reset}       Sell all or part of one position");
+    println!("  {green}make sell-large{reset}         Trim positions above a max value");
+    println!("  {green}make close-stale{reset}        Close positions traders have exited");
+    println!("  {green}make close-resolved{reset}     Sell positions priced near 0 or 1");
+    println!("  {green}make redeem-resolved{reset}   Redeem resolved positions on-chain");
     println!("  {green}make transfer-to-gnosis{reset} Transfer to Gnosis Safe (stub)");
+    println!("  Pass options with ARGS, e.g. make manual-sell ARGS=\"bitcoin --percent 50 --dry-run\"");
     println!();
 
     println!("{yellow}TRADER RESEARCH{reset}\n");
-    println!("  {green}make find-traders{reset}      Find best traders (stub)");
-    println!("  {green}make find-low-risk{reset}      Low-risk traders (stub)");
-    println!("  {green}make scan-traders{reset}       Scan traders (stub)");
+    println!("  {green}make find-traders{reset}      Find best traders to copy");
+    println!("  {green}make find-low-risk{reset}      Low-risk traders (drawdown, Sharpe)");
+    println!("  {green}make scan-traders{reset}       Score candidate or given traders");
     println!("  {green}make scan-markets{reset}       Scan from markets (stub)");
     println!();
 
     println!("{yellow}SIMULATION{reset}\n");
-    println!("  {green}make simulate{reset}           Simulate profitability (stub)");
+    println!("  {green}make simulate{reset}           Replay traders through your strategy");
     println!("  {green}make simulate-old{reset}       Old logic sim (stub)");
     println!("  {green}make sim{reset}                 Run simulations (stub)");
     println!("  {green}make compare{reset}            Compare results (stub)");
     println!("  {green}make fetch-history{reset}      Fetch historical trades (stub)");
-    println!("  {green}make aggregate{reset}          Aggregate results (stub)");
+    println!("  {green}make aggregate{reset}          Aggregate scan results");
     println!("  {green}make audit{reset} / make audit-old{reset}  Algorithm audit (stub)");
     println!();
 
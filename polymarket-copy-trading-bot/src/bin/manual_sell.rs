@@ -0,0 +1,103 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::{
+    arg_value, fetch_positions, is_dry_run, position_label, sell_positions, theme::colors,
+};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Sell all or part of one position: manual_sell <asset | condition id | slug/title text> [--percent 50] [--dry-run]
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let dry_run = is_dry_run();
+
+    println!();
+    println!("{} Manual sell{}{}", colors::ACCENT, if dry_run { " (dry run)" } else { "" }, colors::RESET);
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let positions = fetch_positions(&config, &http_client, &config.proxy_wallet).await?;
+    if positions.is_empty() {
+        Logger::info("No open positions");
+        return Ok(());
+    }
+
+    // First positional arg (skipping flags & their values), else --market / SELL_MARKET
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let positional = args
+        .iter()
+        .enumerate()
+        .find(|(i, a)| {
+            !a.starts_with('-')
+                && (*i == 0 || !matches!(args[i - 1].as_str(), "--percent" | "--market"))
+        })
+        .map(|(_, a)| a.clone());
+    let query = positional
+        .or_else(|| arg_value("--market"))
+        .or_else(|| std::env::var("SELL_MARKET").ok())
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+
+    let Some(query) = query else {
+        println!("Your positions:");
+        for p in &positions {
+            println!(
+                "  {} - {:.2} tokens, ${:.2}\n    asset {}",
+                position_label(p),
+                p.size.unwrap_or(0.0),
+                p.current_value.unwrap_or(0.0),
+                p.asset.as_deref().unwrap_or("-")
+            );
+        }
+        println!();
+        println!("Usage: manual_sell <asset | condition id | market text> [--percent 50] [--dry-run]");
+        println!("       (or set SELL_MARKET / SELL_PERCENT)");
+        return Ok(());
+    };
+
+    let percent: f64 = arg_value("--percent")
+        .or_else(|| std::env::var("SELL_PERCENT").ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100.0);
+    if !(0.0..=100.0).contains(&percent) || percent == 0.0 {
+        anyhow::bail!("--percent must be in (0, 100], got {}", percent);
+    }
+
+    // Exact asset/condition id wins; otherwise the text has to pick out a single position
+    let exact: Vec<_> = positions
+        .iter()
+        .filter(|p| {
+            p.asset.as_deref().map(str::to_lowercase).as_deref() == Some(query.as_str())
+                || p.condition_id.as_deref().map(str::to_lowercase).as_deref() == Some(query.as_str())
+        })
+        .collect();
+    let matches: Vec<_> = if !exact.is_empty() {
+        exact
+    } else {
+        positions
+            .iter()
+            .filter(|p| {
+                [p.slug.as_deref(), p.event_slug.as_deref(), p.title.as_deref()]
+                    .iter()
+                    .flatten()
+                    .any(|s| s.to_lowercase().contains(&query))
+            })
+            .collect()
+    };
+
+    match matches.len() {
+        0 => anyhow::bail!("No position matches \"{}\"", query),
+        1 => {}
+        n => {
+            println!("\"{}\" matches {} positions:", query, n);
+            for p in &matches {
+                println!("  {} - asset {}", position_label(p), p.asset.as_deref().unwrap_or("-"));
+            }
+            anyhow::bail!("Be more specific, or pass the asset id");
+        }
+    }
+
+    let position = matches[0].clone();
+    let tokens = position.size.unwrap_or(0.0) * percent / 100.0;
+    sell_positions(&config, &http_client, &[(position, tokens)], dry_run).await
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use polymarket_copy_rust::services::redemption::redeem_resolved_positions;
+use polymarket_copy_rust::utils::{is_dry_run, theme::colors};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Redeem resolved positions via the CTF (or NegRiskAdapter), from an EOA or a 1-of-n Safe
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let dry_run = is_dry_run();
+
+    println!();
+    println!(
+        "{} Redeem resolved positions{}{}",
+        colors::ACCENT,
+        if dry_run { " (dry run)" } else { "" },
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let report = redeem_resolved_positions(&config, &http_client, dry_run).await?;
+    if report.markets == 0 {
+        Logger::success("No redeemable positions");
+        return Ok(());
+    }
+
+    Logger::separator();
+    if dry_run {
+        Logger::info(&format!(
+            "{} market(s) to redeem, ~${:.2}",
+            report.markets, report.value_usd
+        ));
+        Logger::warning("Dry run - no transactions sent");
+    } else if report.failed == 0 {
+        Logger::success(&format!(
+            "Redeemed {} market(s), ~${:.2}",
+            report.redeemed, report.value_usd
+        ));
+    } else {
+        Logger::warning(&format!(
+            "{} of {} redemption(s) failed",
+            report.failed, report.markets
+        ));
+    }
+    Ok(())
+}
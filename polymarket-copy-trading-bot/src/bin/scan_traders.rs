@@ -0,0 +1,63 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::scanner::{
+    analyze_traders, gather_candidates, print_traders, write_scan_result, ScanOptions, ScanResult,
+};
+use polymarket_copy_rust::utils::{arg_value, theme::colors};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Score every candidate wallet unfiltered, or just the ones given with --addresses
+// (`--addresses current` = USER_ADDRESSES)
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let opts = ScanOptions::from_args();
+    let http_client = reqwest::Client::new();
+
+    let explicit = arg_value("--addresses").map(|list| {
+        if list.eq_ignore_ascii_case("current") {
+            config.user_addresses.clone()
+        } else {
+            list.split(',')
+                .map(|a| a.trim().to_lowercase())
+                .filter(|a| !a.is_empty())
+                .collect()
+        }
+    });
+    let (wallets, dir, prefix) = match explicit {
+        Some(w) => (w, "trader_analysis_results", "analysis"),
+        None => (
+            gather_candidates(&config, &http_client, &opts).await?,
+            "trader_scan_results",
+            "scan",
+        ),
+    };
+
+    println!();
+    println!(
+        "{} Scan {} trader(s) over {} days{}",
+        colors::ACCENT,
+        wallets.len(),
+        opts.history_days,
+        colors::RESET
+    );
+    println!();
+
+    let mut scanned = analyze_traders(&config, &http_client, &wallets, opts.history_days).await;
+    scanned.sort_by(|a, b| b.roi.total_cmp(&a.roi));
+
+    Logger::separator();
+    if scanned.is_empty() {
+        Logger::warning("No trader could be analyzed");
+        return Ok(());
+    }
+    print_traders(&scanned, scanned.len());
+
+    let path = write_scan_result(
+        dir,
+        prefix,
+        &ScanResult::new("scan_traders", opts.history_days, scanned),
+    )?;
+    Logger::success(&format!("Saved {}", path.display()));
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use polymarket_copy_rust::{utils::theme::colors, Db};
+use std::env;
+
+// Migration: older versions wrote the plaintext wallet key to `configs` as PRIVATE_KEY_<n>
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+
+    // Only needs Mongo, so don't go through EnvConfig (which would unlock the keystore)
+    let mongo_uri = env::var("MONGO_URI")
+        .unwrap_or_else(|_| "mongodb://localhost:27017/polymarket_copytrading".into());
+    let db = Db::connect(&mongo_uri).await?;
+
+    let found = db.count_private_keys().await?;
+    if found == 0 {
+        println!(
+            "{} No PRIVATE_KEY documents in configs. Nothing to do.{}",
+            colors::SUCCESS,
+            colors::RESET
+        );
+        return Ok(());
+    }
+
+    let deleted = db.scrub_private_keys().await?;
+    println!(
+        "{} Deleted {} of {} PRIVATE_KEY document(s) from configs.{}",
+        colors::SUCCESS,
+        deleted,
+        found,
+        colors::RESET
+    );
+    println!();
+    println!(
+        "{} Anyone with read access to this database (or its backups) may have copied the key.",
+        colors::WARN
+    );
+    println!(
+        "  Move funds to a fresh wallet and put the new key in the keystore (make create-keystore).{}",
+        colors::RESET
+    );
+    println!();
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::{arg_value, fetch_positions, is_dry_run, sell_positions, theme::colors};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+// Trim every position worth more than --max-value (default MAX_POSITION_SIZE_USD, else $100) back down to it
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+    let dry_run = is_dry_run();
+
+    let max_value: f64 = arg_value("--max-value")
+        .or_else(|| std::env::var("SELL_LARGE_MAX_VALUE_USD").ok())
+        .and_then(|v| v.parse().ok())
+        .or(config.copy_strategy_config.max_position_size_usd)
+        .unwrap_or(100.0);
+
+    println!();
+    println!(
+        "{} Sell large positions above ${:.2}{}{}",
+        colors::ACCENT,
+        max_value,
+        if dry_run { " (dry run)" } else { "" },
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let positions = fetch_positions(&config, &http_client, &config.proxy_wallet).await?;
+
+    let plan: Vec<_> = positions
+        .into_iter()
+        .filter(|p| !p.redeemable.unwrap_or(false))
+        .filter_map(|p| {
+            let value = p.current_value.unwrap_or(0.0);
+            let size = p.size.unwrap_or(0.0);
+            if value <= max_value || size <= 0.0 {
+                return None;
+            }
+            let tokens = size * (1.0 - max_value / value);
+            Some((p, tokens))
+        })
+        .collect();
+
+    if plan.is_empty() {
+        Logger::success(&format!("No positions above ${:.2}", max_value));
+        return Ok(());
+    }
+    sell_positions(&config, &http_client, &plan, dry_run).await
+}
@@ -49,11 +49,12 @@ fn main() {
             );
             println!();
             println!("  Next steps:");
-            println!("  1. Open .env and set: USER_ADDRESSES, PROXY_WALLET, PRIVATE_KEY,");
+            println!("  1. Open .env and set: USER_ADDRESSES, PROXY_WALLET,");
             println!("     MONGO_URI, RPC_URL, CLOB_HTTP_URL, CLOB_WS_URL, USDC_CONTRACT_ADDRESS");
-            println!("  2. Run: make validate-setup");
-            println!("  3. Run: make health-check");
-            println!("  4. Run: make run");
+            println!("  2. Run: make create-keystore (encrypts your private key)");
+            println!("  3. Run: make validate-setup");
+            println!("  4. Run: make health-check");
+            println!("  5. Run: make run");
             println!();
         }
         Err(e) => {
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use polymarket_copy_rust::utils::scanner::{write_scan_result, ScanConfig, ScanResult, ScanSummary};
+use polymarket_copy_rust::utils::simulator::{
+    fetch_trader_history, scaled_strategy, simulate_history, SimOptions, SimResult,
+};
+use polymarket_copy_rust::utils::{arg_value, theme::colors};
+use polymarket_copy_rust::{EnvConfig, Logger};
+
+fn print_results(results: &[SimResult]) {
+    println!(
+        "{}  Address                                    | ROI      | P&L        | Win%  | Copied | Missed | Open{}",
+        colors::BOLD,
+        colors::RESET
+    );
+    println!("{}{}{}", colors::MUTED, "─".repeat(96), colors::RESET);
+    for r in results {
+        let roi_color = if r.roi >= 0.0 { colors::SUCCESS } else { colors::ERROR };
+        println!(
+            "  {}{:42}{} | {}{:>8}{} | {:>10} | {:>5.1} | {:>6} | {:>6} | {}{}",
+            colors::ACCENT,
+            r.address,
+            colors::RESET,
+            roi_color,
+            format!("{:+.1}%", r.roi),
+            colors::RESET,
+            format!("{:+.2}", r.total_pnl),
+            r.win_rate,
+            r.copied_trades,
+            r.missed_trades,
+            r.open_positions,
+            if r.history_complete { "" } else { " *" }
+        );
+        if r.missed_trades > 0 {
+            println!(
+                "{}    missed: {} below minimum, {} no balance, {} nothing to sell, {} trader settings{}",
+                colors::MUTED,
+                r.missed_below_minimum,
+                r.missed_no_balance,
+                r.missed_no_position,
+                r.missed_by_settings,
+                colors::RESET
+            );
+        }
+    }
+    println!();
+}
+
+// Replay traders' history through the bot's sizing (COPY_STRATEGY etc. from .env) and report the result
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = EnvConfig::from_env().await?;
+
+    let num = |name: &str, default: f64| {
+        arg_value(name)
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(default)
+    };
+    let opts = SimOptions {
+        history_days: (num("--days", 30.0) as u32).max(1),
+        starting_capital: num("--capital", 1000.0),
+        slippage_bps: num("--slippage-bps", 50.0),
+    };
+    let traders: Vec<String> = match arg_value("--trader") {
+        Some(list) => list
+            .split(',')
+            .map(|a| a.trim().to_lowercase())
+            .filter(|a| !a.is_empty())
+            .collect(),
+        None => config.user_addresses.clone(),
+    };
+    let multipliers: Vec<f64> = arg_value("--multipliers")
+        .unwrap_or_else(|| "1".to_string())
+        .split(',')
+        .filter_map(|m| m.trim().parse().ok())
+        .filter(|m: &f64| *m > 0.0)
+        .collect();
+    if multipliers.is_empty() {
+        anyhow::bail!("--multipliers needs at least one positive number, e.g. 0.5,1,2");
+    }
+
+    println!();
+    println!(
+        "{} Simulate copying {} trader(s): {} days, ${:.0} capital, {} bps slippage{}",
+        colors::ACCENT,
+        traders.len(),
+        opts.history_days,
+        opts.starting_capital,
+        opts.slippage_bps,
+        colors::RESET
+    );
+    println!();
+
+    let http_client = reqwest::Client::new();
+    let mut histories = Vec::new();
+    for trader in &traders {
+        match fetch_trader_history(&config, &http_client, trader, opts.history_days).await {
+            Ok(h) => {
+                Logger::info(&format!(
+                    "{}: {} activities",
+                    Logger::format_address(trader),
+                    h.activities.len()
+                ));
+                histories.push((trader.clone(), h));
+            }
+            Err(e) => Logger::warning(&format!(
+                "{}: failed to fetch history: {}",
+                Logger::format_address(trader),
+                e
+            )),
+        }
+    }
+    if histories.is_empty() {
+        anyhow::bail!("No trader history to simulate");
+    }
+
+    for multiplier in &multipliers {
+        let mut results: Vec<SimResult> = histories
+            .iter()
+            .map(|(trader, history)| {
+                let strategy = scaled_strategy(config.strategy_for(trader), *multiplier);
+                simulate_history(&config, trader, &strategy, history, &opts)
+            })
+            .collect();
+        results.sort_by(|a, b| b.roi.total_cmp(&a.roi));
+
+        Logger::separator();
+        Logger::header(&format!("{}x multiplier", multiplier));
+        print_results(&results);
+
+        let result = ScanResult {
+            scan_date: chrono::Utc::now().to_rfc3339(),
+            scan_type: "simulate".to_string(),
+            config: ScanConfig {
+                history_days: opts.history_days,
+                multiplier: *multiplier,
+                min_order_size: Some(config.copy_strategy_config.min_order_size_usd),
+                starting_capital: Some(opts.starting_capital),
+            },
+            summary: ScanSummary::from_rows(results.iter().map(|r| (r.roi, r.win_rate))),
+            traders: results,
+        };
+        let path = write_scan_result(
+            "strategy_factory_results",
+            &format!("simulate_{}d_{}x", opts.history_days, multiplier),
+            &result,
+        )?;
+        Logger::success(&format!("Saved {}", path.display()));
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::path::Path;
+
+use super::copy_strategy::CopyStrategyConfig;
+use super::is_valid_ethereum_address;
+use super::keystore::{read_keystore_password, unlock_keystore};
+use super::trader_overrides::StrategyOverride;
+
+// Extra wallets to copy with (relative to the working dir, like .env)
+pub const DEFAULT_ACCOUNTS_CONFIG_PATH: &str = "accounts.json";
+// Name of the .env wallet (PROXY_WALLET + keystore / PRIVATE_KEY)
+pub const PRIMARY_ACCOUNT: &str = "main";
+
+// One entry of accounts.json - keys come from a keystore or an env var, never from the file itself
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AccountEntry {
+    proxy_wallet: String,
+    keystore_path: Option<String>,
+    // Env var holding the keystore passphrase (default: KEYSTORE_PASSWORD / prompt)
+    keystore_password_env: Option<String>,
+    // Env var holding a plaintext private key
+    private_key_env: Option<String>,
+    // Unset fields fall back to the .env copy strategy
+    #[serde(default)]
+    copy_strategy: StrategyOverride,
+}
+
+// An extra account, unlocked, with its strategy merged over the .env one
+pub(super) struct AccountConfig {
+    pub name: String,
+    pub proxy_wallet: String,
+    pub private_key: String,
+    pub copy_strategy: CopyStrategyConfig,
+}
+
+fn is_valid_account_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+fn env_value(key: &str) -> Result<String> {
+    env::var(key)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("{} is not set", key))
+}
+
+fn unlock(name: &str, entry: &AccountEntry) -> Result<String> {
+    match (&entry.keystore_path, &entry.private_key_env) {
+        (Some(path), None) => {
+            let password = match entry.keystore_password_env {
+                Some(ref key) => env_value(key)?,
+                None => read_keystore_password(&format!(
+                    "Keystore passphrase for {} ({}): ",
+                    name, path
+                ))?,
+            };
+            unlock_keystore(Path::new(path), &password)
+        }
+        (None, Some(key)) => Ok(env_value(key)?.trim().trim_start_matches("0x").to_string()),
+        _ => anyhow::bail!("set exactly one of keystorePath or privateKeyEnv"),
+    }
+}
+
+// Extra accounts from ACCOUNTS_CONFIG_PATH (must exist if set), else accounts.json if present
+pub(super) fn load_accounts_from_env(
+    primary_wallet: &str,
+    global: &CopyStrategyConfig,
+) -> Result<Vec<AccountConfig>> {
+    let (path, required) = match env::var("ACCOUNTS_CONFIG_PATH") {
+        Ok(p) if !p.trim().is_empty() => (p.trim().to_string(), true),
+        _ => (DEFAULT_ACCOUNTS_CONFIG_PATH.to_string(), false),
+    };
+    if !required && !Path::new(&path).exists() {
+        return Ok(Vec::new());
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("Cannot read accounts config {}", path))?;
+    // BTreeMap so accounts start (and log) in a stable order
+    let entries: BTreeMap<String, AccountEntry> =
+        serde_json::from_str(&raw).with_context(|| format!("Invalid accounts config {}", path))?;
+
+    let mut wallets: HashSet<String> = HashSet::from([primary_wallet.trim().to_lowercase()]);
+    let mut out = Vec::new();
+    for (name, entry) in entries {
+        if !is_valid_account_name(&name) {
+            anyhow::bail!(
+                "Invalid account name '{}' in {} (use a-z, 0-9, _ and -)",
+                name,
+                path
+            );
+        }
+        if name == PRIMARY_ACCOUNT {
+            anyhow::bail!(
+                "'{}' is the .env wallet and can't be redefined in {}",
+                PRIMARY_ACCOUNT,
+                path
+            );
+        }
+        let wallet = entry.proxy_wallet.trim().to_lowercase();
+        if !is_valid_ethereum_address(&wallet) {
+            anyhow::bail!("Invalid proxyWallet for {} in {}: {}", name, path, wallet);
+        }
+        if !wallets.insert(wallet.clone()) {
+            anyhow::bail!(
+                "{} in {} uses {}, which is already used by another account",
+                name,
+                path,
+                wallet
+            );
+        }
+        let copy_strategy = entry
+            .copy_strategy
+            .apply(global)
+            .with_context(|| format!("Invalid copyStrategy for {} in {}", name, path))?;
+        let private_key = unlock(&name, &entry)
+            .with_context(|| format!("Cannot load the key for {} in {}", name, path))?;
+        out.push(AccountConfig {
+            name,
+            proxy_wallet: wallet,
+            private_key,
+            copy_strategy,
+        });
+    }
+    Ok(out)
+}
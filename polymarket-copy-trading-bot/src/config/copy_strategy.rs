@@ -43,6 +43,8 @@ pub struct OrderSizeCalculation {
     pub capped_by_max: bool,
     pub reduced_by_balance: bool,
     pub below_minimum: bool,
+    // Set by the risk manager when a portfolio limit reduced or blocked the order
+    pub limited_by_risk: bool,
     pub reasoning: String,
 }
 
@@ -180,6 +182,7 @@ pub fn calculate_order_size(
         capped_by_max,
         reduced_by_balance,
         below_minimum,
+        limited_by_risk: false,
         reasoning,
     }
 }
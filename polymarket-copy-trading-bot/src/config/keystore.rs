@@ -0,0 +1,67 @@
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use std::env;
+use std::path::Path;
+
+// Default keystore location (relative to the working dir, like .env)
+pub const DEFAULT_KEYSTORE_PATH: &str = "keystore.json";
+
+// Passphrase for the keystore: KEYSTORE_PASSWORD, then KEYSTORE_PASSWORD_FILE, then a TTY prompt
+pub fn read_keystore_password(prompt: &str) -> Result<String> {
+    if let Ok(password) = env::var("KEYSTORE_PASSWORD") {
+        if !password.is_empty() {
+            return Ok(password);
+        }
+    }
+    if let Ok(path) = env::var("KEYSTORE_PASSWORD_FILE") {
+        if !path.trim().is_empty() {
+            let password = std::fs::read_to_string(path.trim())
+                .with_context(|| format!("Cannot read KEYSTORE_PASSWORD_FILE {}", path.trim()))?;
+            return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+        }
+    }
+    rpassword::prompt_password(prompt)
+        .context("No KEYSTORE_PASSWORD set and no terminal to prompt for the keystore passphrase")
+}
+
+// Decrypt a Web3 Secret Storage v3 file, returns the private key as hex (no 0x)
+pub fn unlock_keystore(path: &Path, password: &str) -> Result<String> {
+    let signer = PrivateKeySigner::decrypt_keystore(path, password)
+        .map_err(|e| anyhow::anyhow!("Cannot unlock keystore {}: {}", path.display(), e))?;
+    Ok(alloy::hex::encode(signer.to_bytes()))
+}
+
+// Encrypt a private key (hex, with or without 0x) into a v3 keystore file, returns the signer address
+pub fn create_keystore(path: &Path, private_key: &str, password: &str) -> Result<String> {
+    if path.exists() {
+        anyhow::bail!("{} already exists, refusing to overwrite it", path.display());
+    }
+    let key_bytes = alloy::hex::decode(private_key.trim().trim_start_matches("0x"))
+        .context("PRIVATE_KEY is not valid hex")?;
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid keystore path: {}", path.display()))?;
+    std::fs::create_dir_all(dir)?;
+
+    let (signer, _uuid) = PrivateKeySigner::encrypt_keystore(
+        dir,
+        &mut rand::thread_rng(),
+        &key_bytes,
+        password,
+        Some(name),
+    )
+    .map_err(|e| anyhow::anyhow!("Cannot write keystore: {}", e))?;
+
+    // Owner-only: the file is encrypted, but there's no reason to let anyone else brute-force it
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(signer.address().to_string())
+}
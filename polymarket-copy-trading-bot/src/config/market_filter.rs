@@ -0,0 +1,123 @@
+use anyhow::Result;
+use regex::Regex;
+use std::env;
+
+// Filters applied to copied buys before they reach the executor (None / empty = off)
+#[derive(Debug, Clone, Default)]
+pub struct MarketFilterConfig {
+    // Glob patterns (`*` wildcard) matched against market & event slugs
+    pub include_slugs: Vec<SlugPattern>,
+    pub exclude_slugs: Vec<SlugPattern>,
+    // Gamma tag labels/slugs or market category, case-insensitive
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    pub min_buy_price: Option<f64>,
+    pub max_buy_price: Option<f64>,
+    pub min_hours_to_resolution: Option<f64>,
+    pub max_days_to_resolution: Option<f64>,
+    pub min_liquidity_usd: Option<f64>,
+    pub min_volume_usd: Option<f64>,
+    pub skip_neg_risk: bool,
+}
+
+impl MarketFilterConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.include_slugs.is_empty()
+            || !self.exclude_slugs.is_empty()
+            || self.min_buy_price.is_some()
+            || self.max_buy_price.is_some()
+            || self.needs_market_data()
+    }
+
+    // Whether any filter needs Gamma market metadata (one lookup per market, cached)
+    pub fn needs_market_data(&self) -> bool {
+        !self.include_tags.is_empty()
+            || !self.exclude_tags.is_empty()
+            || self.min_hours_to_resolution.is_some()
+            || self.max_days_to_resolution.is_some()
+            || self.min_liquidity_usd.is_some()
+            || self.min_volume_usd.is_some()
+            || self.skip_neg_risk
+    }
+}
+
+// "nba-*,*bitcoin-up-or-down*" → ["nba-*", "*bitcoin-up-or-down*"]
+fn parse_list(key: &str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn env_opt(key: &str) -> Option<f64> {
+    env::var(key).ok().and_then(|v| v.trim().parse().ok())
+}
+
+// Slug glob compiled once at startup, `*` is the only wildcard
+#[derive(Debug, Clone)]
+pub struct SlugPattern {
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl SlugPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let re = format!("^{}$", regex::escape(pattern).replace("\\*", ".*"));
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex: Regex::new(&re)?,
+        })
+    }
+
+    // Anchored, case-insensitive match
+    pub fn matches(&self, slug: &str) -> bool {
+        self.regex.is_match(&slug.to_lowercase())
+    }
+}
+
+// A pattern that doesn't compile is a config error, not a filter that never matches
+fn parse_patterns(key: &str) -> Result<Vec<SlugPattern>> {
+    parse_list(key)
+        .iter()
+        .map(|p| {
+            SlugPattern::new(p)
+                .map_err(|e| anyhow::anyhow!("{} has an invalid pattern \"{}\": {}", key, p, e))
+        })
+        .collect()
+}
+
+pub fn parse_market_filter_from_env() -> Result<MarketFilterConfig> {
+    let config = MarketFilterConfig {
+        include_slugs: parse_patterns("MARKET_INCLUDE_SLUGS")?,
+        exclude_slugs: parse_patterns("MARKET_EXCLUDE_SLUGS")?,
+        include_tags: parse_list("MARKET_INCLUDE_TAGS"),
+        exclude_tags: parse_list("MARKET_EXCLUDE_TAGS"),
+        min_buy_price: env_opt("MIN_BUY_PRICE"),
+        max_buy_price: env_opt("MAX_BUY_PRICE"),
+        min_hours_to_resolution: env_opt("MIN_HOURS_TO_RESOLUTION"),
+        max_days_to_resolution: env_opt("MAX_DAYS_TO_RESOLUTION"),
+        min_liquidity_usd: env_opt("MIN_MARKET_LIQUIDITY_USD"),
+        min_volume_usd: env_opt("MIN_MARKET_VOLUME_USD"),
+        skip_neg_risk: env::var("SKIP_NEG_RISK")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false),
+    };
+    for (key, price) in [
+        ("MIN_BUY_PRICE", config.min_buy_price),
+        ("MAX_BUY_PRICE", config.max_buy_price),
+    ] {
+        if let Some(p) = price {
+            if !(0.0..=1.0).contains(&p) {
+                anyhow::bail!("{} must be between 0 and 1, got {}", key, p);
+            }
+        }
+    }
+    if let (Some(min), Some(max)) = (config.min_buy_price, config.max_buy_price) {
+        if min > max {
+            anyhow::bail!("MIN_BUY_PRICE ({}) is above MAX_BUY_PRICE ({})", min, max);
+        }
+    }
+    Ok(config)
+}
@@ -1,12 +1,30 @@
+mod accounts;
 mod copy_strategy;
+mod keystore;
+mod market_filter;
+mod risk;
+mod trader_overrides;
 
+pub use accounts::PRIMARY_ACCOUNT;
 pub use copy_strategy::{
     calculate_order_size, get_trade_multiplier, parse_tiered_multipliers, CopyStrategy,
-    CopyStrategyConfig,
+    CopyStrategyConfig, OrderSizeCalculation,
 };
+pub use keystore::{create_keystore, read_keystore_password, unlock_keystore, DEFAULT_KEYSTORE_PATH};
+pub use market_filter::MarketFilterConfig;
+pub use risk::{market_category, RiskConfig};
+pub use trader_overrides::{load_trader_overrides, TraderOverride, DEFAULT_TRADER_CONFIG_PATH};
 
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::env;
+use std::path::Path;
+
+use crate::utils::Logger;
+
+// Polygon mainnet contracts used for redemption (overridable to point at mocks on a local fork)
+pub const CTF_CONTRACT_ADDRESS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+pub const NEG_RISK_ADAPTER_ADDRESS: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
 
 pub fn is_valid_ethereum_address(addr: &str) -> bool {
     let s = addr.trim().trim_start_matches("0x");
@@ -159,9 +177,69 @@ fn parse_copy_strategy_from_env() -> Result<CopyStrategyConfig> {
     Ok(config)
 }
 
+// Keystore location: KEYSTORE_PATH, else keystore.json in the working dir
+pub fn keystore_path() -> String {
+    env::var("KEYSTORE_PATH")
+        .ok()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| DEFAULT_KEYSTORE_PATH.into())
+}
+
+// Private key from the encrypted keystore, or plaintext PRIVATE_KEY when no keystore exists yet
+fn load_private_key() -> Result<String> {
+    let path = keystore_path();
+    if Path::new(&path).exists() {
+        let password = read_keystore_password(&format!("Keystore passphrase for {}: ", path))?;
+        return unlock_keystore(Path::new(&path), &password);
+    }
+    let key = env::var("PRIVATE_KEY").unwrap_or_default();
+    if key.trim().is_empty() {
+        anyhow::bail!(
+            "No keystore at {} and PRIVATE_KEY is not set. Run `make create-keystore`",
+            path
+        );
+    }
+    Logger::warning("Using plaintext PRIVATE_KEY from the environment; run `make create-keystore` to encrypt it");
+    Ok(key.trim().trim_start_matches("0x").to_string())
+}
+
+// TRADER_CONFIG_PATH (must exist if set), else traders.json if present
+fn trader_config_path() -> Option<String> {
+    match env::var("TRADER_CONFIG_PATH") {
+        Ok(p) if !p.trim().is_empty() => Some(p.trim().to_string()),
+        _ if Path::new(DEFAULT_TRADER_CONFIG_PATH).exists() => {
+            Some(DEFAULT_TRADER_CONFIG_PATH.to_string())
+        }
+        _ => None,
+    }
+}
+
+// Per-trader overrides from the trader config, merged over the global strategy
+fn load_trader_overrides_from_env(
+    global: &CopyStrategyConfig,
+    user_addresses: &[String],
+) -> Result<HashMap<String, TraderOverride>> {
+    let Some(path) = trader_config_path() else {
+        return Ok(HashMap::new());
+    };
+    let overrides = load_trader_overrides(Path::new(&path), global)?;
+    for address in overrides.keys() {
+        if !user_addresses.contains(address) {
+            Logger::warning(&format!(
+                "{} has settings in {} but is not in USER_ADDRESSES",
+                address, path
+            ));
+        }
+    }
+    Ok(overrides)
+}
+
 #[derive(Clone)]
 pub struct EnvConfig {
     pub user_addresses: Vec<String>,
+    // PRIMARY_ACCOUNT for the .env wallet, else the accounts.json name
+    pub account_name: String,
     pub proxy_wallet: String,
     pub private_key: String,
     pub clob_http_url: String,
@@ -170,6 +248,10 @@ pub struct EnvConfig {
     pub too_old_timestamp_hours: i64,
     pub retry_limit: u32,
     pub copy_strategy_config: CopyStrategyConfig,
+    // Keyed by lowercase trader address
+    pub trader_overrides: HashMap<String, TraderOverride>,
+    pub risk_config: RiskConfig,
+    pub market_filter: MarketFilterConfig,
     pub request_timeout_ms: u64,
     pub network_retry_limit: u32,
     pub trade_aggregation_enabled: bool,
@@ -177,6 +259,10 @@ pub struct EnvConfig {
     pub mongo_uri: String,
     pub rpc_url: String,
     pub usdc_contract_address: String,
+    pub ctf_contract_address: String,
+    pub neg_risk_adapter_address: String,
+    // 0 = no automatic redemption in the main bot
+    pub redeem_interval_minutes: u64,
 }
 
 impl EnvConfig {
@@ -186,7 +272,6 @@ impl EnvConfig {
         let required = [
             "USER_ADDRESSES",
             "PROXY_WALLET",
-            "PRIVATE_KEY",
             "CLOB_HTTP_URL",
             "CLOB_WS_URL",
             "RPC_URL",
@@ -244,16 +329,35 @@ impl EnvConfig {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(300);
-        let private_key = env::var("PRIVATE_KEY")?
-            .trim()
-            .trim_start_matches("0x")
-            .to_string();
+        let copy_strategy_config = parse_copy_strategy_from_env()?;
+        let trader_overrides =
+            load_trader_overrides_from_env(&copy_strategy_config, &user_addresses)?;
+        let redeem_interval_minutes: u64 = env::var("REDEEM_INTERVAL_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let contract_env = |key: &str, default: &str| -> Result<String> {
+            let value = env::var(key)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| default.to_string());
+            if !is_valid_ethereum_address(&value) {
+                anyhow::bail!("Invalid {}: {}", key, value);
+            }
+            Ok(value)
+        };
+        let ctf_contract_address = contract_env("CTF_CONTRACT_ADDRESS", CTF_CONTRACT_ADDRESS)?;
+        let neg_risk_adapter_address =
+            contract_env("NEG_RISK_ADAPTER_ADDRESS", NEG_RISK_ADAPTER_ADDRESS)?;
+        let private_key = load_private_key()?;
 
         let mongo_uri = env::var("MONGO_URI")
             .unwrap_or_else(|_| "mongodb://localhost:27017/polymarket_copytrading".into());
 
         Ok(Self {
             user_addresses,
+            account_name: PRIMARY_ACCOUNT.to_string(),
             proxy_wallet: env::var("PROXY_WALLET")?.trim().to_string(),
             private_key,
             clob_http_url: env::var("CLOB_HTTP_URL")?
@@ -264,7 +368,10 @@ impl EnvConfig {
             fetch_interval_secs,
             too_old_timestamp_hours,
             retry_limit,
-            copy_strategy_config: parse_copy_strategy_from_env()?,
+            copy_strategy_config,
+            trader_overrides,
+            risk_config: risk::parse_risk_config_from_env()?,
+            market_filter: market_filter::parse_market_filter_from_env()?,
             request_timeout_ms,
             network_retry_limit,
             trade_aggregation_enabled,
@@ -272,6 +379,59 @@ impl EnvConfig {
             mongo_uri,
             rpc_url: env::var("RPC_URL")?.trim().to_string(),
             usdc_contract_address: env::var("USDC_CONTRACT_ADDRESS")?.trim().to_string(),
+            ctf_contract_address,
+            neg_risk_adapter_address,
+            redeem_interval_minutes,
         })
     }
+
+    // Override entry for a copied trader, if traders.json has one
+    pub fn trader_override(&self, address: &str) -> Option<&TraderOverride> {
+        self.trader_overrides.get(&address.to_lowercase())
+    }
+
+    pub fn is_primary_account(&self) -> bool {
+        self.account_name == PRIMARY_ACCOUNT
+    }
+
+    // "[name] " for extra accounts, so their log lines can be told apart from the main wallet's
+    pub fn log_prefix(&self) -> String {
+        if self.is_primary_account() {
+            String::new()
+        } else {
+            format!("[{}] ", self.account_name)
+        }
+    }
+
+    // One config per accounts.json entry: same traders & settings, own wallet, key & strategy.
+    // traders.json is re-applied over each account's strategy, so per-trader fields still win.
+    pub fn load_accounts(&self) -> Result<Vec<EnvConfig>> {
+        let accounts =
+            accounts::load_accounts_from_env(&self.proxy_wallet, &self.copy_strategy_config)?;
+        let trader_path = trader_config_path();
+        let mut out = Vec::new();
+        for account in accounts {
+            let trader_overrides = match trader_path {
+                Some(ref path) => load_trader_overrides(Path::new(path), &account.copy_strategy)
+                    .with_context(|| format!("Trader settings for account {}", account.name))?,
+                None => HashMap::new(),
+            };
+            out.push(EnvConfig {
+                account_name: account.name,
+                proxy_wallet: account.proxy_wallet,
+                private_key: account.private_key,
+                copy_strategy_config: account.copy_strategy,
+                trader_overrides,
+                ..self.clone()
+            });
+        }
+        Ok(out)
+    }
+
+    // Copy strategy for a trader: their override, else the global one
+    pub fn strategy_for(&self, address: &str) -> &CopyStrategyConfig {
+        self.trader_override(address)
+            .map(|t| &t.copy_strategy)
+            .unwrap_or(&self.copy_strategy_config)
+    }
 }
@@ -0,0 +1,167 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::env;
+
+// Market categories for allocation limits
+pub const CATEGORIES: [&str; 4] = ["sports", "politics", "crypto", "other"];
+
+// Portfolio risk limits (None = check disabled; daily volume lives in CopyStrategyConfig)
+#[derive(Debug, Clone, Default)]
+pub struct RiskConfig {
+    pub max_trader_daily_volume_usd: Option<f64>,
+    pub max_market_exposure_usd: Option<f64>,
+    pub max_event_exposure_usd: Option<f64>,
+    pub max_open_positions: Option<usize>,
+    pub max_drawdown_percent: Option<f64>,
+    // Max % of equity per category, e.g. {"sports": 20.0}
+    pub category_allocation: HashMap<String, f64>,
+}
+
+impl RiskConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_trader_daily_volume_usd.is_some()
+            || self.max_market_exposure_usd.is_some()
+            || self.max_event_exposure_usd.is_some()
+            || self.max_open_positions.is_some()
+            || self.max_drawdown_percent.is_some()
+            || !self.category_allocation.is_empty()
+    }
+}
+
+// Parse "sports:20,politics:40,crypto:30" (percent of equity per category)
+pub fn parse_category_allocation(input: &str) -> Result<HashMap<String, f64>> {
+    let mut out = HashMap::new();
+    for part in input.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let (name, pct) = part
+            .split_once(':')
+            .with_context(|| format!("Invalid CATEGORY_ALLOCATION entry: {}", part))?;
+        let name = name.trim().to_lowercase();
+        if !CATEGORIES.contains(&name.as_str()) {
+            anyhow::bail!(
+                "Unknown category '{}' in CATEGORY_ALLOCATION (use {})",
+                name,
+                CATEGORIES.join(", ")
+            );
+        }
+        let pct: f64 = pct
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid percent in CATEGORY_ALLOCATION: {}", part))?;
+        if !(0.0..=100.0).contains(&pct) {
+            anyhow::bail!("CATEGORY_ALLOCATION percent must be 0-100: {}", part);
+        }
+        out.insert(name, pct);
+    }
+    Ok(out)
+}
+
+fn env_opt<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env::var(key).ok().and_then(|v| v.trim().parse().ok())
+}
+
+pub fn parse_risk_config_from_env() -> Result<RiskConfig> {
+    let category_allocation = match env::var("CATEGORY_ALLOCATION") {
+        Ok(v) if !v.trim().is_empty() => parse_category_allocation(&v)?,
+        _ => HashMap::new(),
+    };
+    Ok(RiskConfig {
+        max_trader_daily_volume_usd: env_opt("MAX_TRADER_DAILY_VOLUME_USD"),
+        max_market_exposure_usd: env_opt("MAX_MARKET_EXPOSURE_USD"),
+        max_event_exposure_usd: env_opt("MAX_EVENT_EXPOSURE_USD"),
+        max_open_positions: env_opt("MAX_OPEN_POSITIONS"),
+        max_drawdown_percent: env_opt("MAX_DRAWDOWN_PERCENT"),
+        category_allocation,
+    })
+}
+
+// Keyword match on slug/title words (cheap & deterministic, no extra API call per trade)
+pub fn market_category(title: Option<&str>, slug: Option<&str>, event_slug: Option<&str>) -> &'static str {
+    const CRYPTO: &[&str] = &[
+        "bitcoin", "btc", "ethereum", "eth", "solana", "sol", "xrp", "crypto", "doge",
+        "dogecoin", "bnb", "cardano", "memecoin", "altcoin",
+    ];
+    const SPORTS: &[&str] = &[
+        "nfl", "nba", "wnba", "mlb", "nhl", "ufc", "mma", "boxing", "soccer", "football",
+        "basketball", "baseball", "hockey", "tennis", "golf", "f1", "nascar", "epl", "laliga",
+        "bundesliga", "uefa", "fifa", "olympics", "superbowl", "bowl", "playoffs", "championship",
+    ];
+    const POLITICS: &[&str] = &[
+        "election", "president", "presidential", "trump", "biden", "harris", "senate",
+        "congress", "governor", "mayor", "primary", "democrat", "democratic", "republican",
+        "gop", "parliament", "minister", "nominee", "impeach", "cabinet", "electoral",
+    ];
+
+    let text = [title, slug, event_slug]
+        .iter()
+        .flatten()
+        .map(|s| s.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let has = |list: &[&str]| words.iter().any(|w| list.contains(w));
+
+    if has(CRYPTO) {
+        "crypto"
+    } else if has(SPORTS) {
+        "sports"
+    } else if has(POLITICS) {
+        "politics"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_category_allocation() {
+        let parsed = parse_category_allocation(" Sports:20, politics:40 ,crypto:30,").unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed["sports"], 20.0);
+        assert_eq!(parsed["politics"], 40.0);
+        assert_eq!(parsed["crypto"], 30.0);
+    }
+
+    #[test]
+    fn empty_allocation_is_empty() {
+        assert!(parse_category_allocation("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_allocation_entries() {
+        assert!(parse_category_allocation("sports").is_err());
+        assert!(parse_category_allocation("weather:10").is_err());
+        assert!(parse_category_allocation("sports:lots").is_err());
+        assert!(parse_category_allocation("sports:120").is_err());
+        assert!(parse_category_allocation("sports:-5").is_err());
+    }
+
+    #[test]
+    fn categorizes_by_keyword() {
+        assert_eq!(market_category(Some("Bitcoin Up or Down"), None, None), "crypto");
+        assert_eq!(market_category(None, Some("nba-lakers-vs-celtics"), None), "sports");
+        assert_eq!(market_category(Some("Who will win the presidential election?"), None, None), "politics");
+        assert_eq!(market_category(Some("Will it rain in NYC?"), None, None), "other");
+    }
+
+    #[test]
+    fn short_tickers_match_whole_words_only() {
+        assert_eq!(market_category(Some("Will ETH hit $5k?"), None, None), "crypto");
+        assert_eq!(market_category(None, Some("sol-above-200"), None), "crypto");
+        // "eth" and "sol" inside longer words aren't tickers
+        assert_eq!(market_category(Some("Senate ethics probe"), None, None), "politics");
+        assert_eq!(market_category(Some("Solar eclipse visible in Europe?"), None, None), "other");
+        assert_eq!(market_category(None, Some("ethiopia-gdp-growth"), None), "other");
+    }
+
+    #[test]
+    fn crypto_wins_over_other_categories() {
+        assert_eq!(market_category(Some("Will Trump launch a memecoin?"), None, None), "crypto");
+        assert_eq!(market_category(Some("NBA Finals"), None, Some("btc-halftime-show")), "crypto");
+    }
+}
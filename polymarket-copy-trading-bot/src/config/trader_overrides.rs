@@ -0,0 +1,360 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::copy_strategy::{parse_tiered_multipliers, CopyStrategy, CopyStrategyConfig};
+use super::is_valid_ethereum_address;
+use crate::types::UserActivity;
+
+// Default per-trader config file (relative to the working dir, like .env)
+pub const DEFAULT_TRADER_CONFIG_PATH: &str = "traders.json";
+
+// One entry of traders.json - every field is optional & falls back to the global config
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct TraderOverrideEntry {
+    strategy: Option<String>,
+    copy_size: Option<f64>,
+    max_order_size_usd: Option<f64>,
+    min_order_size_usd: Option<f64>,
+    max_position_size_usd: Option<f64>,
+    adaptive_min_percent: Option<f64>,
+    adaptive_max_percent: Option<f64>,
+    adaptive_threshold_usd: Option<f64>,
+    // Same format as TIERED_MULTIPLIERS, e.g. "1-10:2.0,10-100:1.0,100+:0.5"
+    tiered_multipliers: Option<String>,
+    trade_multiplier: Option<f64>,
+    #[serde(default)]
+    buy_only: bool,
+    #[serde(default)]
+    sell_only: bool,
+    #[serde(default)]
+    include_markets: Vec<String>,
+    #[serde(default)]
+    exclude_markets: Vec<String>,
+}
+
+// Resolved settings for one copied trader (strategy already merged over the global one)
+#[derive(Debug, Clone)]
+pub struct TraderOverride {
+    pub copy_strategy: CopyStrategyConfig,
+    pub buy_only: bool,
+    pub sell_only: bool,
+    // Lowercase substrings matched against the market slug, event slug & title
+    pub include_markets: Vec<String>,
+    pub exclude_markets: Vec<String>,
+}
+
+impl TraderOverride {
+    // Why this trade shouldn't be copied for this trader (None = copy it)
+    pub fn skip_reason(&self, condition: &str, trade: &UserActivity) -> Option<String> {
+        if condition == "buy" {
+            if self.sell_only {
+                return Some("trader is sell-only".into());
+            }
+        } else if self.buy_only {
+            return Some("trader is buy-only".into());
+        }
+
+        // Market lists only gate new buys, so positions we already hold can still be exited
+        if condition != "buy" {
+            return None;
+        }
+        let haystack = [
+            trade.slug.as_deref(),
+            trade.event_slug.as_deref(),
+            trade.title.as_deref(),
+        ]
+        .iter()
+        .flatten()
+        .map(|s| s.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ");
+        if let Some(p) = self.exclude_markets.iter().find(|p| haystack.contains(p.as_str())) {
+            return Some(format!("market matches exclude \"{}\"", p));
+        }
+        if !self.include_markets.is_empty()
+            && !self.include_markets.iter().any(|p| haystack.contains(p.as_str()))
+        {
+            return Some("market not in include list".into());
+        }
+        None
+    }
+}
+
+fn parse_strategy(s: &str) -> Result<CopyStrategy> {
+    match s.trim().to_uppercase().as_str() {
+        "PERCENTAGE" => Ok(CopyStrategy::Percentage),
+        "FIXED" => Ok(CopyStrategy::Fixed),
+        "ADAPTIVE" => Ok(CopyStrategy::Adaptive),
+        other => anyhow::bail!("Unknown strategy '{}' (use PERCENTAGE, FIXED or ADAPTIVE)", other),
+    }
+}
+
+// Copy strategy fields of a JSON config entry (traders.json, accounts.json); unset = keep the base
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(super) struct StrategyOverride {
+    strategy: Option<String>,
+    copy_size: Option<f64>,
+    max_order_size_usd: Option<f64>,
+    min_order_size_usd: Option<f64>,
+    max_position_size_usd: Option<f64>,
+    adaptive_min_percent: Option<f64>,
+    adaptive_max_percent: Option<f64>,
+    adaptive_threshold_usd: Option<f64>,
+    tiered_multipliers: Option<String>,
+    trade_multiplier: Option<f64>,
+}
+
+impl StrategyOverride {
+    pub(super) fn apply(&self, base: &CopyStrategyConfig) -> Result<CopyStrategyConfig> {
+        let mut cfg = base.clone();
+        if let Some(ref s) = self.strategy {
+            cfg.strategy = parse_strategy(s)?;
+        }
+        if let Some(v) = self.copy_size {
+            cfg.copy_size = v;
+        }
+        if let Some(v) = self.max_order_size_usd {
+            cfg.max_order_size_usd = v;
+        }
+        if let Some(v) = self.min_order_size_usd {
+            cfg.min_order_size_usd = v;
+        }
+        if self.max_position_size_usd.is_some() {
+            cfg.max_position_size_usd = self.max_position_size_usd;
+        }
+        if let Some(ref tiers) = self.tiered_multipliers {
+            cfg.tiered_multipliers = Some(parse_tiered_multipliers(tiers)?);
+        }
+        if let Some(m) = self.trade_multiplier {
+            cfg.trade_multiplier = if (m - 1.0).abs() > 1e-9 { Some(m) } else { None };
+        }
+        if cfg.strategy == CopyStrategy::Adaptive {
+            cfg.adaptive_min_percent = self
+                .adaptive_min_percent
+                .or(cfg.adaptive_min_percent)
+                .or(Some(cfg.copy_size));
+            cfg.adaptive_max_percent = self
+                .adaptive_max_percent
+                .or(cfg.adaptive_max_percent)
+                .or(Some(cfg.copy_size));
+            cfg.adaptive_threshold = self
+                .adaptive_threshold_usd
+                .or(cfg.adaptive_threshold)
+                .or(Some(500.0));
+        }
+        if cfg.copy_size <= 0.0 {
+            anyhow::bail!("copySize must be > 0");
+        }
+        if cfg.max_order_size_usd < cfg.min_order_size_usd {
+            anyhow::bail!("maxOrderSizeUsd must be >= minOrderSizeUsd");
+        }
+        Ok(cfg)
+    }
+}
+
+fn resolve(entry: TraderOverrideEntry, global: &CopyStrategyConfig) -> Result<TraderOverride> {
+    if entry.buy_only && entry.sell_only {
+        anyhow::bail!("buyOnly and sellOnly can't both be set");
+    }
+
+    let cfg = StrategyOverride {
+        strategy: entry.strategy,
+        copy_size: entry.copy_size,
+        max_order_size_usd: entry.max_order_size_usd,
+        min_order_size_usd: entry.min_order_size_usd,
+        max_position_size_usd: entry.max_position_size_usd,
+        adaptive_min_percent: entry.adaptive_min_percent,
+        adaptive_max_percent: entry.adaptive_max_percent,
+        adaptive_threshold_usd: entry.adaptive_threshold_usd,
+        tiered_multipliers: entry.tiered_multipliers,
+        trade_multiplier: entry.trade_multiplier,
+    }
+    .apply(global)?;
+
+    let normalize = |list: Vec<String>| -> Vec<String> {
+        list.into_iter()
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect()
+    };
+    Ok(TraderOverride {
+        copy_strategy: cfg,
+        buy_only: entry.buy_only,
+        sell_only: entry.sell_only,
+        include_markets: normalize(entry.include_markets),
+        exclude_markets: normalize(entry.exclude_markets),
+    })
+}
+
+// Load traders.json ({"0xaddr": {...}, ...}), keyed by lowercase address
+pub fn load_trader_overrides(
+    path: &Path,
+    global: &CopyStrategyConfig,
+) -> Result<HashMap<String, TraderOverride>> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Cannot read trader config {}", path.display()))?;
+    let entries: HashMap<String, TraderOverrideEntry> = serde_json::from_str(&raw)
+        .with_context(|| format!("Invalid trader config {}", path.display()))?;
+
+    let mut out = HashMap::new();
+    for (address, entry) in entries {
+        let address = address.trim().to_lowercase();
+        if !is_valid_ethereum_address(&address) {
+            anyhow::bail!("Invalid address in {}: {}", path.display(), address);
+        }
+        let resolved = resolve(entry, global)
+            .with_context(|| format!("Invalid settings for {} in {}", address, path.display()))?;
+        out.insert(address, resolved);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> CopyStrategyConfig {
+        CopyStrategyConfig {
+            strategy: CopyStrategy::Percentage,
+            copy_size: 10.0,
+            max_order_size_usd: 100.0,
+            min_order_size_usd: 1.0,
+            max_position_size_usd: None,
+            max_daily_volume_usd: None,
+            adaptive_min_percent: None,
+            adaptive_max_percent: None,
+            adaptive_threshold: None,
+            tiered_multipliers: None,
+            trade_multiplier: None,
+        }
+    }
+
+    fn trader(entry: serde_json::Value) -> TraderOverride {
+        resolve(serde_json::from_value(entry).unwrap(), &base()).unwrap()
+    }
+
+    fn trade(slug: &str, title: &str) -> UserActivity {
+        serde_json::from_value(serde_json::json!({
+            "slug": slug,
+            "eventSlug": "nba-finals-2025",
+            "title": title,
+        }))
+        .unwrap()
+    }
+
+    fn strategy(entry: serde_json::Value) -> Result<CopyStrategyConfig> {
+        serde_json::from_value::<StrategyOverride>(entry).unwrap().apply(&base())
+    }
+
+    #[test]
+    fn default_trader_copies_everything() {
+        let t = trader(serde_json::json!({}));
+        let trade = trade("lakers-vs-celtics", "Lakers vs. Celtics");
+        for condition in ["buy", "sell", "merge"] {
+            assert_eq!(t.skip_reason(condition, &trade), None);
+        }
+    }
+
+    #[test]
+    fn buy_only_skips_sells_and_merges() {
+        let t = trader(serde_json::json!({ "buyOnly": true }));
+        let trade = trade("lakers-vs-celtics", "Lakers vs. Celtics");
+        assert_eq!(t.skip_reason("buy", &trade), None);
+        assert_eq!(t.skip_reason("sell", &trade).as_deref(), Some("trader is buy-only"));
+        assert_eq!(t.skip_reason("merge", &trade).as_deref(), Some("trader is buy-only"));
+    }
+
+    #[test]
+    fn sell_only_skips_buys() {
+        let t = trader(serde_json::json!({ "sellOnly": true }));
+        let trade = trade("lakers-vs-celtics", "Lakers vs. Celtics");
+        assert_eq!(t.skip_reason("buy", &trade).as_deref(), Some("trader is sell-only"));
+        assert_eq!(t.skip_reason("sell", &trade), None);
+    }
+
+    #[test]
+    fn buy_only_and_sell_only_conflict() {
+        let entry = serde_json::from_value(serde_json::json!({ "buyOnly": true, "sellOnly": true })).unwrap();
+        assert!(resolve(entry, &base()).is_err());
+    }
+
+    #[test]
+    fn include_list_gates_buys_on_slug_event_or_title() {
+        let t = trader(serde_json::json!({ "includeMarkets": [" NBA "] }));
+        // Matched through the event slug, case-insensitively
+        assert_eq!(t.skip_reason("buy", &trade("lakers-vs-celtics", "Lakers vs. Celtics")), None);
+
+        let t = trader(serde_json::json!({ "includeMarkets": ["bitcoin"] }));
+        assert_eq!(
+            t.skip_reason("buy", &trade("lakers-vs-celtics", "Lakers vs. Celtics")).as_deref(),
+            Some("market not in include list")
+        );
+        assert_eq!(t.skip_reason("buy", &trade("btc-updown", "Bitcoin Up or Down")), None);
+    }
+
+    #[test]
+    fn exclude_list_wins_over_include_list() {
+        let t = trader(serde_json::json!({ "includeMarkets": ["nba"], "excludeMarkets": ["celtics"] }));
+        assert_eq!(
+            t.skip_reason("buy", &trade("lakers-vs-celtics", "Lakers vs. Celtics")).as_deref(),
+            Some("market matches exclude \"celtics\"")
+        );
+    }
+
+    #[test]
+    fn market_lists_never_block_exits() {
+        let t = trader(serde_json::json!({ "includeMarkets": ["bitcoin"], "excludeMarkets": ["celtics"] }));
+        let trade = trade("lakers-vs-celtics", "Lakers vs. Celtics");
+        assert_eq!(t.skip_reason("sell", &trade), None);
+        assert_eq!(t.skip_reason("merge", &trade), None);
+    }
+
+    #[test]
+    fn adaptive_defaults_to_copy_size_and_500_threshold() {
+        let cfg = strategy(serde_json::json!({ "strategy": "adaptive", "copySize": 5.0 })).unwrap();
+        assert_eq!(cfg.strategy, CopyStrategy::Adaptive);
+        assert_eq!(cfg.adaptive_min_percent, Some(5.0));
+        assert_eq!(cfg.adaptive_max_percent, Some(5.0));
+        assert_eq!(cfg.adaptive_threshold, Some(500.0));
+    }
+
+    #[test]
+    fn adaptive_keeps_explicit_and_base_values() {
+        let mut global = base();
+        global.strategy = CopyStrategy::Adaptive;
+        global.adaptive_max_percent = Some(20.0);
+        global.adaptive_threshold = Some(250.0);
+        let cfg = serde_json::from_value::<StrategyOverride>(serde_json::json!({ "adaptiveMinPercent": 2.0 }))
+            .unwrap()
+            .apply(&global)
+            .unwrap();
+        assert_eq!(cfg.adaptive_min_percent, Some(2.0));
+        assert_eq!(cfg.adaptive_max_percent, Some(20.0));
+        assert_eq!(cfg.adaptive_threshold, Some(250.0));
+    }
+
+    #[test]
+    fn non_adaptive_strategy_leaves_adaptive_fields_unset() {
+        let cfg = strategy(serde_json::json!({ "strategy": "FIXED", "adaptiveMinPercent": 2.0 })).unwrap();
+        assert_eq!(cfg.strategy, CopyStrategy::Fixed);
+        assert_eq!(cfg.adaptive_min_percent, None);
+        assert_eq!(cfg.adaptive_threshold, None);
+    }
+
+    #[test]
+    fn trade_multiplier_of_one_is_dropped() {
+        assert_eq!(strategy(serde_json::json!({ "tradeMultiplier": 1.0 })).unwrap().trade_multiplier, None);
+        assert_eq!(strategy(serde_json::json!({ "tradeMultiplier": 2.0 })).unwrap().trade_multiplier, Some(2.0));
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected() {
+        assert!(strategy(serde_json::json!({ "strategy": "martingale" })).is_err());
+        assert!(strategy(serde_json::json!({ "copySize": 0.0 })).is_err());
+        assert!(strategy(serde_json::json!({ "maxOrderSizeUsd": 0.5 })).is_err());
+    }
+}
@@ -6,13 +6,15 @@ use mongodb::{
 };
 use std::sync::Arc;
 
-use crate::types::{UserActivity, UserPosition};
+use crate::types::{RiskFill, UserActivity, UserPosition};
 
 // MongoDB wrapper - stores trades & positions per trader
 #[derive(Clone)]
 pub struct Db {
     _client: Arc<Client>,
     db: Database,
+    // "" for the main wallet, "_<account>" for extra accounts (copied trades & risk state are per wallet)
+    suffix: String,
 }
 
 impl Db {
@@ -23,12 +25,26 @@ impl Db {
         Ok(Self {
             _client: Arc::new(client),
             db: db.clone(),
+            suffix: String::new(),
         })
     }
 
+    // Same connection, scoped to one of our accounts; trader positions & configs stay shared
+    pub fn for_account(&self, account: &str) -> Self {
+        let suffix = if account == crate::config::PRIMARY_ACCOUNT {
+            String::new()
+        } else {
+            format!("_{}", account)
+        };
+        Self {
+            suffix,
+            ..self.clone()
+        }
+    }
+
     // Get collection for trader's activities (one per trader)
     pub fn activity_collection(&self, user_address: &str) -> Collection<UserActivity> {
-        let name = format!("user_activities_{}", user_address.to_lowercase());
+        let name = format!("user_activities_{}{}", user_address.to_lowercase(), self.suffix);
         self.db.collection(&name)
     }
 
@@ -44,11 +60,18 @@ impl Db {
         Ok(coll.estimated_document_count(None).await?)
     }
 
-    // Insert new trade activity
-    pub async fn insert_activity(&self, user_address: &str, activity: &UserActivity) -> Result<()> {
+    // Insert new trade activity, returns its _id
+    pub async fn insert_activity(
+        &self,
+        user_address: &str,
+        activity: &UserActivity,
+    ) -> Result<mongodb::bson::oid::ObjectId> {
         let coll = self.activity_collection(user_address);
-        coll.insert_one(activity, None).await?;
-        Ok(())
+        let result = coll.insert_one(activity, None).await?;
+        result
+            .inserted_id
+            .as_object_id()
+            .ok_or_else(|| anyhow::anyhow!("Inserted activity has no ObjectId"))
     }
 
     // Find activity by tx hash (duplicate check)
@@ -62,13 +85,14 @@ impl Db {
         Ok(coll.find_one(filter, None).await?)
     }
 
-    // Find unprocessed trades (not executed by bot yet)
+    // Find unprocessed trades (not executed by bot yet, not claimed by an executor)
     pub async fn find_unprocessed_trades(&self, user_address: &str) -> Result<Vec<UserActivity>> {
         let coll = self.activity_collection(user_address);
         let filter = doc! {
             "type": "TRADE",
             "bot": false,
-            "botExcutedTime": 0_i64
+            "botExcutedTime": 0_i64,
+            "claimedAt": { "$exists": false }
         };
         let mut cursor = coll.find(filter, None).await?;
         let mut out = Vec::new();
@@ -91,14 +115,79 @@ impl Db {
         Ok(())
     }
 
-    pub async fn mark_historical_processed(&self, user_address: &str) -> Result<u64> {
+    // Atomically claim a pending trade for execution - true only for the one caller that wins
+    pub async fn claim_activity(
+        &self,
+        user_address: &str,
+        id: &mongodb::bson::oid::ObjectId,
+    ) -> Result<bool> {
         let coll = self.activity_collection(user_address);
-        let filter = doc! { "bot": false };
+        let filter = doc! {
+            "_id": id,
+            "bot": false,
+            "botExcutedTime": 0_i64,
+            "claimedAt": { "$exists": false }
+        };
+        let update = doc! { "$set": { "claimedAt": chrono::Utc::now().timestamp_millis() } };
+        let result = coll.update_one(filter, update, None).await?;
+        Ok(result.modified_count == 1)
+    }
+
+    // Hand a claimed trade back to the queue (only when nothing was sent for it yet)
+    pub async fn release_claim(
+        &self,
+        user_address: &str,
+        id: &mongodb::bson::oid::ObjectId,
+    ) -> Result<()> {
+        let coll = self.activity_collection(user_address);
+        let filter = doc! { "_id": id, "bot": false };
+        coll.update_one(filter, doc! { "$unset": { "claimedAt": "" } }, None)
+            .await?;
+        Ok(())
+    }
+
+    // Trades claimed but never finished (bot stopped mid-trade) - not retried, orders may be out
+    pub async fn count_unfinished_claims(&self, user_address: &str) -> Result<u64> {
+        let coll = self.activity_collection(user_address);
+        let filter = doc! { "bot": false, "claimedAt": { "$exists": true } };
+        Ok(coll.count_documents(filter, None).await?)
+    }
+
+    // Mark pending trades older than the cutoff as processed (too old to copy)
+    pub async fn mark_historical_processed(&self, user_address: &str, cutoff_ms: i64) -> Result<u64> {
+        let coll = self.activity_collection(user_address);
+        // RTDS timestamps come in seconds or ms; anything without one is treated as old
+        let filter = doc! {
+            "bot": false,
+            "claimedAt": { "$exists": false },
+            "$or": [
+                { "timestamp": null },
+                { "timestamp": { "$lt": cutoff_ms / 1000 } },
+                { "timestamp": { "$gt": 1_000_000_000_000_i64, "$lt": cutoff_ms } }
+            ]
+        };
         let update = doc! { "$set": { "bot": true, "botExcutedTime": 999_i64 } };
         let result = coll.update_many(filter, update, None).await?;
         Ok(result.modified_count)
     }
 
+    // Most recent trades that were not copied because of a filter (newest first)
+    pub async fn find_skipped_trades(&self, user_address: &str, limit: i64) -> Result<Vec<UserActivity>> {
+        let coll = self.activity_collection(user_address);
+        let opts = mongodb::options::FindOptions::builder()
+            .sort(doc! { "timestamp": -1 })
+            .limit(limit)
+            .build();
+        let mut cursor = coll
+            .find(doc! { "skipReason": { "$exists": true } }, opts)
+            .await?;
+        let mut out = Vec::new();
+        while cursor.advance().await? {
+            out.push(cursor.deserialize_current()?);
+        }
+        Ok(out)
+    }
+
     pub async fn upsert_position(&self, user_address: &str, position: &UserPosition) -> Result<()> {
         let coll = self.position_collection(user_address);
         let filter = doc! {
@@ -127,54 +216,65 @@ impl Db {
         self.db.collection("configs")
     }
 
-    async fn get_next_sequence_number(&self, key_prefix: &str) -> Result<u32> {
+    fn private_key_filter() -> mongodb::bson::Document {
+        doc! { "key": { "$regex": "^PRIVATE_KEY(_\\d+)?$", "$options": "i" } }
+    }
+
+    // Plaintext keys written by older versions (PRIVATE_KEY_1, PRIVATE_KEY_2, ...)
+    pub async fn count_private_keys(&self) -> Result<u64> {
         let coll = self.config_collection();
-        let filter = doc! {
-            "key": { "$regex": format!("^{}_\\d+$", key_prefix) }
-        };
-        let mut cursor = coll.find(filter, None).await?;
-        let mut numbers = Vec::new();
-        while cursor.advance().await? {
-            let doc = cursor.deserialize_current()?;
-            if let Ok(key) = doc.get_str("key") {
-                if let Some(underscore_pos) = key.rfind('_') {
-                    if let Ok(num) = key[underscore_pos + 1..].parse::<u32>() {
-                        numbers.push(num);
-                    }
-                }
-            }
-        }
-        Ok(if numbers.is_empty() {
-            1
-        } else {
-            numbers.iter().max().unwrap() + 1
-        })
+        Ok(coll.count_documents(Self::private_key_filter(), None).await?)
     }
 
-    pub async fn set_config(&self, key: &str, value: &str) -> Result<()> {
+    // Migration: delete every plaintext key document, returns how many were removed
+    pub async fn scrub_private_keys(&self) -> Result<u64> {
         let coll = self.config_collection();
-        let mut final_key = key.to_string();
-
-        if key == "PRIVATE_KEY" {
-            let filter = doc! {
-                "key": { "$regex": "^PRIVATE_KEY_\\d+$" },
-                "value": value
-            };
-            let existing = coll.find_one(filter, None).await?;
-            if existing.is_some() {
-                return Ok(());
-            }
-
-            let sequence_number = self.get_next_sequence_number("PRIVATE_KEY").await?;
-            final_key = format!("PRIVATE_KEY_{}", sequence_number);
+        let result = coll.delete_many(Self::private_key_filter(), None).await?;
+        Ok(result.deleted_count)
+    }
+
+    pub fn risk_fill_collection(&self) -> Collection<RiskFill> {
+        self.db.collection(&format!("risk_fills{}", self.suffix))
+    }
+
+    fn risk_state_collection(&self) -> Collection<mongodb::bson::Document> {
+        self.db.collection(&format!("risk_state{}", self.suffix))
+    }
+
+    pub async fn insert_risk_fill(&self, fill: &RiskFill) -> Result<()> {
+        self.risk_fill_collection().insert_one(fill, None).await?;
+        Ok(())
+    }
+
+    // Total copied buy volume for a UTC day (optionally one trader only)
+    pub async fn sum_risk_fills(&self, day: &str, trader: Option<&str>) -> Result<f64> {
+        let mut filter = doc! { "day": day };
+        if let Some(t) = trader {
+            filter.insert("trader", t.to_lowercase());
+        }
+        let mut cursor = self.risk_fill_collection().find(filter, None).await?;
+        let mut total = 0.0;
+        while cursor.advance().await? {
+            total += cursor.deserialize_current()?.usdc;
         }
+        Ok(total)
+    }
+
+    // Equity high-water mark for the drawdown limit (risk_state collection)
+    pub async fn get_high_water_mark(&self) -> Result<Option<f64>> {
+        let coll = self.risk_state_collection();
+        let found = coll.find_one(doc! { "_id": "equity_high_water_mark" }, None).await?;
+        Ok(found.and_then(|d| d.get_f64("value").ok()))
+    }
 
-        let doc = doc! {
-            "key": &final_key,
-            "value": value,
-            "timestamp": mongodb::bson::DateTime::now(),
+    pub async fn set_high_water_mark(&self, value: f64) -> Result<()> {
+        let coll = self.risk_state_collection();
+        let update = doc! {
+            "$set": { "value": value, "updatedAt": mongodb::bson::DateTime::now() }
         };
-        coll.insert_one(doc, None).await?;
+        let opts = mongodb::options::UpdateOptions::builder().upsert(true).build();
+        coll.update_one(doc! { "_id": "equity_high_water_mark" }, update, opts)
+            .await?;
         Ok(())
     }
 
@@ -9,7 +9,10 @@ use tokio::signal;
 
 use config::EnvConfig;
 use db::Db;
-use services::{run_trade_executor, run_trade_monitor, stop_trade_executor, stop_trade_monitor};
+use services::{
+    run_redemption_loop, run_trade_executor, run_trade_monitor, stop_trade_executor,
+    stop_trade_monitor, trade_channel, AccountFeed,
+};
 use utils::{get_usdc_balance, is_contract_address, perform_health_check, Logger};
 
 #[tokio::main]
@@ -31,14 +34,27 @@ async fn main() -> Result<()> {
 
     // Load config & connect to DB
     let config = EnvConfig::from_env().await?;
+    // Extra wallets from accounts.json, copying the same traders (keys unlocked up front)
+    let extra_accounts = config.load_accounts()?;
     let db = Db::connect(&config.mongo_uri).await?;
 
-    // Store priv key in DB (encrypted, obv)
-    if let Err(e) = db.set_config("PRIVATE_KEY", &config.private_key).await {
-        Logger::error(&format!("Failed to store PRIVATE_KEY in database: {}", e));
+    // Older versions stored the key in plaintext in `configs`; nag until it's scrubbed
+    match db.count_private_keys().await {
+        Ok(0) => {}
+        Ok(n) => Logger::warning(&format!(
+            "Found {} plaintext PRIVATE_KEY document(s) in MongoDB. Run `make scrub-private-keys` and rotate the wallet",
+            n
+        )),
+        Err(e) => Logger::error(&format!("Failed to check configs for stored keys: {}", e)),
     }
 
     Logger::startup(&config.user_addresses, &config.proxy_wallet);
+    for account in &extra_accounts {
+        Logger::info(&format!(
+            "Also copying with account {}: {}",
+            account.account_name, account.proxy_wallet
+        ));
+    }
 
     // Run health checks - DB, RPC, balance, Polymarket API
     Logger::info("Running system check…");
@@ -113,20 +129,38 @@ async fn main() -> Result<()> {
         .timeout(std::time::Duration::from_millis(config.request_timeout_ms))
         .build()?;
 
+    // One monitor feeds every account; each has its own outbox in Mongo & its own executor
+    let accounts: Vec<EnvConfig> = std::iter::once(config.clone()).chain(extra_accounts).collect();
+    let mut feeds = Vec::new();
+    let mut executors = Vec::new();
+    for account in accounts {
+        let (trade_tx, trade_rx) = trade_channel();
+        let account_db = db.for_account(&account.account_name);
+        feeds.push(AccountFeed {
+            name: account.account_name.clone(),
+            db: account_db.clone(),
+            trades: trade_tx,
+        });
+        executors.push((account, account_db, trade_rx));
+    }
+
     // Start monitor (watches for new trades via RTDS)
     Logger::info("Starting trade monitor...");
-    let _monitor_handle = run_trade_monitor(&config, &db, &http_client).await?;
+    let _monitor_handle = run_trade_monitor(&config, &db, &http_client, feeds).await?;
 
-    // Start executor (processes trades & executes orders)
+    // Start executors (process trades & execute orders) - an account that fails only stops itself
     Logger::info("Starting trade executor...");
-    let config_clone = config.clone();
-    let db_clone = db.clone();
-    let http_clone = http_client.clone();
-    let executor_handle = tokio::spawn(async move {
-        if let Err(e) = run_trade_executor(&config_clone, &db_clone, &http_clone).await {
-            Logger::error(&format!("Trade executor error: {}", e));
-        }
-    });
+    let mut handles = Vec::new();
+    for (account, account_db, trade_rx) in executors {
+        let http_clone = http_client.clone();
+        // Redeem resolved positions in the background (REDEEM_INTERVAL_MINUTES = 0 turns it off)
+        handles.push(tokio::spawn(run_redemption_loop(account.clone(), http_client.clone())));
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = run_trade_executor(&account, &account_db, &http_clone, trade_rx).await {
+                Logger::error(&format!("{}Trade executor error: {}", account.log_prefix(), e));
+            }
+        }));
+    }
 
     // Wait for Ctrl+C, then graceful shutdown
     match signal::ctrl_c().await {
@@ -140,7 +174,9 @@ async fn main() -> Result<()> {
     stop_trade_monitor();
     stop_trade_executor();
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-    executor_handle.abort();
+    for handle in &handles {
+        handle.abort();
+    }
     let _ = db.close().await;
     Logger::success("Goodbye.");
     Ok(())
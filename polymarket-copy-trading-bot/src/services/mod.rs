@@ -1,5 +1,13 @@
+pub mod redemption;
+mod risk_manager;
 mod trade_executor;
+mod trade_filter;
 mod trade_monitor;
 
-pub use trade_executor::{run_trade_executor, stop_trade_executor};
-pub use trade_monitor::{run_trade_monitor, stop_trade_monitor};
+pub use redemption::run_redemption_loop;
+pub use risk_manager::RiskManager;
+pub use trade_executor::{
+    get_aggregation_key, run_trade_executor, should_aggregate, stop_trade_executor, trade_channel,
+    TradeWithUser, TRADE_AGGREGATION_MIN_TOTAL_USD,
+};
+pub use trade_monitor::{run_trade_monitor, stop_trade_monitor, AccountFeed};
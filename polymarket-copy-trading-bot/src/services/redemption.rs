@@ -0,0 +1,500 @@
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use alloy::sol_types::SolCall;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use crate::config::EnvConfig;
+use crate::types::UserPosition;
+use crate::utils::{fetch_positions, position_label, Logger};
+
+const POLYGON_CHAIN_ID: u64 = 137;
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface IConditionalTokens {
+        function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets) external;
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function isApprovedForAll(address account, address operator) external view returns (bool);
+        function setApprovalForAll(address operator, bool approved) external;
+    }
+
+    #[sol(rpc)]
+    interface INegRiskAdapter {
+        function redeemPositions(bytes32 conditionId, uint256[] amounts) external;
+    }
+
+    #[sol(rpc)]
+    #[allow(clippy::too_many_arguments)]
+    interface IGnosisSafe {
+        function getThreshold() external view returns (uint256);
+        function isOwner(address owner) external view returns (bool);
+        function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures) external payable returns (bool success);
+    }
+}
+
+// What a redemption pass found & did
+#[derive(Debug, Clone, Default)]
+pub struct RedemptionReport {
+    pub markets: usize,
+    pub redeemed: usize,
+    pub failed: usize,
+    pub value_usd: f64,
+}
+
+// Sends redemption txs from PROXY_WALLET: directly for an EOA, through execTransaction for a Safe
+struct Redeemer {
+    provider: DynProvider,
+    owner: Address,
+    holder: Address,
+    is_safe: bool,
+    ctf: Address,
+    neg_risk_adapter: Address,
+    collateral: Address,
+}
+
+impl Redeemer {
+    async fn new(config: &EnvConfig) -> Result<Self> {
+        Self::connect(
+            &config.rpc_url,
+            &config.private_key,
+            &config.proxy_wallet,
+            Address::from_str(&config.ctf_contract_address)?,
+            Address::from_str(&config.neg_risk_adapter_address)?,
+            Address::from_str(&config.usdc_contract_address)?,
+        )
+        .await
+    }
+
+    async fn connect(
+        rpc_url: &str,
+        private_key: &str,
+        proxy_wallet: &str,
+        ctf: Address,
+        neg_risk_adapter: Address,
+        collateral: Address,
+    ) -> Result<Self> {
+        let signer = PrivateKeySigner::from_str(&format!("0x{}", private_key))
+            .map_err(|e| anyhow::anyhow!("Invalid private key: {}", e))?
+            .with_chain_id(Some(POLYGON_CHAIN_ID));
+        let owner = signer.address();
+        let holder = Address::from_str(proxy_wallet)?;
+        let url: url::Url = rpc_url.parse()?;
+        let provider = ProviderBuilder::new()
+            .wallet(signer)
+            .with_chain_id(POLYGON_CHAIN_ID)
+            .connect_http(url)
+            .erased();
+
+        let is_safe = holder != owner;
+        if is_safe {
+            if provider.get_code_at(holder).await?.is_empty() {
+                anyhow::bail!(
+                    "PROXY_WALLET {} is neither the signer ({}) nor a contract wallet",
+                    holder,
+                    owner
+                );
+            }
+            // Only a Safe answers getThreshold; Polymarket's email/Magic proxies don't
+            let safe = IGnosisSafe::new(holder, &provider);
+            let Ok(threshold) = safe.getThreshold().call().await else {
+                anyhow::bail!("PROXY_WALLET {} is not a Gnosis Safe - unsupported proxy type", holder);
+            };
+            // Single-owner Safes only: the owner's own tx is the signature (see send)
+            if threshold != U256::from(1) {
+                anyhow::bail!("Safe {} needs {} signatures - only 1-of-n Safes are supported", holder, threshold);
+            }
+            if !safe.isOwner(owner).call().await? {
+                anyhow::bail!("Signer {} is not an owner of the Safe {}", owner, holder);
+            }
+        }
+
+        Ok(Self {
+            provider,
+            owner,
+            holder,
+            is_safe,
+            ctf,
+            neg_risk_adapter,
+            collateral,
+        })
+    }
+
+    // Execute a call as the holder, wait for the receipt & fail on revert
+    async fn send(&self, to: Address, data: Bytes) -> Result<B256> {
+        let tx = if self.is_safe {
+            // Pre-validated signature (r = owner, s = 0, v = 1): accepted because msg.sender is that owner
+            let mut signatures = Vec::with_capacity(65);
+            signatures.extend_from_slice(self.owner.into_word().as_slice());
+            signatures.extend_from_slice(B256::ZERO.as_slice());
+            signatures.push(1);
+            let call = IGnosisSafe::execTransactionCall {
+                to,
+                value: U256::ZERO,
+                data,
+                operation: 0,
+                safeTxGas: U256::ZERO,
+                baseGas: U256::ZERO,
+                gasPrice: U256::ZERO,
+                gasToken: Address::ZERO,
+                refundReceiver: Address::ZERO,
+                signatures: signatures.into(),
+            };
+            TransactionRequest::default()
+                .with_to(self.holder)
+                .with_input(call.abi_encode())
+        } else {
+            TransactionRequest::default().with_to(to).with_input(data)
+        };
+
+        let pending = self.provider.send_transaction(tx).await?;
+        let tx_hash = *pending.tx_hash();
+        let receipt = pending.get_receipt().await?;
+        if !receipt.status() {
+            anyhow::bail!("Transaction reverted: https://polygonscan.com/tx/0x{:x}", tx_hash);
+        }
+        Ok(tx_hash)
+    }
+
+    // Plain markets redeem both index sets on the CTF; neg-risk ones go through the adapter with exact amounts
+    async fn redeem_condition(&self, condition_id: &str, held: &[UserPosition]) -> Result<B256> {
+        let cid = B256::from_str(condition_id)?;
+        if !held.iter().any(|p| p.negative_risk.unwrap_or(false)) {
+            let call = IConditionalTokens::redeemPositionsCall {
+                collateralToken: self.collateral,
+                parentCollectionId: B256::ZERO,
+                conditionId: cid,
+                indexSets: vec![U256::from(1), U256::from(2)],
+            };
+            return self.send(self.ctf, call.abi_encode().into()).await;
+        }
+
+        // Adapter takes [yes, no] amounts & pulls the tokens from the holder
+        let ctf = IConditionalTokens::new(self.ctf, &self.provider);
+        let mut amounts = vec![U256::ZERO, U256::ZERO];
+        for p in held {
+            let (Some(asset), Some(idx)) = (p.asset.as_deref(), p.outcome_index) else {
+                continue;
+            };
+            if !(0..=1).contains(&idx) {
+                continue;
+            }
+            let token_id = U256::from_str(asset)?;
+            amounts[idx as usize] = ctf.balanceOf(self.holder, token_id).call().await?;
+        }
+        if amounts.iter().all(|a| a.is_zero()) {
+            anyhow::bail!("No on-chain balance left to redeem");
+        }
+        if !ctf
+            .isApprovedForAll(self.holder, self.neg_risk_adapter)
+            .call()
+            .await?
+        {
+            Logger::info("Approving the NegRiskAdapter to move outcome tokens...");
+            let approve = IConditionalTokens::setApprovalForAllCall {
+                operator: self.neg_risk_adapter,
+                approved: true,
+            };
+            self.send(self.ctf, approve.abi_encode().into()).await?;
+        }
+        let call = INegRiskAdapter::redeemPositionsCall {
+            conditionId: cid,
+            amounts,
+        };
+        self.send(self.neg_risk_adapter, call.abi_encode().into()).await
+    }
+}
+
+// Redeem every resolved position PROXY_WALLET holds; worthless ones are skipped (gas for $0)
+pub async fn redeem_resolved_positions(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    dry_run: bool,
+) -> Result<RedemptionReport> {
+    let positions = fetch_positions(config, http_client, &config.proxy_wallet).await?;
+
+    // One redemption per condition, however many outcomes we hold in it
+    let mut by_condition: BTreeMap<String, Vec<UserPosition>> = BTreeMap::new();
+    for p in positions.into_iter().filter(|p| p.redeemable.unwrap_or(false)) {
+        if let Some(cid) = p.condition_id.clone() {
+            by_condition.entry(cid).or_default().push(p);
+        }
+    }
+
+    let mut report = RedemptionReport::default();
+    let mut worthless = 0;
+    let mut to_redeem = Vec::new();
+    for (cid, held) in by_condition {
+        let value: f64 = held.iter().map(|p| p.current_value.unwrap_or(0.0)).sum();
+        if value < 0.01 {
+            worthless += 1;
+            continue;
+        }
+        for p in &held {
+            Logger::info(&format!(
+                "{} - {:.2} tokens, ~${:.2}{}",
+                position_label(p),
+                p.size.unwrap_or(0.0),
+                p.current_value.unwrap_or(0.0),
+                if p.negative_risk.unwrap_or(false) { " (neg-risk)" } else { "" }
+            ));
+        }
+        report.value_usd += value;
+        to_redeem.push((cid, held, value));
+    }
+    report.markets = to_redeem.len();
+    if worthless > 0 {
+        Logger::info(&format!("{} resolved market(s) paid out $0 - skipped", worthless));
+    }
+    if to_redeem.is_empty() || dry_run {
+        return Ok(report);
+    }
+
+    let redeemer = Redeemer::new(config).await?;
+    for (cid, held, value) in &to_redeem {
+        let label = held
+            .first()
+            .map(position_label)
+            .unwrap_or_else(|| cid.clone());
+        match redeemer.redeem_condition(cid, held).await {
+            Ok(tx_hash) => {
+                report.redeemed += 1;
+                Logger::success(&format!(
+                    "Redeemed {} (~${:.2}): https://polygonscan.com/tx/0x{:x}",
+                    label, value, tx_hash
+                ));
+            }
+            Err(e) => {
+                report.failed += 1;
+                Logger::error(&format!("Failed to redeem {}: {}", label, e));
+            }
+        }
+    }
+    Ok(report)
+}
+
+// Background redemption for the main bot (REDEEM_INTERVAL_MINUTES, 0 = off)
+pub async fn run_redemption_loop(config: EnvConfig, http_client: reqwest::Client) {
+    if config.redeem_interval_minutes == 0 {
+        return;
+    }
+    Logger::info(&format!(
+        "{}Auto-redeem enabled: every {} min",
+        config.log_prefix(),
+        config.redeem_interval_minutes
+    ));
+    let mut tick = interval(Duration::from_secs(config.redeem_interval_minutes * 60));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        match redeem_resolved_positions(&config, &http_client, false).await {
+            Ok(r) if r.markets == 0 => {}
+            Ok(r) => Logger::info(&format!(
+                "{}Auto-redeem: {}/{} market(s) redeemed (~${:.2}){}",
+                config.log_prefix(),
+                r.redeemed,
+                r.markets,
+                r.value_usd,
+                if r.failed > 0 { format!(", {} failed", r.failed) } else { String::new() }
+            )),
+            Err(e) => Logger::error(&format!("{}Auto-redeem failed: {}", config.log_prefix(), e)),
+        }
+    }
+}
+
+// Needs foundry's anvil on PATH: cargo test redemption -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::keccak256;
+    use std::net::{TcpListener, TcpStream};
+    use std::process::{Child, Command, Stdio};
+
+    // anvil's first dev account
+    const OWNER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const CONDITION_ID: &str = "0x4242424242424242424242424242424242424242424242424242424242424242";
+
+    // Hand-assembled mocks, so the test needs no solc. What they do, and the slots the asserts read:
+    // CTF: balanceOf -> 7; setApprovalForAll -> slot 2 = approved, slot 3 = caller;
+    //      isApprovedForAll(account, _) -> slot 2 && account == slot 3;
+    //      anything else (redeemPositions) -> slot 0 = caller, slot 1 = keccak256(calldata)
+    const MOCK_CTF: &str = "60003560e01c806300fdd58e14610039578063e985e9c514610044578063a22cb46514610058573660006000373660002060015533600055005b600760005260206000f35b600354600435146002541660005260206000f35b6024356002553360035500";
+    // NegRiskAdapter: reverts unless ctf (slot 9).isApprovedForAll(caller, this), then records like the CTF
+    const MOCK_ADAPTER: &str = "63e985e9c560e01b600052336004523060245260206080604460006009545afa6080511661002d5760006000fd5b366000600037366000206001553360005500";
+    // 1-of-1 Safe, owner in slot 0: getThreshold -> 1; isOwner; execTransaction -> reverts unless
+    // sent by the owner, then calls `to` with `data` and reverts if that call does
+    const MOCK_SAFE: &str = "60003560e01c8063e75235b81461002c5780632f54bf6e146100375780636a761202146100475760006000fd5b600160005260206000f35b6000546004351460005260206000f35b60005433146100565760006000fd5b600060006044356004018035906020018190600037600060006004355af161007e5760006000fd5b600160005260206000f3";
+    // Contract wallet without getThreshold (like Polymarket's proxy): reverts on every call
+    const MOCK_PROXY: &str = "60006000fd";
+
+    // Local anvil on a free port, killed on drop
+    struct Anvil {
+        child: Child,
+        url: String,
+        provider: DynProvider,
+    }
+
+    impl Anvil {
+        async fn start() -> Anvil {
+            let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+            let child = Command::new("anvil")
+                .args(["--port", &port.to_string(), "--chain-id", &POLYGON_CHAIN_ID.to_string(), "--silent"])
+                .stdout(Stdio::null())
+                .spawn()
+                .expect("anvil not found on PATH (install foundry)");
+            for _ in 0..100 {
+                if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            let url = format!("http://127.0.0.1:{}", port);
+            let provider = ProviderBuilder::new().connect_http(url.parse().unwrap()).erased();
+            Anvil { child, url, provider }
+        }
+
+        async fn deploy(&self, at: Address, code_hex: &str) {
+            let code = Bytes::from(alloy::hex::decode(code_hex).unwrap());
+            let _: serde_json::Value = self
+                .provider
+                .raw_request("anvil_setCode".into(), (at, code))
+                .await
+                .unwrap();
+        }
+
+        async fn set_slot(&self, at: Address, slot: u64, value: B256) {
+            let _: serde_json::Value = self
+                .provider
+                .raw_request("anvil_setStorageAt".into(), (at, U256::from(slot), value))
+                .await
+                .unwrap();
+        }
+
+        async fn slot(&self, at: Address, slot: u64) -> B256 {
+            self.provider.get_storage_at(at, U256::from(slot)).await.unwrap().into()
+        }
+
+        // Mock CTF + adapter; returns (ctf, adapter, collateral)
+        async fn deploy_markets(&self) -> (Address, Address, Address) {
+            let (ctf, adapter) = (Address::repeat_byte(0xc1), Address::repeat_byte(0xa1));
+            self.deploy(ctf, MOCK_CTF).await;
+            self.deploy(adapter, MOCK_ADAPTER).await;
+            self.set_slot(adapter, 9, ctf.into_word()).await;
+            (ctf, adapter, Address::repeat_byte(0x0c))
+        }
+
+        async fn redeemer(&self, proxy_wallet: Address, (ctf, adapter, collateral): (Address, Address, Address)) -> Redeemer {
+            Redeemer::connect(&self.url, OWNER_KEY, &proxy_wallet.to_string(), ctf, adapter, collateral)
+                .await
+                .unwrap()
+        }
+    }
+
+    impl Drop for Anvil {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn owner() -> Address {
+        PrivateKeySigner::from_str(OWNER_KEY).unwrap().address()
+    }
+
+    fn position(outcome_index: i32, negative_risk: bool) -> UserPosition {
+        serde_json::from_value(serde_json::json!({
+            "asset": (100 + outcome_index).to_string(),
+            "conditionId": CONDITION_ID,
+            "outcomeIndex": outcome_index,
+            "negativeRisk": negative_risk,
+        }))
+        .unwrap()
+    }
+
+    fn plain_redeem_calldata(collateral: Address) -> Vec<u8> {
+        IConditionalTokens::redeemPositionsCall {
+            collateralToken: collateral,
+            parentCollectionId: B256::ZERO,
+            conditionId: B256::from_str(CONDITION_ID).unwrap(),
+            indexSets: vec![U256::from(1), U256::from(2)],
+        }
+        .abi_encode()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn eoa_redeems_on_the_ctf() {
+        let anvil = Anvil::start().await;
+        let markets = anvil.deploy_markets().await;
+        let (ctf, _, collateral) = markets;
+        let redeemer = anvil.redeemer(owner(), markets).await;
+        assert!(!redeemer.is_safe);
+
+        redeemer.redeem_condition(CONDITION_ID, &[position(0, false)]).await.unwrap();
+        assert_eq!(anvil.slot(ctf, 0).await, owner().into_word());
+        assert_eq!(anvil.slot(ctf, 1).await, keccak256(plain_redeem_calldata(collateral)));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn neg_risk_approves_the_adapter_then_redeems_balances() {
+        let anvil = Anvil::start().await;
+        let markets = anvil.deploy_markets().await;
+        let (ctf, adapter, _) = markets;
+        let redeemer = anvil.redeemer(owner(), markets).await;
+
+        // The mock adapter reverts without the approval, so success means it was sent first
+        redeemer
+            .redeem_condition(CONDITION_ID, &[position(0, true), position(1, true)])
+            .await
+            .unwrap();
+        assert_eq!(anvil.slot(ctf, 2).await, B256::with_last_byte(1));
+        assert_eq!(anvil.slot(ctf, 3).await, owner().into_word());
+
+        let expected = INegRiskAdapter::redeemPositionsCall {
+            conditionId: B256::from_str(CONDITION_ID).unwrap(),
+            amounts: vec![U256::from(7), U256::from(7)],
+        };
+        assert_eq!(anvil.slot(adapter, 0).await, owner().into_word());
+        assert_eq!(anvil.slot(adapter, 1).await, keccak256(expected.abi_encode()));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn safe_redeems_through_exec_transaction() {
+        let anvil = Anvil::start().await;
+        let markets = anvil.deploy_markets().await;
+        let (ctf, _, collateral) = markets;
+        let safe = Address::repeat_byte(0x5a);
+        anvil.deploy(safe, MOCK_SAFE).await;
+        anvil.set_slot(safe, 0, owner().into_word()).await;
+        let redeemer = anvil.redeemer(safe, markets).await;
+        assert!(redeemer.is_safe);
+
+        // The CTF sees the Safe as the caller, not the owner's EOA
+        redeemer.redeem_condition(CONDITION_ID, &[position(0, false)]).await.unwrap();
+        assert_eq!(anvil.slot(ctf, 0).await, safe.into_word());
+        assert_eq!(anvil.slot(ctf, 1).await, keccak256(plain_redeem_calldata(collateral)));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil"]
+    async fn non_safe_contract_wallet_is_rejected() {
+        let anvil = Anvil::start().await;
+        let (ctf, adapter, collateral) = anvil.deploy_markets().await;
+        let proxy = Address::repeat_byte(0x9f);
+        anvil.deploy(proxy, MOCK_PROXY).await;
+
+        let result = Redeemer::connect(&anvil.url, OWNER_KEY, &proxy.to_string(), ctf, adapter, collateral).await;
+        match result {
+            Ok(_) => panic!("a contract without getThreshold was treated as a Safe"),
+            Err(e) => assert!(e.to_string().contains("unsupported proxy type"), "{}", e),
+        }
+    }
+}
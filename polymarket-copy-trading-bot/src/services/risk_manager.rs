@@ -0,0 +1,347 @@
+use anyhow::Result;
+
+use crate::config::{market_category, EnvConfig, OrderSizeCalculation, RiskConfig};
+use crate::db::Db;
+use crate::types::{RiskFill, UserActivity, UserPosition};
+use crate::utils::Logger;
+
+// Portfolio-level limits on top of the copy strategy; state lives in Mongo so it survives restarts
+pub struct RiskManager {
+    config: RiskConfig,
+    max_daily_volume_usd: Option<f64>,
+    db: Db,
+}
+
+// Clamp to what's left under a limit & note the outcome
+fn cap(calc: &mut OrderSizeCalculation, label: &str, used: f64, limit: f64) {
+    let remaining = (limit - used).max(0.0);
+    if remaining < calc.final_amount {
+        calc.final_amount = remaining;
+        calc.limited_by_risk = true;
+        calc.reasoning.push_str(&format!(
+            " → {}: ${:.2}/${:.2} used, reduced to ${:.2}",
+            label, used, limit, remaining
+        ));
+    } else {
+        calc.reasoning
+            .push_str(&format!(" → {}: ${:.2}/${:.2} ok", label, used, limit));
+    }
+}
+
+fn block(calc: &mut OrderSizeCalculation, why: &str) {
+    calc.final_amount = 0.0;
+    calc.limited_by_risk = true;
+    calc.reasoning.push_str(&format!(" → {}", why));
+}
+
+// High-water mark after seeing `equity`, and whether it moved (so it needs saving)
+fn high_water_mark(stored: Option<f64>, equity: f64) -> (f64, bool) {
+    match stored {
+        Some(h) if h >= equity => (h, false),
+        _ => (equity, true),
+    }
+}
+
+// Percent below the high-water mark (0 while there's no mark yet)
+fn drawdown_percent(hwm: f64, equity: f64) -> f64 {
+    if hwm > 0.0 {
+        (hwm - equity) / hwm * 100.0
+    } else {
+        0.0
+    }
+}
+
+// Open positions, and whether `asset` is already one of them
+fn open_positions(positions: &[UserPosition], asset: Option<&str>) -> (usize, bool) {
+    let open: Vec<&UserPosition> = positions
+        .iter()
+        .filter(|p| p.size.unwrap_or(0.0) > 0.0)
+        .collect();
+    let already_held = asset.is_some() && open.iter().any(|p| p.asset.as_deref() == asset);
+    (open.len(), already_held)
+}
+
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+impl RiskManager {
+    pub fn new(config: &EnvConfig, db: &Db) -> Self {
+        Self {
+            config: config.risk_config.clone(),
+            max_daily_volume_usd: config.copy_strategy_config.max_daily_volume_usd,
+            db: db.clone(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_daily_volume_usd.is_some() || self.config.is_enabled()
+    }
+
+    // Run every enabled check against a planned buy; each one appends its outcome to the reasoning
+    pub async fn check_buy(
+        &self,
+        calc: &mut OrderSizeCalculation,
+        trade: &UserActivity,
+        trader: &str,
+        balance: f64,
+        my_positions: &[UserPosition],
+    ) {
+        if let Err(e) = self.run_checks(calc, trade, trader, balance, my_positions).await {
+            // Fail closed: without the persisted state we can't tell how much room is left
+            block(calc, &format!("Risk state unavailable ({}) - skipping", e));
+        }
+    }
+
+    async fn run_checks(
+        &self,
+        calc: &mut OrderSizeCalculation,
+        trade: &UserActivity,
+        trader: &str,
+        balance: f64,
+        my_positions: &[UserPosition],
+    ) -> Result<()> {
+        let held_value: f64 = my_positions
+            .iter()
+            .map(|p| p.current_value.unwrap_or(0.0))
+            .sum();
+        let equity = balance + held_value;
+
+        // Drawdown from the equity high-water mark
+        if let Some(max_dd) = self.config.max_drawdown_percent {
+            let (hwm, raised) = high_water_mark(self.db.get_high_water_mark().await?, equity);
+            if raised {
+                self.db.set_high_water_mark(hwm).await?;
+            }
+            let drawdown = drawdown_percent(hwm, equity);
+            if drawdown >= max_dd {
+                block(
+                    calc,
+                    &format!(
+                        "Drawdown {:.1}% from high-water ${:.2} ≥ {}% - buys paused",
+                        drawdown, hwm, max_dd
+                    ),
+                );
+            } else {
+                calc.reasoning
+                    .push_str(&format!(" → Drawdown {:.1}%/{}% ok", drawdown, max_dd));
+            }
+        }
+
+        let day = today();
+        if let Some(limit) = self.max_daily_volume_usd {
+            let used = self.db.sum_risk_fills(&day, None).await?;
+            cap(calc, "Daily volume", used, limit);
+        }
+        if let Some(limit) = self.config.max_trader_daily_volume_usd {
+            let used = self.db.sum_risk_fills(&day, Some(trader)).await?;
+            cap(calc, "Trader daily volume", used, limit);
+        }
+
+        // Adding to a position we already hold doesn't open a new one
+        if let Some(max_open) = self.config.max_open_positions {
+            let (open, already_held) = open_positions(my_positions, trade.asset.as_deref());
+            if !already_held && open >= max_open {
+                block(calc, &format!("Open positions {}/{} - no new positions", open, max_open));
+            } else {
+                calc.reasoning
+                    .push_str(&format!(" → Open positions {}/{} ok", open, max_open));
+            }
+        }
+
+        if let (Some(limit), Some(cid)) = (self.config.max_market_exposure_usd, trade.condition_id.as_deref()) {
+            let used: f64 = my_positions
+                .iter()
+                .filter(|p| p.condition_id.as_deref() == Some(cid))
+                .map(|p| p.current_value.unwrap_or(0.0))
+                .sum();
+            cap(calc, "Market exposure", used, limit);
+        }
+
+        if let (Some(limit), Some(event)) = (self.config.max_event_exposure_usd, trade.event_slug.as_deref()) {
+            let used: f64 = my_positions
+                .iter()
+                .filter(|p| p.event_slug.as_deref() == Some(event))
+                .map(|p| p.current_value.unwrap_or(0.0))
+                .sum();
+            cap(calc, "Event exposure", used, limit);
+        }
+
+        let category = market_category(
+            trade.title.as_deref(),
+            trade.slug.as_deref(),
+            trade.event_slug.as_deref(),
+        );
+        if let Some(pct) = self.config.category_allocation.get(category) {
+            let used: f64 = my_positions
+                .iter()
+                .filter(|p| {
+                    market_category(p.title.as_deref(), p.slug.as_deref(), p.event_slug.as_deref())
+                        == category
+                })
+                .map(|p| p.current_value.unwrap_or(0.0))
+                .sum();
+            cap(
+                calc,
+                &format!("Category {} ({}% of ${:.2})", category, pct, equity),
+                used,
+                equity * pct / 100.0,
+            );
+        }
+
+        Ok(())
+    }
+
+    // Persist an executed buy (counted towards today's volume limits)
+    pub async fn record_buy(&self, trade: &UserActivity, trader: &str, usdc: f64) -> Result<()> {
+        if usdc <= 0.0 {
+            return Ok(());
+        }
+        let fill = RiskFill {
+            day: today(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            trader: trader.to_lowercase(),
+            condition_id: trade.condition_id.clone(),
+            event_slug: trade.event_slug.clone(),
+            category: market_category(
+                trade.title.as_deref(),
+                trade.slug.as_deref(),
+                trade.event_slug.as_deref(),
+            )
+            .to_string(),
+            usdc,
+        };
+        self.db.insert_risk_fill(&fill).await
+    }
+
+    pub fn log_limits(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut parts = Vec::new();
+        if let Some(v) = self.max_daily_volume_usd {
+            parts.push(format!("daily ${}", v));
+        }
+        if let Some(v) = self.config.max_trader_daily_volume_usd {
+            parts.push(format!("per-trader daily ${}", v));
+        }
+        if let Some(v) = self.config.max_market_exposure_usd {
+            parts.push(format!("market ${}", v));
+        }
+        if let Some(v) = self.config.max_event_exposure_usd {
+            parts.push(format!("event ${}", v));
+        }
+        if let Some(v) = self.config.max_open_positions {
+            parts.push(format!("{} open positions", v));
+        }
+        if let Some(v) = self.config.max_drawdown_percent {
+            parts.push(format!("drawdown {}%", v));
+        }
+        let mut categories: Vec<_> = self.config.category_allocation.iter().collect();
+        categories.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, pct) in categories {
+            parts.push(format!("{} {}%", name, pct));
+        }
+        Logger::info(&format!("Risk limits: {}", parts.join(", ")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CopyStrategy;
+
+    fn calc(amount: f64) -> OrderSizeCalculation {
+        OrderSizeCalculation {
+            trader_order_size: amount,
+            base_amount: amount,
+            final_amount: amount,
+            strategy: CopyStrategy::Fixed,
+            capped_by_max: false,
+            reduced_by_balance: false,
+            below_minimum: false,
+            limited_by_risk: false,
+            reasoning: String::new(),
+        }
+    }
+
+    fn position(asset: &str, size: f64) -> UserPosition {
+        serde_json::from_value(serde_json::json!({ "asset": asset, "size": size })).unwrap()
+    }
+
+    #[test]
+    fn cap_leaves_orders_that_fit() {
+        let mut c = calc(10.0);
+        cap(&mut c, "Daily volume", 50.0, 100.0);
+        assert_eq!(c.final_amount, 10.0);
+        assert!(!c.limited_by_risk);
+        assert!(c.reasoning.contains("ok"));
+    }
+
+    #[test]
+    fn cap_reduces_to_what_is_left() {
+        let mut c = calc(10.0);
+        cap(&mut c, "Daily volume", 95.0, 100.0);
+        assert_eq!(c.final_amount, 5.0);
+        assert!(c.limited_by_risk);
+    }
+
+    #[test]
+    fn cap_over_the_limit_blocks_instead_of_going_negative() {
+        let mut c = calc(10.0);
+        cap(&mut c, "Market exposure", 130.0, 100.0);
+        assert_eq!(c.final_amount, 0.0);
+        assert!(c.limited_by_risk);
+        assert!(c.reasoning.contains("reduced to $0.00"));
+    }
+
+    #[test]
+    fn high_water_mark_is_set_on_first_run() {
+        assert_eq!(high_water_mark(None, 100.0), (100.0, true));
+    }
+
+    #[test]
+    fn high_water_mark_rises_with_equity() {
+        // A stored mark below equity is stale: the new equity becomes the mark, drawdown 0
+        let (hwm, raised) = high_water_mark(Some(80.0), 100.0);
+        assert_eq!((hwm, raised), (100.0, true));
+        assert_eq!(drawdown_percent(hwm, 100.0), 0.0);
+    }
+
+    #[test]
+    fn high_water_mark_holds_through_a_drawdown() {
+        let (hwm, raised) = high_water_mark(Some(200.0), 150.0);
+        assert_eq!((hwm, raised), (200.0, false));
+        assert_eq!(drawdown_percent(hwm, 150.0), 25.0);
+    }
+
+    #[test]
+    fn drawdown_is_zero_without_equity() {
+        assert_eq!(drawdown_percent(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn open_positions_ignore_closed_rows() {
+        let positions = [position("1", 5.0), position("2", 0.0), position("3", 2.0)];
+        assert_eq!(open_positions(&positions, Some("4")), (2, false));
+    }
+
+    #[test]
+    fn adding_to_a_held_asset_is_not_a_new_position() {
+        let positions = [position("1", 5.0), position("2", 3.0)];
+        assert_eq!(open_positions(&positions, Some("2")), (2, true));
+    }
+
+    #[test]
+    fn closed_asset_counts_as_new() {
+        let positions = [position("1", 5.0), position("2", 0.0)];
+        assert_eq!(open_positions(&positions, Some("2")), (1, false));
+    }
+
+    #[test]
+    fn unknown_asset_is_never_already_held() {
+        let positions: Vec<UserPosition> =
+            vec![serde_json::from_value(serde_json::json!({ "size": 5.0 })).unwrap()];
+        assert_eq!(open_positions(&positions, None), (1, false));
+    }
+}
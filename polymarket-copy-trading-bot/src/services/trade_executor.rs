@@ -6,25 +6,35 @@ use polymarket_client_sdk::auth::Normal;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use tokio::time::{sleep, Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
 
 use crate::config::EnvConfig;
 use crate::db::Db;
+use crate::services::RiskManager;
 use crate::types::{UserActivity, UserPosition};
 use crate::utils::{create_clob_client, fetch_data, get_usdc_balance, post_order, Logger};
 
 // Min USD to aggregate trades (small trades get batched)
-const TRADE_AGGREGATION_MIN_TOTAL_USD: f64 = 1.0;
+pub const TRADE_AGGREGATION_MIN_TOTAL_USD: f64 = 1.0;
+// Trades buffered between monitor & executor (overflow stays in Mongo for the sweep)
+const TRADE_QUEUE_CAPACITY: usize = 256;
+// Re-read the Mongo outbox this often (catches trades the channel dropped)
+const OUTBOX_SWEEP_SECS: u64 = 30;
 
 // Global flag to stop executor gracefully
 static IS_RUNNING: AtomicBool = AtomicBool::new(true);
 
 // Trade + trader address wrapper
 #[derive(Debug, Clone)]
-struct TradeWithUser {
-    trade: UserActivity,
-    user_address: String,
+pub struct TradeWithUser {
+    pub trade: UserActivity,
+    pub user_address: String,
+}
+
+// Bounded monitor -> executor queue
+pub fn trade_channel() -> (mpsc::Sender<TradeWithUser>, mpsc::Receiver<TradeWithUser>) {
+    mpsc::channel(TRADE_QUEUE_CAPACITY)
 }
 
 // Aggregated trade group (batches small trades together)
@@ -63,8 +73,30 @@ async fn read_temp_trades(config: &EnvConfig, db: &Db) -> Result<Vec<TradeWithUs
     Ok(all_trades)
 }
 
+// Claim trades atomically; drops ones already claimed (by a sweep, the channel, or another executor)
+async fn claim_trades(db: &Db, trades: Vec<TradeWithUser>) -> Vec<TradeWithUser> {
+    let mut claimed = Vec::new();
+    for trade in trades {
+        let Some(id) = trade.trade.id else {
+            continue;
+        };
+        match db.claim_activity(&trade.user_address, &id).await {
+            Ok(true) => claimed.push(trade),
+            Ok(false) => {}
+            Err(e) => Logger::error(&format!("Failed to claim trade: {}", e)),
+        }
+    }
+    claimed
+}
+
+// Small BUYs wait in the aggregation buffer; everything else executes immediately
+pub fn should_aggregate(trade: &UserActivity) -> bool {
+    trade.side.as_deref() == Some("BUY")
+        && trade.usdc_size.unwrap_or(0.0) < TRADE_AGGREGATION_MIN_TOTAL_USD
+}
+
 // Generate key for grouping trades (user:condition:asset:side)
-fn get_aggregation_key(trade: &TradeWithUser) -> String {
+pub fn get_aggregation_key(trade: &TradeWithUser) -> String {
     format!(
         "{}:{}:{}:{}",
         trade.user_address,
@@ -74,7 +106,32 @@ fn get_aggregation_key(trade: &TradeWithUser) -> String {
     )
 }
 
-// Add trade to aggregation buffer (batches small trades)
+// Recalc group size & weighted avg price from its trades
+fn recompute_totals(agg: &mut AggregatedTrade) {
+    let mut total_value = 0.0;
+    agg.total_usdc_size = 0.0;
+    for t in &agg.trades {
+        let usdc = t.trade.usdc_size.unwrap_or(0.0);
+        agg.total_usdc_size += usdc;
+        total_value += usdc * t.trade.price.unwrap_or(0.0);
+    }
+    if agg.total_usdc_size > 0.0 {
+        agg.average_price = total_value / agg.total_usdc_size;
+    }
+}
+
+// Whether a trade is already waiting in the buffer (sweeps re-read unclaimed buffered trades)
+async fn is_buffered(buffer: &AggregationBuffer, trade: &TradeWithUser) -> bool {
+    let Some(id) = trade.trade.id else {
+        return false;
+    };
+    let buffer_guard = buffer.lock().await;
+    buffer_guard
+        .get(&get_aggregation_key(trade))
+        .is_some_and(|agg| agg.trades.iter().any(|t| t.trade.id == Some(id)))
+}
+
+// Add trade to aggregation buffer (batches small trades); it stays unclaimed until its group flushes
 async fn add_to_aggregation_buffer(
     buffer: &AggregationBuffer,
     trade: TradeWithUser,
@@ -85,18 +142,8 @@ async fn add_to_aggregation_buffer(
 
     // Update existing group or create new one
     if let Some(existing) = buffer_guard.get_mut(&key) {
-        existing.trades.push(trade.clone());
-        existing.total_usdc_size += trade.trade.usdc_size.unwrap_or(0.0);
-        // Recalc weighted avg price
-        let mut total_value = 0.0;
-        for t in &existing.trades {
-            let usdc = t.trade.usdc_size.unwrap_or(0.0);
-            let price = t.trade.price.unwrap_or(0.0);
-            total_value += usdc * price;
-        }
-        if existing.total_usdc_size > 0.0 {
-            existing.average_price = total_value / existing.total_usdc_size;
-        }
+        existing.trades.push(trade);
+        recompute_totals(existing);
         existing.last_trade_time = now;
     } else {
         // New aggregation group
@@ -139,8 +186,18 @@ async fn get_ready_aggregated_trades(
         let time_elapsed = now.duration_since(agg.first_trade_time);
 
         if time_elapsed >= window_duration {
+            keys_to_remove.push(key.clone());
+
+            // Claim on flush; trades another executor took (or already processed) drop out of the group
+            let mut agg = agg.clone();
+            agg.trades = claim_trades(db, agg.trades).await;
+            if agg.trades.is_empty() {
+                continue;
+            }
+            recompute_totals(&mut agg);
+
             if agg.total_usdc_size >= TRADE_AGGREGATION_MIN_TOTAL_USD {
-                ready.push(agg.clone());
+                ready.push(agg);
             } else {
                 let asset_display = agg
                     .slug
@@ -168,7 +225,6 @@ async fn get_ready_aggregated_trades(
                     }
                 }
             }
-            keys_to_remove.push(key.clone());
         }
     }
 
@@ -179,26 +235,62 @@ async fn get_ready_aggregated_trades(
     Ok(ready)
 }
 
-// Execute trades immediately (no aggregation)
+// Positions for both wallets (yours & trader's)
+async fn fetch_positions(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    user_address: &str,
+) -> Result<(Vec<UserPosition>, Vec<UserPosition>)> {
+    let my_positions = fetch_wallet_positions(config, http_client, &config.proxy_wallet).await?;
+    let user_positions = fetch_wallet_positions(config, http_client, user_address).await?;
+    Ok((my_positions, user_positions))
+}
+
+async fn fetch_wallet_positions(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    wallet: &str,
+) -> Result<Vec<UserPosition>> {
+    let url = format!("https://data-api.polymarket.com/positions?user={}", wallet);
+    let data: serde_json::Value = fetch_data(
+        http_client,
+        &url,
+        config.request_timeout_ms,
+        config.network_retry_limit,
+    )
+    .await?;
+    Ok(if let Some(arr) = data.as_array() {
+        arr.iter()
+            .filter_map(|p| serde_json::from_value::<UserPosition>(p.clone()).ok())
+            .collect()
+    } else {
+        Vec::new()
+    })
+}
+
+// Unclaim trades that failed before any order went out, so the next sweep retries them
+async fn release_claims(db: &Db, trades: &[TradeWithUser]) {
+    for trade in trades {
+        let Some(ref id) = trade.trade.id else {
+            continue;
+        };
+        if let Err(e) = db.release_claim(&trade.user_address, id).await {
+            Logger::error(&format!("Failed to release trade claim: {}", e));
+        }
+    }
+}
+
+// Execute trades immediately (no aggregation); a failed trade is logged and the rest still run
 async fn do_trading(
     config: &EnvConfig,
     trades: &[TradeWithUser],
     clob_client: &ClobClient<Authenticated<Normal>>,
     http_client: &reqwest::Client,
     db: &Db,
+    risk: &RiskManager,
     signer: &mut PrivateKeySigner,
-) -> Result<()> {
+) {
     for trade in trades {
-        // Mark as processing in DB
-        if let Some(ref id) = trade.trade.id {
-            db.update_activity(
-                &trade.user_address,
-                id,
-                &mongodb::bson::doc! { "botExcutedTime": 1_i64 },
-            )
-            .await?;
-        }
-
         // Log trade details
         Logger::trade(
             &trade.user_address,
@@ -215,46 +307,16 @@ async fn do_trading(
             },
         );
 
-        // Fetch positions for both wallets (yours & trader's)
-        let my_positions_url = format!(
-            "https://data-api.polymarket.com/positions?user={}",
-            config.proxy_wallet
-        );
-        let user_positions_url = format!(
-            "https://data-api.polymarket.com/positions?user={}",
-            trade.user_address
-        );
-
-        let my_positions_data: serde_json::Value = fetch_data(
-            http_client,
-            &my_positions_url,
-            config.request_timeout_ms,
-            config.network_retry_limit,
-        )
-        .await?;
-        let user_positions_data: serde_json::Value = fetch_data(
-            http_client,
-            &user_positions_url,
-            config.request_timeout_ms,
-            config.network_retry_limit,
-        )
-        .await?;
-
-        let my_positions: Vec<UserPosition> = if let Some(arr) = my_positions_data.as_array() {
-            arr.iter()
-                .filter_map(|p| serde_json::from_value::<UserPosition>(p.clone()).ok())
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        let user_positions: Vec<UserPosition> = if let Some(arr) = user_positions_data.as_array() {
-            arr.iter()
-                .filter_map(|p| serde_json::from_value::<UserPosition>(p.clone()).ok())
-                .collect()
-        } else {
-            Vec::new()
-        };
+        // Nothing is sent yet, so a failed fetch hands the trade back for the next sweep
+        let (my_positions, user_positions) =
+            match fetch_positions(config, http_client, &trade.user_address).await {
+                Ok(positions) => positions,
+                Err(e) => {
+                    Logger::error(&format!("{}Failed to fetch positions: {}", config.log_prefix(), e));
+                    release_claims(db, std::slice::from_ref(trade)).await;
+                    continue;
+                }
+            };
 
         let condition_id = trade.trade.condition_id.as_deref();
         let my_position = my_positions
@@ -287,26 +349,30 @@ async fn do_trading(
             "sell"
         };
 
-        post_order(
+        if let Err(e) = post_order(
             config,
             clob_client,
             condition,
             my_position,
             user_position,
+            &my_positions,
             &trade.trade,
             my_balance,
             user_balance,
             &trade.user_address,
             http_client,
             db,
+            risk,
             signer,
         )
-        .await?;
+        .await
+        {
+            // Kept claimed: an order may already be out
+            Logger::error(&format!("{}Trade executor error: {}", config.log_prefix(), e));
+        }
 
         Logger::separator();
     }
-
-    Ok(())
 }
 
 async fn do_aggregated_trading(
@@ -315,8 +381,9 @@ async fn do_aggregated_trading(
     clob_client: &ClobClient<Authenticated<Normal>>,
     http_client: &reqwest::Client,
     db: &Db,
+    risk: &RiskManager,
     signer: &mut PrivateKeySigner,
-) -> Result<()> {
+) {
     for agg in aggregated_trades {
         Logger::header(&format!(
             "📊 AGGREGATED TRADE ({} trades combined)",
@@ -334,56 +401,15 @@ async fn do_aggregated_trading(
         Logger::info(&format!("Total volume: ${:.2}", agg.total_usdc_size));
         Logger::info(&format!("Average price: ${:.4}", agg.average_price));
 
-        for trade in &agg.trades {
-            if let Some(ref id) = trade.trade.id {
-                db.update_activity(
-                    &trade.user_address,
-                    id,
-                    &mongodb::bson::doc! { "botExcutedTime": 1_i64 },
-                )
-                .await?;
-            }
-        }
-
-        let my_positions_url = format!(
-            "https://data-api.polymarket.com/positions?user={}",
-            config.proxy_wallet
-        );
-        let user_positions_url = format!(
-            "https://data-api.polymarket.com/positions?user={}",
-            agg.user_address
-        );
-
-        let my_positions_data: serde_json::Value = fetch_data(
-            http_client,
-            &my_positions_url,
-            config.request_timeout_ms,
-            config.network_retry_limit,
-        )
-        .await?;
-        let user_positions_data: serde_json::Value = fetch_data(
-            http_client,
-            &user_positions_url,
-            config.request_timeout_ms,
-            config.network_retry_limit,
-        )
-        .await?;
-
-        let my_positions: Vec<UserPosition> = if let Some(arr) = my_positions_data.as_array() {
-            arr.iter()
-                .filter_map(|p| serde_json::from_value::<UserPosition>(p.clone()).ok())
-                .collect()
-        } else {
-            Vec::new()
-        };
-
-        let user_positions: Vec<UserPosition> = if let Some(arr) = user_positions_data.as_array() {
-            arr.iter()
-                .filter_map(|p| serde_json::from_value::<UserPosition>(p.clone()).ok())
-                .collect()
-        } else {
-            Vec::new()
-        };
+        let (my_positions, user_positions) =
+            match fetch_positions(config, http_client, &agg.user_address).await {
+                Ok(positions) => positions,
+                Err(e) => {
+                    Logger::error(&format!("{}Failed to fetch positions: {}", config.log_prefix(), e));
+                    release_claims(db, &agg.trades).await;
+                    continue;
+                }
+            };
 
         let condition_id = agg.condition_id.as_deref();
         let my_position = my_positions
@@ -415,40 +441,48 @@ async fn do_aggregated_trading(
 
         let condition = if agg.side == "BUY" { "buy" } else { "sell" };
 
-        post_order(
+        if let Err(e) = post_order(
             config,
             clob_client,
             condition,
             my_position,
             user_position,
+            &my_positions,
             &synthetic_trade,
             my_balance,
             user_balance,
             &agg.user_address,
             http_client,
             db,
+            risk,
             signer,
         )
-        .await?;
+        .await
+        {
+            // Kept claimed: an order may already be out
+            Logger::error(&format!("{}Trade executor error: {}", config.log_prefix(), e));
+        }
 
         Logger::separator();
     }
-
-    Ok(())
 }
 
-// Main executor loop - polls DB for trades & executes orders
+// Main executor loop - trades arrive over the channel; Mongo sweeps recover anything missed
 pub async fn run_trade_executor(
     config: &EnvConfig,
     db: &Db,
     http_client: &reqwest::Client,
+    mut incoming: mpsc::Receiver<TradeWithUser>,
 ) -> Result<()> {
     // Init CLOB client & signer
     let (clob_client, mut signer) = create_clob_client(config).await?;
+    let risk = RiskManager::new(config, db);
 
     Logger::success(&format!(
-        "Trade executor ready for {} trader(s)",
-        config.user_addresses.len()
+        "{}Trade executor ready for {} trader(s) on {}",
+        config.log_prefix(),
+        config.user_addresses.len(),
+        Logger::format_address(&config.proxy_wallet)
     ));
     if config.trade_aggregation_enabled {
         Logger::info(&format!(
@@ -456,27 +490,91 @@ pub async fn run_trade_executor(
             config.trade_aggregation_window_seconds, TRADE_AGGREGATION_MIN_TOTAL_USD
         ));
     }
+    for (address, t) in &config.trader_overrides {
+        let side = if t.buy_only {
+            ", buy-only"
+        } else if t.sell_only {
+            ", sell-only"
+        } else {
+            ""
+        };
+        Logger::info(&format!(
+            "{}Custom settings for {}: {:?} {}, max order ${}{}",
+            config.log_prefix(),
+            Logger::format_address(address),
+            t.copy_strategy.strategy,
+            t.copy_strategy.copy_size,
+            t.copy_strategy.max_order_size_usd,
+            side
+        ));
+    }
+    risk.log_limits();
 
-    // Init aggregation buffer (even if disabled, keeps code simpler)
-    let aggregation_buffer: AggregationBuffer = if config.trade_aggregation_enabled {
-        Arc::new(Mutex::new(HashMap::new()))
-    } else {
-        Arc::new(Mutex::new(HashMap::new()))
-    };
+    // Trades a previous run claimed but never finished are left alone (orders may already be out)
+    for user_address in &config.user_addresses {
+        if let Ok(n) = db.count_unfinished_claims(user_address).await {
+            if n > 0 {
+                Logger::warning(&format!(
+                    "{}{} trade(s) for {} were claimed but not finished by a previous run - not retrying, check your positions",
+                    config.log_prefix(),
+                    n,
+                    Logger::format_address(user_address)
+                ));
+            }
+        }
+    }
 
-    // Poll every 300ms for new trades
+    let aggregation_buffer: AggregationBuffer = Arc::new(Mutex::new(HashMap::new()));
+
+    // First sweep fires immediately (startup recovery); the tick drives aggregation windows & status
+    let mut sweep = interval(Duration::from_secs(OUTBOX_SWEEP_SECS));
+    sweep.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut tick = interval(Duration::from_millis(300));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
     let mut last_check = Instant::now();
-    let poll_interval = Duration::from_millis(300);
 
     while IS_RUNNING.load(Ordering::Relaxed) {
-        // Fetch unprocessed trades from DB
-        let trades = match read_temp_trades(config, db).await {
-            Ok(t) => t,
-            Err(e) => {
-                Logger::error(&format!("Failed to read trades: {}", e));
-                sleep(poll_interval).await;
-                continue;
+        let candidates = tokio::select! {
+            received = incoming.recv() => match received {
+                Some(first) => {
+                    // Drain whatever else is already queued so bursts are handled together
+                    let mut batch = vec![first];
+                    while let Ok(more) = incoming.try_recv() {
+                        batch.push(more);
+                    }
+                    batch
+                }
+                None => {
+                    Logger::warning(&format!(
+                        "{}Trade queue closed - executor falling back to database sweeps",
+                        config.log_prefix()
+                    ));
+                    sweep.tick().await;
+                    read_temp_trades(config, db).await.unwrap_or_default()
+                }
+            },
+            _ = sweep.tick() => match read_temp_trades(config, db).await {
+                Ok(t) => t,
+                Err(e) => {
+                    Logger::error(&format!("{}Failed to read trades: {}", config.log_prefix(), e));
+                    Vec::new()
+                }
+            },
+            _ = tick.tick() => Vec::new(),
+        };
+        // Small BUYs are claimed when their aggregation group flushes, so a stop mid-window leaves them for the next run
+        let trades = if config.trade_aggregation_enabled {
+            let (to_buffer, immediate): (Vec<_>, Vec<_>) =
+                candidates.into_iter().partition(|t| should_aggregate(&t.trade));
+            let mut trades = claim_trades(db, immediate).await;
+            for trade in to_buffer {
+                if !is_buffered(&aggregation_buffer, &trade).await {
+                    trades.push(trade);
+                }
             }
+            trades
+        } else {
+            claim_trades(db, candidates).await
         };
 
         // Aggregation mode: batch small trades, execute large ones immediately
@@ -484,7 +582,8 @@ pub async fn run_trade_executor(
             if !trades.is_empty() {
                 Logger::clear_line();
                 Logger::info(&format!(
-                    "📥 {} new trade{} detected",
+                    "{}📥 {} new trade{} detected",
+                    config.log_prefix(),
                     trades.len(),
                     if trades.len() > 1 { "s" } else { "" }
                 ));
@@ -492,8 +591,7 @@ pub async fn run_trade_executor(
                 for trade in &trades {
                     let usdc_size = trade.trade.usdc_size.unwrap_or(0.0);
                     let side = trade.trade.side.as_deref().unwrap_or("");
-                    // Small BUY trades go to buffer, everything else executes immediately
-                    if side == "BUY" && usdc_size < TRADE_AGGREGATION_MIN_TOTAL_USD {
+                    if should_aggregate(&trade.trade) {
                         let asset_display = trade
                             .trade
                             .slug
@@ -510,19 +608,20 @@ pub async fn run_trade_executor(
                         }
                     } else {
                         Logger::clear_line();
-                        Logger::header("⚡ IMMEDIATE TRADE (above threshold)");
-                        if let Err(e) = do_trading(
+                        Logger::header(&format!(
+                            "{}⚡ IMMEDIATE TRADE (above threshold)",
+                            config.log_prefix()
+                        ));
+                        do_trading(
                             config,
-                            &[trade.clone()],
+                            std::slice::from_ref(trade),
                             &clob_client,
                             http_client,
                             db,
+                            &risk,
                             &mut signer,
                         )
-                        .await
-                        {
-                            Logger::error(&format!("Trade executor error: {}", e));
-                        }
+                        .await;
                     }
                 }
                 last_check = Instant::now();
@@ -544,26 +643,26 @@ pub async fn run_trade_executor(
             if !ready_aggregations.is_empty() {
                 Logger::clear_line();
                 Logger::header(&format!(
-                    "⚡ {} AGGREGATED TRADE{} READY",
+                    "{}⚡ {} AGGREGATED TRADE{} READY",
+                    config.log_prefix(),
                     ready_aggregations.len(),
                     if ready_aggregations.len() > 1 { "S" } else { "" }
                 ));
-                if let Err(e) = do_aggregated_trading(
+                do_aggregated_trading(
                     config,
                     &ready_aggregations,
                     &clob_client,
                     http_client,
                     db,
+                    &risk,
                     &mut signer,
                 )
-                .await
-                {
-                    Logger::error(&format!("Trade executor error: {}", e));
-                }
+                .await;
                 last_check = Instant::now();
             }
 
-            if trades.is_empty() && ready_aggregations.is_empty() {
+            // One status line is enough - only the main account draws it
+            if trades.is_empty() && ready_aggregations.is_empty() && config.is_primary_account() {
                 if last_check.elapsed() > Duration::from_millis(300) {
                     let buffered_count = aggregation_buffer.lock().await.len();
                     if buffered_count > 0 {
@@ -581,31 +680,25 @@ pub async fn run_trade_executor(
             if !trades.is_empty() {
                 Logger::clear_line();
                 Logger::header(&format!(
-                    "⚡ {} NEW TRADE{} TO COPY",
+                    "{}⚡ {} NEW TRADE{} TO COPY",
+                    config.log_prefix(),
                     trades.len(),
                     if trades.len() > 1 { "S" } else { "" }
                 ));
-                if let Err(e) = do_trading(config, &trades, &clob_client, http_client, db, &mut signer)                .await {
-                    Logger::error(&format!("Trade executor error: {}", e));
-                }
+                do_trading(config, &trades, &clob_client, http_client, db, &risk, &mut signer).await;
                 last_check = Instant::now();
             }
-            
-            if trades.is_empty() {
+
+            if trades.is_empty() && config.is_primary_account() {
                 if last_check.elapsed() > Duration::from_millis(300) {
                     Logger::waiting(config.user_addresses.len(), None);
                     last_check = Instant::now();
                 }
             }
         }
-
-        if !IS_RUNNING.load(Ordering::Relaxed) {
-            break;
-        }
-        sleep(poll_interval).await;
     }
 
-    Logger::info("Trade executor stopped");
+    Logger::info(&format!("{}Trade executor stopped", config.log_prefix()));
     Ok(())
 }
 
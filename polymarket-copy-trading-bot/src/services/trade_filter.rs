@@ -0,0 +1,221 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::config::{EnvConfig, MarketFilterConfig};
+use crate::types::UserActivity;
+use crate::utils::fetch_data;
+
+const GAMMA_MARKETS_URL: &str = "https://gamma-api.polymarket.com/markets";
+// Liquidity/volume drift a bit within this window, which is fine for a threshold check
+const MARKET_CACHE_TTL_SECS: u64 = 300;
+
+// Gamma metadata the filters look at
+#[derive(Debug, Clone, Default)]
+struct MarketInfo {
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+    liquidity: f64,
+    volume: f64,
+    neg_risk: bool,
+    // Lowercase tag labels & slugs plus the market category
+    tags: Vec<String>,
+}
+
+// Gamma returns some numbers as strings ("liquidity": "1234.5")
+fn num(v: &serde_json::Value, keys: &[&str]) -> f64 {
+    keys.iter()
+        .filter_map(|k| v.get(*k))
+        .find_map(|x| x.as_f64().or_else(|| x.as_str().and_then(|s| s.parse().ok())))
+        .unwrap_or(0.0)
+}
+
+fn collect_tags(v: &serde_json::Value, out: &mut Vec<String>) {
+    if let Some(tags) = v.get("tags").and_then(|t| t.as_array()) {
+        for tag in tags {
+            for key in ["label", "slug"] {
+                if let Some(s) = tag.get(key).and_then(|s| s.as_str()) {
+                    out.push(s.to_lowercase());
+                }
+            }
+        }
+    }
+}
+
+fn parse_market_info(m: &serde_json::Value) -> MarketInfo {
+    let events = m.get("events").and_then(|e| e.as_array());
+    let mut tags = Vec::new();
+    collect_tags(m, &mut tags);
+    for event in events.into_iter().flatten() {
+        collect_tags(event, &mut tags);
+    }
+    if let Some(c) = m.get("category").and_then(|c| c.as_str()) {
+        tags.push(c.to_lowercase());
+    }
+    tags.sort();
+    tags.dedup();
+
+    let neg_risk = m.get("negRisk").and_then(|v| v.as_bool()).unwrap_or(false)
+        || events
+            .into_iter()
+            .flatten()
+            .any(|e| e.get("negRisk").and_then(|v| v.as_bool()).unwrap_or(false));
+
+    MarketInfo {
+        end_date: m
+            .get("endDate")
+            .and_then(|d| d.as_str())
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.with_timezone(&chrono::Utc)),
+        liquidity: num(m, &["liquidityNum", "liquidity"]),
+        volume: num(m, &["volumeNum", "volume"]),
+        neg_risk,
+        tags,
+    }
+}
+
+// Market filter stage between the monitor & the executor
+pub struct TradeFilter {
+    config: MarketFilterConfig,
+    http_client: reqwest::Client,
+    request_timeout_ms: u64,
+    network_retry_limit: u32,
+    cache: Mutex<HashMap<String, (Instant, MarketInfo)>>,
+}
+
+impl TradeFilter {
+    pub fn new(config: &EnvConfig, http_client: &reqwest::Client) -> Self {
+        Self {
+            config: config.market_filter.clone(),
+            http_client: http_client.clone(),
+            request_timeout_ms: config.request_timeout_ms,
+            network_retry_limit: config.network_retry_limit,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    // Why a trade shouldn't be copied (None = copy it). Only buys are filtered so held positions can always be exited
+    pub async fn skip_reason(&self, trade: &UserActivity) -> Option<String> {
+        let c = &self.config;
+        if !c.is_enabled() || !trade.side_buy() {
+            return None;
+        }
+
+        let slugs: Vec<&str> = [trade.slug.as_deref(), trade.event_slug.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect();
+        if let Some(p) = c
+            .exclude_slugs
+            .iter()
+            .find(|p| slugs.iter().any(|s| p.matches(s)))
+        {
+            return Some(format!("slug matches excluded pattern \"{}\"", p.pattern));
+        }
+        if !c.include_slugs.is_empty()
+            && !c
+                .include_slugs
+                .iter()
+                .any(|p| slugs.iter().any(|s| p.matches(s)))
+        {
+            return Some("slug not in MARKET_INCLUDE_SLUGS".into());
+        }
+
+        if let Some(price) = trade.price {
+            if let Some(max) = c.max_buy_price {
+                if price > max {
+                    return Some(format!("price {:.3} above MAX_BUY_PRICE {}", price, max));
+                }
+            }
+            if let Some(min) = c.min_buy_price {
+                if price < min {
+                    return Some(format!("price {:.3} below MIN_BUY_PRICE {}", price, min));
+                }
+            }
+        }
+
+        if !c.needs_market_data() {
+            return None;
+        }
+        let Some(condition_id) = trade.condition_id.as_deref() else {
+            return Some("no condition id to look up market data".into());
+        };
+        // Fail closed: a filter we can't evaluate shouldn't let the trade through
+        let info = match self.market_info(condition_id).await {
+            Ok(info) => info,
+            Err(e) => return Some(format!("market data unavailable ({})", e)),
+        };
+
+        if c.skip_neg_risk && info.neg_risk {
+            return Some("neg-risk market".into());
+        }
+        if let Some(t) = c.exclude_tags.iter().find(|t| info.tags.contains(t)) {
+            return Some(format!("tag \"{}\" excluded", t));
+        }
+        if !c.include_tags.is_empty() && !c.include_tags.iter().any(|t| info.tags.contains(t)) {
+            return Some("no tag in MARKET_INCLUDE_TAGS".into());
+        }
+
+        if c.min_hours_to_resolution.is_some() || c.max_days_to_resolution.is_some() {
+            let Some(end) = info.end_date else {
+                return Some("market has no end date".into());
+            };
+            let hours = (end - chrono::Utc::now()).num_seconds() as f64 / 3600.0;
+            if let Some(min) = c.min_hours_to_resolution {
+                if hours < min {
+                    return Some(format!("resolves in {:.1}h (min {}h)", hours, min));
+                }
+            }
+            if let Some(max) = c.max_days_to_resolution {
+                if hours > max * 24.0 {
+                    return Some(format!("resolves in {:.1}d (max {}d)", hours / 24.0, max));
+                }
+            }
+        }
+
+        if let Some(min) = c.min_liquidity_usd {
+            if info.liquidity < min {
+                return Some(format!("liquidity ${:.0} below ${}", info.liquidity, min));
+            }
+        }
+        if let Some(min) = c.min_volume_usd {
+            if info.volume < min {
+                return Some(format!("volume ${:.0} below ${}", info.volume, min));
+            }
+        }
+        None
+    }
+
+    async fn market_info(&self, condition_id: &str) -> Result<MarketInfo> {
+        if let Some((at, info)) = self.cache.lock().await.get(condition_id) {
+            if at.elapsed() < Duration::from_secs(MARKET_CACHE_TTL_SECS) {
+                return Ok(info.clone());
+            }
+        }
+        let url = format!(
+            "{}?condition_ids={}&include_tag=true",
+            GAMMA_MARKETS_URL, condition_id
+        );
+        let data = fetch_data(
+            &self.http_client,
+            &url,
+            self.request_timeout_ms,
+            self.network_retry_limit,
+        )
+        .await?;
+        let market = data
+            .as_array()
+            .and_then(|a| a.first())
+            .ok_or_else(|| anyhow::anyhow!("market {} not found", condition_id))?;
+        let info = parse_market_info(market);
+        self.cache
+            .lock()
+            .await
+            .insert(condition_id.to_string(), (Instant::now(), info.clone()));
+        Ok(info)
+    }
+}
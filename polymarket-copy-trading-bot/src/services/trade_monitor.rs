@@ -3,12 +3,14 @@ use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tokio::time::{sleep, Duration};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 use crate::config::EnvConfig;
 use crate::db::Db;
+use crate::services::trade_executor::TradeWithUser;
+use crate::services::trade_filter::TradeFilter;
 use crate::types::{RtdsActivity, UserActivity, UserPosition};
 use crate::utils::{self, Logger};
 
@@ -31,6 +33,14 @@ pub struct TradeMonitorHandle {
     _tx: broadcast::Sender<()>,
 }
 
+// One of our accounts: where its copy of each trade is stored & which executor gets it
+#[derive(Clone)]
+pub struct AccountFeed {
+    pub name: String,
+    pub db: Db,
+    pub trades: mpsc::Sender<TradeWithUser>,
+}
+
 // Init: show DB stats, positions, balances
 async fn init(
     config: &EnvConfig,
@@ -183,12 +193,13 @@ async fn init(
     Ok(())
 }
 
-// Process trade from RTDS: validate timestamp, check duplicates, save to DB
+// Process trade from RTDS: validate timestamp, filter, then save & hand to each account's executor
 async fn process_trade_activity(
-    db: &Db,
     config: &EnvConfig,
     activity: &RtdsActivity,
     address: &str,
+    filter: &TradeFilter,
+    feeds: &[AccountFeed],
 ) -> Result<()> {
     // Normalize timestamp (handle both ms & sec formats)
     let ts = activity.timestamp.unwrap_or(0);
@@ -208,9 +219,6 @@ async fn process_trade_activity(
     if tx_hash.is_empty() {
         return Ok(());
     }
-    if db.find_activity_by_tx(address, tx_hash).await?.is_some() {
-        return Ok(());
-    }
 
     let doc = UserActivity {
         id: None,
@@ -238,13 +246,72 @@ async fn process_trade_activity(
         bot: Some(false),
         bot_executed_time: Some(0),
         my_bought_size: None,
+        claimed_at: None,
+        skip_reason: None,
     };
 
-    db.insert_activity(address, &doc).await?;
-    Logger::info(&format!(
-        "New trade detected for {}",
-        Logger::format_address(address)
-    ));
+    // Filter once - market data doesn't depend on the account
+    let skip_reason = filter.skip_reason(&doc).await;
+    if let Some(ref reason) = skip_reason {
+        Logger::info(&format!(
+            "Skipping trade for {}: {}",
+            Logger::format_address(address),
+            reason
+        ));
+    } else {
+        Logger::info(&format!(
+            "New trade detected for {}",
+            Logger::format_address(address)
+        ));
+    }
+
+    // Each account has its own outbox; a failure in one doesn't stop the others
+    for feed in feeds {
+        if let Err(e) = queue_for_account(feed, address, tx_hash, &doc, skip_reason.as_deref()).await {
+            Logger::error(&format!(
+                "[{}] Failed to save trade for {}: {}",
+                feed.name,
+                Logger::format_address(address),
+                e
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Save one account's copy of a trade (skipped or pending) and push pending ones to its executor
+async fn queue_for_account(
+    feed: &AccountFeed,
+    address: &str,
+    tx_hash: &str,
+    doc: &UserActivity,
+    skip_reason: Option<&str>,
+) -> Result<()> {
+    if feed.db.find_activity_by_tx(address, tx_hash).await?.is_some() {
+        return Ok(());
+    }
+    let mut doc = doc.clone();
+
+    // Filtered trades are stored already processed, with the reason, and never queued
+    if let Some(reason) = skip_reason {
+        doc.bot = Some(true);
+        doc.skip_reason = Some(reason.to_string());
+        feed.db.insert_activity(address, &doc).await?;
+        return Ok(());
+    }
+
+    // Mongo is the durable outbox; the channel is just the fast path
+    doc.id = Some(feed.db.insert_activity(address, &doc).await?);
+    let queued = TradeWithUser {
+        trade: doc,
+        user_address: address.to_string(),
+    };
+    if let Err(mpsc::error::TrySendError::Full(_)) = feed.trades.try_send(queued) {
+        Logger::warning(&format!(
+            "[{}] Executor queue full - trade saved, will be picked up from the database",
+            feed.name
+        ));
+    }
     Ok(())
 }
 
@@ -286,9 +353,8 @@ async fn update_positions(
 // Connect to RTDS WebSocket & subscribe to trade activity (with auto-reconnect)
 async fn connect_rtds(
     config: Arc<EnvConfig>,
-    db: Arc<Db>,
-    http_client: Arc<reqwest::Client>,
     reconnect_attempts: Arc<std::sync::atomic::AtomicU32>,
+    activities: mpsc::UnboundedSender<(String, RtdsActivity)>,
 ) -> Result<()> {
     loop {
         if !RUNNING.load(Ordering::SeqCst) {
@@ -327,8 +393,8 @@ async fn connect_rtds(
                     config.user_addresses.len()
                 ));
 
-                let db_msg = db.clone();
                 let config_msg = config.clone();
+                let activities_msg = activities.clone();
                 let mut message_task = tokio::spawn(async move {
                     while RUNNING.load(Ordering::SeqCst) {
                         match read.next().await {
@@ -358,13 +424,8 @@ async fn connect_rtds(
                                                     .iter()
                                                     .any(|a| a.to_lowercase() == proxy)
                                                 {
-                                                    let _ = process_trade_activity(
-                                                        &db_msg,
-                                                        &config_msg,
-                                                        &activity,
-                                                        &proxy,
-                                                    )
-                                                    .await;
+                                                    // Filtering can hit Gamma, so it runs off the read loop
+                                                    let _ = activities_msg.send((proxy, activity));
                                                 }
                                             }
                                         }
@@ -418,6 +479,7 @@ pub async fn run_trade_monitor(
     config: &EnvConfig,
     db: &Db,
     http_client: &reqwest::Client,
+    feeds: Vec<AccountFeed>,
 ) -> Result<TradeMonitorHandle> {
     RUNNING.store(true, Ordering::SeqCst);
 
@@ -429,18 +491,30 @@ pub async fn run_trade_monitor(
     ));
     Logger::separator();
 
-    Logger::info("First run: marking all historical trades as processed...");
-    for addr in &config.user_addresses {
-        let n = db.mark_historical_processed(addr).await?;
-        if n > 0 {
-            Logger::info(&format!(
-                "Marked {} historical trades as processed for {}",
-                n,
-                Logger::format_address(addr)
-            ));
+    // Pending trades newer than the cutoff are recovered by the executor, older ones are dropped
+    Logger::info("Marking trades older than TOO_OLD_TIMESTAMP as processed...");
+    let cutoff_ms =
+        chrono::Utc::now().timestamp_millis() - config.too_old_timestamp_hours * 3_600_000;
+    for feed in &feeds {
+        for addr in &config.user_addresses {
+            match feed.db.mark_historical_processed(addr, cutoff_ms).await {
+                Ok(0) => {}
+                Ok(n) => Logger::info(&format!(
+                    "Marked {} historical trades as processed for {}{}",
+                    n,
+                    Logger::format_address(addr),
+                    if feeds.len() > 1 { format!(" ({})", feed.name) } else { String::new() }
+                )),
+                Err(e) => Logger::error(&format!(
+                    "[{}] Failed to mark historical trades for {}: {}",
+                    feed.name,
+                    Logger::format_address(addr),
+                    e
+                )),
+            }
         }
     }
-    Logger::success("\nHistorical trades processed. Now monitoring for new trades only.");
+    Logger::success("\nHistorical trades processed. Now monitoring for new trades.");
     Logger::separator();
 
     let config_arc = Arc::new(config.clone());
@@ -463,11 +537,21 @@ pub async fn run_trade_monitor(
     });
 
     let config_ws = config_arc.clone();
-    let db_ws = db_arc.clone();
-    let http_ws = http_arc.clone();
     let reconnect_ws = reconnect_attempts.clone();
+    let filter = Arc::new(TradeFilter::new(config, http_client));
+    if filter.is_enabled() {
+        Logger::info("Market filters enabled - skipped trades are saved with a skipReason");
+    }
+    // One worker filters & saves trades in arrival order, so the duplicate check never races itself
+    let (activity_tx, mut activity_rx) = mpsc::unbounded_channel::<(String, RtdsActivity)>();
+    let config_filter = config_arc.clone();
+    tokio::spawn(async move {
+        while let Some((address, activity)) = activity_rx.recv().await {
+            let _ = process_trade_activity(&config_filter, &activity, &address, &filter, &feeds).await;
+        }
+    });
     tokio::spawn(async move {
-        let _ = connect_rtds(config_ws, db_ws, http_ws, reconnect_ws).await;
+        let _ = connect_rtds(config_ws, reconnect_ws, activity_tx).await;
     });
 
     let (tx, _) = broadcast::channel::<()>(1);
@@ -31,6 +31,12 @@ pub struct UserActivity {
     #[serde(rename = "botExcutedTime")]
     pub bot_executed_time: Option<i64>,
     pub my_bought_size: Option<f64>,
+    // Set (ms) when the executor claims the trade; a claimed trade is never executed again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claimed_at: Option<i64>,
+    // Why the trade was not copied (market filter, trader settings); set together with bot: true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
 }
 
 impl UserActivity {
@@ -39,6 +45,19 @@ impl UserActivity {
     }
 }
 
+// One executed copy buy, kept for daily risk limits (risk_fills collection)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFill {
+    pub day: String,
+    pub timestamp: i64,
+    pub trader: String,
+    pub condition_id: Option<String>,
+    pub event_slug: Option<String>,
+    pub category: String,
+    pub usdc: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserPosition {
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use alloy::signers::local::PrivateKeySigner;
 use alloy::signers::Signer as _;
+use alloy::primitives::Address;
+use polymarket_client_sdk::clob::types::SignatureType;
 use polymarket_client_sdk::clob::Client as ClobClient;
 use polymarket_client_sdk::auth::state::Authenticated;
 use polymarket_client_sdk::auth::Normal;
@@ -30,8 +32,16 @@ pub async fn create_clob_client(config: &EnvConfig) -> Result<(ClobClient<Authen
     
     Logger::info(&format!("Wallet type detected: {}", wallet_type));
     
-    // Auth with CLOB API using appropriate sig type
-    let clob_client = ClobClient::new(host, Default::default())?;
+    // Auth with CLOB API using appropriate sig type (a Safe funds orders from the proxy wallet)
+    let mut auth = ClobClient::new(host, Default::default())?.authentication_builder(&signer);
+    if is_proxy_safe {
+        let funder = Address::from_str(&config.proxy_wallet)
+            .map_err(|e| anyhow::anyhow!("Invalid proxy wallet address: {}", e))?;
+        auth = auth.funder(funder).signature_type(SignatureType::GnosisSafe);
+    } else {
+        auth = auth.signature_type(SignatureType::Eoa);
+    }
+    let clob_client = auth.authenticate().await?;
     Ok((clob_client, signer))
 }
 
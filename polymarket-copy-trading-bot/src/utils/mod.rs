@@ -2,7 +2,10 @@ mod create_clob_client;
 mod fetch;
 mod health;
 mod logger;
+mod positions;
 mod post_order;
+pub mod scanner;
+pub mod simulator;
 mod spinner;
 pub mod theme;
 
@@ -10,7 +13,10 @@ pub use create_clob_client::create_clob_client;
 pub use fetch::fetch_data;
 pub use health::perform_health_check;
 pub use logger::{Logger, TradeDetails};
-pub use post_order::post_order;
+pub use positions::{
+    arg_value, fetch_positions, is_dry_run, position_label, record_manual_sell, sell_positions,
+};
+pub use post_order::{post_order, sell_at_best_bid};
 pub use spinner::Spinner;
 
 pub async fn is_contract_address(rpc_url: &str, address: &str) -> anyhow::Result<bool> {
@@ -0,0 +1,188 @@
+use anyhow::Result;
+
+use crate::config::EnvConfig;
+use crate::db::Db;
+use crate::types::UserPosition;
+use crate::utils::{fetch_data, Logger};
+
+// --dry-run / -n on the command line, or DRY_RUN=true (for runs launched from Telegram)
+pub fn is_dry_run() -> bool {
+    std::env::args().skip(1).any(|a| a == "--dry-run" || a == "-n")
+        || std::env::var("DRY_RUN")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false)
+}
+
+// Value of `--name <value>` or `--name=<value>` on the command line
+pub fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let prefix = format!("{}=", name);
+    for (i, a) in args.iter().enumerate() {
+        if a == name {
+            return args.get(i + 1).cloned();
+        }
+        if let Some(v) = a.strip_prefix(&prefix) {
+            return Some(v.to_string());
+        }
+    }
+    None
+}
+
+// Open positions for a wallet from the data API
+pub async fn fetch_positions(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    wallet: &str,
+) -> Result<Vec<UserPosition>> {
+    let url = format!("https://data-api.polymarket.com/positions?user={}", wallet);
+    let data = fetch_data(
+        http_client,
+        &url,
+        config.request_timeout_ms,
+        config.network_retry_limit,
+    )
+    .await?;
+    Ok(data
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|p| serde_json::from_value::<UserPosition>(p.clone()).ok())
+                .filter(|p| p.size.unwrap_or(0.0) > 0.0)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// "Title [Outcome]" for listings
+pub fn position_label(p: &UserPosition) -> String {
+    let title = p
+        .title
+        .as_deref()
+        .or(p.slug.as_deref())
+        .unwrap_or("Unknown market");
+    match p.outcome.as_deref() {
+        Some(o) => format!("{} [{}]", title, o),
+        None => title.to_string(),
+    }
+}
+
+// Keep copy-trade purchase tracking in line after selling part of a position by hand,
+// so later copied sells are sized from what we still hold
+pub async fn record_manual_sell(
+    db: &Db,
+    config: &EnvConfig,
+    position: &UserPosition,
+    sold_tokens: f64,
+) -> Result<()> {
+    let size = position.size.unwrap_or(0.0);
+    let Some(asset) = position.asset.as_deref() else {
+        return Ok(());
+    };
+    if size <= 0.0 || sold_tokens <= 0.0 {
+        return Ok(());
+    }
+    let fraction = (sold_tokens / size).min(1.0);
+
+    for trader in &config.user_addresses {
+        let buys = db
+            .find_all_buy_activities_for_asset(trader, asset, &position.condition_id)
+            .await?;
+        for buy in buys {
+            if let Some(ref id) = buy.id {
+                let left = if fraction >= 0.99 {
+                    0.0
+                } else {
+                    buy.my_bought_size.unwrap_or(0.0) * (1.0 - fraction)
+                };
+                db.update_activity(trader, id, &mongodb::bson::doc! { "myBoughtSize": left })
+                    .await?;
+            }
+        }
+    }
+    Logger::info(&format!(
+        "📝 Updated purchase tracking ({:.1}% of position sold)",
+        fraction * 100.0
+    ));
+    Ok(())
+}
+
+// Sell each (position, tokens) pair at the best bid; dry run only prints the plan
+pub async fn sell_positions(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    plan: &[(UserPosition, f64)],
+    dry_run: bool,
+) -> Result<()> {
+    if plan.is_empty() {
+        Logger::info("Nothing to sell");
+        return Ok(());
+    }
+
+    let mut planned_value = 0.0;
+    for (p, tokens) in plan {
+        let value = tokens * p.cur_price.unwrap_or(0.0);
+        planned_value += value;
+        Logger::info(&format!(
+            "{} - sell {:.2} of {:.2} tokens (~${:.2} @ ${:.4})",
+            position_label(p),
+            tokens,
+            p.size.unwrap_or(0.0),
+            value,
+            p.cur_price.unwrap_or(0.0)
+        ));
+    }
+    Logger::info(&format!(
+        "{} position(s), ~${:.2} at current prices",
+        plan.len(),
+        planned_value
+    ));
+    if dry_run {
+        Logger::warning("Dry run - no orders sent");
+        return Ok(());
+    }
+
+    let db = Db::connect(&config.mongo_uri).await?;
+    let (clob_client, signer) = crate::utils::create_clob_client(config).await?;
+
+    let mut received = 0.0;
+    let mut failed = 0;
+    for (p, tokens) in plan {
+        let Some(asset) = p.asset.as_deref() else {
+            continue;
+        };
+        Logger::separator();
+        Logger::info(&format!("Selling {}", position_label(p)));
+        match crate::utils::sell_at_best_bid(config, &clob_client, http_client, &signer, asset, *tokens)
+            .await
+        {
+            Ok(outcome) => {
+                received += outcome.usdc_received;
+                if outcome.sold_tokens < *tokens {
+                    failed += 1;
+                    Logger::warning(&format!(
+                        "Only sold {:.2} of {:.2} tokens",
+                        outcome.sold_tokens, tokens
+                    ));
+                }
+                if let Err(e) = record_manual_sell(&db, config, p, outcome.sold_tokens).await {
+                    Logger::error(&format!("Failed to update purchase tracking: {}", e));
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                Logger::error(&format!("Sell failed: {}", e));
+            }
+        }
+    }
+
+    Logger::separator();
+    if failed == 0 {
+        Logger::success(&format!("Done: received ~${:.2}", received));
+    } else {
+        Logger::warning(&format!(
+            "Done with {} incomplete sell(s): received ~${:.2}",
+            failed, received
+        ));
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use alloy::signers::local::PrivateKeySigner;
 use polymarket_client_sdk::clob::Client as ClobClient;
+use polymarket_client_sdk::auth::state::Authenticated;
 use polymarket_client_sdk::auth::Normal;
 use polymarket_client_sdk::clob::types::{OrderType as SdkOrderType, Amount, Side};
 use polymarket_client_sdk::types::Decimal;
@@ -9,12 +10,13 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::config::{get_trade_multiplier, EnvConfig};
 use crate::db::Db;
+use crate::services::RiskManager;
 use crate::types::{UserActivity, UserPosition};
 use crate::utils::{fetch_data, Logger};
 
 // Min order sizes (PM API requirements)
 const MIN_ORDER_SIZE_USD: f64 = 1.0;
-const MIN_ORDER_SIZE_TOKENS: f64 = 1.0;
+pub(crate) const MIN_ORDER_SIZE_TOKENS: f64 = 1.0;
 
 // Extract error msg from API response (handles nested error objs)
 fn extract_order_error(response: &serde_json::Value) -> Option<String> {
@@ -66,24 +68,43 @@ fn is_insufficient_balance_or_allowance_error(message: Option<&str>) -> bool {
 // Main order router - dispatches to buy/sell/merge strategies
 pub async fn post_order(
     config: &EnvConfig,
-    clob_client: &ClobClient,
+    clob_client: &ClobClient<Authenticated<Normal>>,
     condition: &str,
     my_position: Option<&UserPosition>,
     user_position: Option<&UserPosition>,
+    my_positions: &[UserPosition],
     trade: &UserActivity,
     my_balance: f64,
     _user_balance: f64,
     user_address: &str,
     http_client: &reqwest::Client,
     db: &Db,
+    risk: &RiskManager,
     signer: &mut PrivateKeySigner,
 ) -> Result<()> {
+    // Per-trader side & market restrictions from traders.json
+    if let Some(reason) = config
+        .trader_override(user_address)
+        .and_then(|t| t.skip_reason(condition, trade))
+    {
+        Logger::info(&format!("Skipping trade: {}", reason));
+        if let Some(ref id) = trade.id {
+            db.update_activity(
+                user_address,
+                id,
+                &mongodb::bson::doc! { "bot": true, "skipReason": reason },
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
     match condition {
         "merge" => {
             execute_merge_strategy(config, trade, my_position, user_address, clob_client, http_client, db, signer).await?;
         }
         "buy" => {
-            execute_buy_strategy(config, trade, my_position, my_balance, user_address, clob_client, http_client, db, signer).await?;
+            execute_buy_strategy(config, trade, my_position, my_positions, my_balance, user_address, clob_client, http_client, db, risk, signer).await?;
         }
         "sell" => {
             execute_sell_strategy(config, trade, my_position, user_position, user_address, clob_client, http_client, db, signer).await?;
@@ -101,7 +122,7 @@ async fn execute_merge_strategy(
     trade: &UserActivity,
     my_position: Option<&UserPosition>,
     user_address: &str,
-    clob_client: &ClobClient,
+    clob_client: &ClobClient<Authenticated<Normal>>,
     http_client: &reqwest::Client,
     db: &Db,
     signer: &mut PrivateKeySigner,
@@ -131,7 +152,7 @@ async fn execute_merge_strategy(
         return Ok(());
     }
 
-    let mut remaining = my_position.size.unwrap_or(0.0);
+    let remaining = my_position.size.unwrap_or(0.0);
 
     // Skip if position too small (below PM min)
     if remaining < MIN_ORDER_SIZE_TOKENS {
@@ -146,10 +167,48 @@ async fn execute_merge_strategy(
         return Ok(());
     }
 
+    let outcome =
+        sell_at_best_bid(config, clob_client, http_client, signer, asset, remaining).await?;
+    let (retry, abort_due_to_funds) = (outcome.retries, outcome.aborted_for_funds);
+
+    if let Some(ref id) = trade.id {
+        let mut update_doc = mongodb::bson::doc! { "bot": true };
+        if abort_due_to_funds {
+            update_doc.insert("botExcutedTime", config.retry_limit as i64);
+        } else if retry >= config.retry_limit {
+            update_doc.insert("botExcutedTime", retry as i64);
+        }
+        db.update_activity(user_address, id, &update_doc).await?;
+    }
+
+    Ok(())
+}
+
+// Outcome of selling tokens into the order book
+#[derive(Debug, Clone, Default)]
+pub struct SellOutcome {
+    pub sold_tokens: f64,
+    pub usdc_received: f64,
+    pub retries: u32,
+    pub aborted_for_funds: bool,
+}
+
+// Sell tokens at the best bid level by level (FOK) until done, out of bids or out of retries
+pub async fn sell_at_best_bid(
+    config: &EnvConfig,
+    clob_client: &ClobClient<Authenticated<Normal>>,
+    http_client: &reqwest::Client,
+    signer: &PrivateKeySigner,
+    asset: &str,
+    amount: f64,
+) -> Result<SellOutcome> {
+    let mut remaining = amount;
     let mut retry = 0u32;
     let mut abort_due_to_funds = false;
+    let mut sold_tokens = 0.0;
+    let mut usdc_received = 0.0;
 
-        while remaining > 0.0 && retry < config.retry_limit {
+    while remaining > 0.0 && retry < config.retry_limit {
         let book_url = format!(
             "{}/book?token_id={}",
             config.clob_http_url.trim_end_matches('/'),
@@ -170,10 +229,6 @@ async fn execute_merge_strategy(
 
         if bids.is_empty() {
             Logger::warning("No bids available in order book");
-            if let Some(ref id) = trade.id {
-                db.update_activity(user_address, id, &mongodb::bson::doc! { "bot": true })
-                    .await?;
-            }
             break;
         }
 
@@ -231,7 +286,7 @@ async fn execute_merge_strategy(
             .expiration(exp)
             .build()
             .await?;
-        let signed = clob_client.sign(&signer, order).await?;
+        let signed = clob_client.sign(signer, order).await?;
         let resp = clob_client.post_order(signed).await?;
 
         let error_msg = resp.error_msg.as_deref();
@@ -243,6 +298,8 @@ async fn execute_merge_strategy(
                 &format!("Sold {:.2} tokens at ${:.4}", sell_amount, price),
             );
             remaining -= sell_amount;
+            sold_tokens += sell_amount;
+            usdc_received += sell_amount * price;
         } else {
             if is_insufficient_balance_or_allowance_error(error_msg) {
                 abort_due_to_funds = true;
@@ -265,17 +322,13 @@ async fn execute_merge_strategy(
         }
     }
 
-    if let Some(ref id) = trade.id {
-        let mut update_doc = mongodb::bson::doc! { "bot": true };
-        if abort_due_to_funds {
-            update_doc.insert("botExcutedTime", config.retry_limit as i64);
-        } else if retry >= config.retry_limit {
-            update_doc.insert("botExcutedTime", retry as i64);
-        }
-        db.update_activity(user_address, id, &update_doc).await?;
-    }
 
-    Ok(())
+    Ok(SellOutcome {
+        sold_tokens,
+        usdc_received,
+        retries: retry,
+        aborted_for_funds: abort_due_to_funds,
+    })
 }
 
 // Buy strategy: copy trader's buy order (with size limits & multipliers)
@@ -283,11 +336,13 @@ async fn execute_buy_strategy(
     config: &EnvConfig,
     trade: &UserActivity,
     my_position: Option<&UserPosition>,
+    my_positions: &[UserPosition],
     my_balance: f64,
     user_address: &str,
-    clob_client: &ClobClient,
+    clob_client: &ClobClient<Authenticated<Normal>>,
     http_client: &reqwest::Client,
     db: &Db,
+    risk: &RiskManager,
     signer: &mut PrivateKeySigner,
 ) -> Result<()> {
     Logger::info("Executing BUY strategy...");
@@ -310,19 +365,26 @@ async fn execute_buy_strategy(
         .unwrap_or(0.0);
 
     // Calc order size based on strategy (percentage/fixed/adaptive)
-    let order_calc = crate::config::calculate_order_size(
-        &config.copy_strategy_config,
+    let strategy = config.strategy_for(user_address);
+    let mut order_calc = crate::config::calculate_order_size(
+        strategy,
         trade.usdc_size.unwrap_or(0.0),
         my_balance,
         current_position_value,
     );
 
+    // Portfolio limits (daily volume, exposure, drawdown...) can only shrink the order
+    risk.check_buy(&mut order_calc, trade, user_address, my_balance, my_positions)
+        .await;
+
     Logger::info(&format!("📊 {}", order_calc.reasoning));
 
     // Skip if below min order size
-    if order_calc.final_amount < config.copy_strategy_config.min_order_size_usd {
+    if order_calc.final_amount < strategy.min_order_size_usd {
         Logger::warning(&format!("❌ Cannot execute: {}", order_calc.reasoning));
-        if order_calc.below_minimum {
+        if order_calc.limited_by_risk {
+            Logger::warning("💡 Blocked by risk limits - see reasoning above");
+        } else if order_calc.below_minimum {
             Logger::warning("💡 Increase COPY_SIZE or wait for larger trades");
         }
         if let Some(ref id) = trade.id {
@@ -338,6 +400,7 @@ async fn execute_buy_strategy(
     let mut retry = 0u32;
     let mut abort_due_to_funds = false;
     let mut total_bought_tokens = 0.0;
+    let mut total_spent = 0.0;
 
     while remaining > 0.0 && retry < config.retry_limit {
         let book_url = format!(
@@ -468,6 +531,7 @@ async fn execute_buy_strategy(
             retry = 0;
             let tokens_bought = order_size / best_price;
             total_bought_tokens += tokens_bought;
+            total_spent += order_size;
             Logger::order_result(
                 true,
                 &format!(
@@ -499,6 +563,11 @@ async fn execute_buy_strategy(
         }
     }
 
+    // Before the activity update, so a Mongo error can't leave the fill out of the risk limits
+    if let Err(e) = risk.record_buy(trade, user_address, total_spent).await {
+        Logger::error(&format!("Failed to record buy for risk limits: {}", e));
+    }
+
     if let Some(ref id) = trade.id {
         let mut update_doc = mongodb::bson::doc! { "bot": true };
         if abort_due_to_funds {
@@ -528,7 +597,7 @@ async fn execute_sell_strategy(
     my_position: Option<&UserPosition>,
     user_position: Option<&UserPosition>,
     user_address: &str,
-    clob_client: &ClobClient,
+    clob_client: &ClobClient<Authenticated<Normal>>,
     http_client: &reqwest::Client,
     db: &Db,
     signer: &mut PrivateKeySigner,
@@ -615,7 +684,7 @@ async fn execute_sell_strategy(
         };
 
         let multiplier = get_trade_multiplier(
-            &config.copy_strategy_config,
+            config.strategy_for(user_address),
             trade.usdc_size.unwrap_or(0.0),
         );
         let calculated = base_sell_size * multiplier;
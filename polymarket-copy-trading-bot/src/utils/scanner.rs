@@ -0,0 +1,617 @@
+use anyhow::Result;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::config::EnvConfig;
+use crate::types::UserActivity;
+use crate::utils::theme::colors;
+use crate::utils::{arg_value, fetch_data, fetch_positions, Logger};
+
+const DATA_API: &str = "https://data-api.polymarket.com";
+const ACTIVITY_PAGE: usize = 500;
+// The data API stops paging around here; longer histories are cut off (history_complete = false)
+const ACTIVITY_MAX_OFFSET: usize = 10_000;
+const LEADERBOARD_PAGE: usize = 50;
+const SCAN_CONCURRENCY: usize = 4;
+
+// CLI options shared by the scanner binaries
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub history_days: u32,
+    pub candidates: usize,
+    pub min_trades: u32,
+    pub top: usize,
+    // Leaderboard window: day, week, month or all
+    pub period: String,
+    // Recent trades at least this big also add their wallet to the pool
+    pub min_trade_usd: f64,
+}
+
+impl ScanOptions {
+    pub fn from_args() -> Self {
+        let num = |name: &str| arg_value(name).and_then(|v| v.parse::<f64>().ok());
+        Self {
+            history_days: num("--days").map(|v| v as u32).unwrap_or(30).max(1),
+            candidates: num("--candidates").map(|v| v as usize).unwrap_or(50),
+            min_trades: num("--min-trades").map(|v| v as u32).unwrap_or(10),
+            top: num("--top").map(|v| v as usize).unwrap_or(10),
+            period: arg_value("--period")
+                .unwrap_or_else(|| "month".to_string())
+                .to_uppercase(),
+            min_trade_usd: num("--min-trade-usd").unwrap_or(1000.0),
+        }
+    }
+}
+
+// One scored wallet; field names match what `aggregate` reads
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TraderStats {
+    pub address: String,
+    pub status: String,
+    // Percent of USDC spent on buys in the window
+    pub roi: f64,
+    pub total_pnl: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub invested: f64,
+    // Percent of closed positions that made money
+    pub win_rate: f64,
+    // Annualised, from daily realized P&L
+    pub sharpe: f64,
+    // Percent, on capital = peak cost of open positions
+    pub max_drawdown: f64,
+    pub avg_holding_hours: f64,
+    pub trades: u32,
+    pub trades_per_day: f64,
+    pub markets_traded: u32,
+    // Herfindahl index of buy volume per market (1 = a single market)
+    pub market_concentration: f64,
+    // Percent of buy volume in the biggest market
+    pub top_market_share: f64,
+    pub closed_positions: u32,
+    pub open_positions: u32,
+    pub history_complete: bool,
+}
+
+impl TraderStats {
+    pub fn is_profitable(&self) -> bool {
+        self.total_pnl > 0.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanConfig {
+    pub history_days: u32,
+    pub multiplier: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_order_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starting_capital: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanSummary {
+    pub total_analyzed: u32,
+    pub profitable: u32,
+    pub avg_roi: f64,
+    pub avg_win_rate: f64,
+}
+
+impl ScanSummary {
+    // From (roi, win_rate) of every analysed trader
+    pub fn from_rows(rows: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        let mut s = Self::default();
+        for (roi, win_rate) in rows {
+            s.total_analyzed += 1;
+            if roi > 0.0 {
+                s.profitable += 1;
+            }
+            s.avg_roi += roi;
+            s.avg_win_rate += win_rate;
+        }
+        if s.total_analyzed > 0 {
+            s.avg_roi /= s.total_analyzed as f64;
+            s.avg_win_rate /= s.total_analyzed as f64;
+        }
+        s
+    }
+}
+
+// The JSON file `aggregate` collects from the *_results directories
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanResult<T: Serialize> {
+    pub scan_date: String,
+    pub scan_type: String,
+    pub config: ScanConfig,
+    pub summary: ScanSummary,
+    pub traders: Vec<T>,
+}
+
+impl ScanResult<TraderStats> {
+    pub fn new(scan_type: &str, history_days: u32, traders: Vec<TraderStats>) -> Self {
+        Self {
+            scan_date: chrono::Utc::now().to_rfc3339(),
+            scan_type: scan_type.to_string(),
+            config: ScanConfig {
+                history_days,
+                multiplier: 1.0,
+                min_order_size: None,
+                starting_capital: None,
+            },
+            summary: ScanSummary::from_rows(traders.iter().map(|t| (t.roi, t.win_rate))),
+            traders,
+        }
+    }
+}
+
+// Write <dir>/<prefix>_<timestamp>.json and return its path
+pub fn write_scan_result<T: Serialize>(
+    dir: &str,
+    prefix: &str,
+    result: &ScanResult<T>,
+) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = PathBuf::from(dir).join(format!(
+        "{}_{}.json",
+        prefix,
+        chrono::Utc::now().format("%Y%m%d_%H%M%S")
+    ));
+    std::fs::write(&path, serde_json::to_string_pretty(result)?)?;
+    Ok(path)
+}
+
+// Top wallets by P&L from the data API leaderboard
+pub async fn fetch_leaderboard(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    period: &str,
+    limit: usize,
+) -> Result<Vec<String>> {
+    let mut wallets = Vec::new();
+    let mut offset = 0;
+    while wallets.len() < limit {
+        let page = (limit - wallets.len()).min(LEADERBOARD_PAGE);
+        let url = format!(
+            "{}/v1/leaderboard?category=OVERALL&timePeriod={}&orderBy=PNL&limit={}&offset={}",
+            DATA_API, period, page, offset
+        );
+        let data = fetch_data(
+            http_client,
+            &url,
+            config.request_timeout_ms,
+            config.network_retry_limit,
+        )
+        .await?;
+        let entries = data.as_array().cloned().unwrap_or_default();
+        wallets.extend(
+            entries
+                .iter()
+                .filter_map(|e| e.get("proxyWallet").and_then(|v| v.as_str()))
+                .map(|w| w.to_lowercase()),
+        );
+        if entries.len() < page {
+            break;
+        }
+        offset += entries.len();
+    }
+    Ok(wallets)
+}
+
+// Wallets behind the latest trades of at least `min_usd`
+pub async fn fetch_large_traders(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    min_usd: f64,
+) -> Result<Vec<String>> {
+    let url = format!(
+        "{}/trades?limit=500&filterType=CASH&filterAmount={}",
+        DATA_API, min_usd
+    );
+    let data = fetch_data(
+        http_client,
+        &url,
+        config.request_timeout_ms,
+        config.network_retry_limit,
+    )
+    .await?;
+    Ok(data
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|t| t.get("proxyWallet").and_then(|v| v.as_str()))
+                .map(|w| w.to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// Leaderboard first, then big recent traders, deduplicated and capped at opts.candidates
+pub async fn gather_candidates(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    opts: &ScanOptions,
+) -> Result<Vec<String>> {
+    let mut pool = Vec::new();
+    match fetch_leaderboard(config, http_client, &opts.period, opts.candidates).await {
+        Ok(w) => pool.extend(w),
+        Err(e) => Logger::warning(&format!("Leaderboard unavailable: {}", e)),
+    }
+    match fetch_large_traders(config, http_client, opts.min_trade_usd).await {
+        Ok(w) => pool.extend(w),
+        Err(e) => Logger::warning(&format!("Recent trades unavailable: {}", e)),
+    }
+
+    let mut seen = HashSet::new();
+    pool.retain(|w| seen.insert(w.clone()));
+    pool.truncate(opts.candidates);
+    if pool.is_empty() {
+        anyhow::bail!("No candidate wallets found");
+    }
+    Ok(pool)
+}
+
+// All activity (trades, redeems, merges...) since `since_ts`, oldest first
+pub async fn fetch_activity_history(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    wallet: &str,
+    since_ts: i64,
+) -> Result<(Vec<UserActivity>, bool)> {
+    let mut activities = Vec::new();
+    let mut offset = 0;
+    loop {
+        let url = format!(
+            "{}/activity?user={}&limit={}&offset={}&start={}&sortBy=TIMESTAMP&sortDirection=ASC",
+            DATA_API, wallet, ACTIVITY_PAGE, offset, since_ts
+        );
+        let data = fetch_data(
+            http_client,
+            &url,
+            config.request_timeout_ms,
+            config.network_retry_limit,
+        )
+        .await?;
+        let page = data.as_array().cloned().unwrap_or_default();
+        activities.extend(
+            page.iter()
+                .filter_map(|a| serde_json::from_value::<UserActivity>(a.clone()).ok()),
+        );
+        if page.len() < ACTIVITY_PAGE {
+            break;
+        }
+        offset += ACTIVITY_PAGE;
+        if offset >= ACTIVITY_MAX_OFFSET {
+            return Ok((activities, false));
+        }
+    }
+    activities.sort_by_key(|a| a.timestamp.unwrap_or(0));
+    Ok((activities, true))
+}
+
+// Tokens held in one asset, bought inside the window
+#[derive(Default)]
+struct Lot {
+    condition_id: String,
+    tokens: f64,
+    cost: f64,
+    // sum(tokens * buy timestamp), for the average entry time
+    entry_weight: f64,
+    realized: f64,
+}
+
+impl Lot {
+    // Take `tokens` out at their average cost; returns that cost
+    fn reduce(&mut self, tokens: f64) -> f64 {
+        if self.tokens <= 0.0 {
+            return 0.0;
+        }
+        let share = (tokens / self.tokens).min(1.0);
+        let cost = self.cost * share;
+        self.cost -= cost;
+        self.entry_weight *= 1.0 - share;
+        self.tokens -= tokens.min(self.tokens);
+        cost
+    }
+
+    fn entry_ts(&self, fallback: i64) -> f64 {
+        if self.tokens > 0.0 {
+            self.entry_weight / self.tokens
+        } else {
+            fallback as f64
+        }
+    }
+}
+
+struct ClosedPosition {
+    pnl: f64,
+    held_secs: f64,
+}
+
+// Replay a wallet's activity into position-level results; open lots are marked to `prices`
+pub fn analyze_activity(
+    address: &str,
+    activities: &[UserActivity],
+    prices: &HashMap<String, f64>,
+    history_days: u32,
+    now_ts: i64,
+) -> TraderStats {
+    let start_ts = now_ts - history_days as i64 * 86_400;
+    let day_of = |ts: i64| ((ts - start_ts).max(0) / 86_400).min(history_days as i64 - 1) as usize;
+
+    let mut lots: HashMap<String, Lot> = HashMap::new();
+    let mut closed: Vec<ClosedPosition> = Vec::new();
+    let mut daily_pnl = vec![0.0; history_days as usize];
+    let mut volume_by_market: HashMap<String, f64> = HashMap::new();
+    let mut trades = 0u32;
+    let mut invested = 0.0;
+    let mut realized = 0.0;
+    let mut peak_exposure: f64 = 0.0;
+
+    for a in activities {
+        let ts = a.timestamp.unwrap_or(now_ts);
+        let cid = a.condition_id.clone().unwrap_or_default();
+        let size = a.size.unwrap_or(0.0);
+        let usdc = a.usdc_size.unwrap_or(size * a.price.unwrap_or(0.0));
+        let mut pnl = 0.0;
+
+        match a.activity_type.as_deref() {
+            Some("TRADE") => {
+                trades += 1;
+                let Some(asset) = a.asset.clone() else {
+                    continue;
+                };
+                if a.side_buy() {
+                    let lot = lots.entry(asset).or_default();
+                    lot.condition_id = cid.clone();
+                    lot.tokens += size;
+                    lot.cost += usdc;
+                    lot.entry_weight += size * ts as f64;
+                    invested += usdc;
+                    *volume_by_market.entry(cid).or_default() += usdc;
+                } else if let Some(lot) = lots.get_mut(&asset) {
+                    // Tokens bought before the window have no known cost and are ignored
+                    let matched = size.min(lot.tokens);
+                    if matched > 0.0 && size > 0.0 {
+                        let entry = lot.entry_ts(ts);
+                        let proceeds = usdc * matched / size;
+                        let gain = proceeds - lot.reduce(matched);
+                        lot.realized += gain;
+                        pnl += gain;
+                        if lot.tokens < 1e-6 {
+                            closed.push(ClosedPosition {
+                                pnl: lot.realized,
+                                held_secs: ts as f64 - entry,
+                            });
+                            lots.remove(&asset);
+                        }
+                    }
+                }
+            }
+            Some("REDEEM") => {
+                // Payout is per market, so every lot in it closes as one position
+                let assets: Vec<String> = lots
+                    .iter()
+                    .filter(|(_, l)| l.condition_id == cid)
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                if assets.is_empty() {
+                    continue;
+                }
+                let mut cost = 0.0;
+                let mut prior = 0.0;
+                let mut entry = 0.0;
+                let mut tokens = 0.0;
+                for asset in &assets {
+                    if let Some(lot) = lots.remove(asset) {
+                        entry += lot.entry_weight;
+                        tokens += lot.tokens;
+                        prior += lot.realized;
+                        cost += lot.cost;
+                    }
+                }
+                let gain = usdc - cost;
+                pnl += gain;
+                let entry_ts = if tokens > 0.0 { entry / tokens } else { ts as f64 };
+                closed.push(ClosedPosition {
+                    pnl: prior + gain,
+                    held_secs: ts as f64 - entry_ts,
+                });
+            }
+            Some("MERGE") => {
+                // `size` of each outcome turns back into `usdc`, split evenly between the sides
+                let assets: Vec<String> = lots
+                    .iter()
+                    .filter(|(_, l)| l.condition_id == cid)
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                for asset in &assets {
+                    let Some(lot) = lots.get_mut(asset) else {
+                        continue;
+                    };
+                    let entry = lot.entry_ts(ts);
+                    let gain = usdc / 2.0 - lot.reduce(size);
+                    lot.realized += gain;
+                    pnl += gain;
+                    if lot.tokens < 1e-6 {
+                        closed.push(ClosedPosition {
+                            pnl: lot.realized,
+                            held_secs: ts as f64 - entry,
+                        });
+                        lots.remove(asset);
+                    }
+                }
+            }
+            // SPLIT, REWARD, CONVERSION... don't change what we can score
+            _ => continue,
+        }
+
+        realized += pnl;
+        daily_pnl[day_of(ts)] += pnl;
+        peak_exposure = peak_exposure.max(lots.values().map(|l| l.cost).sum());
+    }
+
+    // Open lots at the current price; unknown prices count at cost
+    let mut unrealized = 0.0;
+    for (asset, lot) in &lots {
+        if let Some(price) = prices.get(asset) {
+            unrealized += lot.tokens * price - lot.cost;
+        }
+    }
+
+    let mut stats = TraderStats {
+        address: address.to_string(),
+        realized_pnl: realized,
+        unrealized_pnl: unrealized,
+        total_pnl: realized + unrealized,
+        invested,
+        trades,
+        trades_per_day: trades as f64 / history_days as f64,
+        markets_traded: volume_by_market.len() as u32,
+        closed_positions: closed.len() as u32,
+        open_positions: lots.len() as u32,
+        history_complete: true,
+        ..Default::default()
+    };
+    if invested > 0.0 {
+        stats.roi = stats.total_pnl / invested * 100.0;
+        let shares = volume_by_market.values().map(|v| v / invested);
+        stats.market_concentration = shares.clone().map(|s| s * s).sum();
+        stats.top_market_share = shares.fold(0.0, f64::max) * 100.0;
+    }
+    if !closed.is_empty() {
+        let wins = closed.iter().filter(|c| c.pnl > 0.0).count();
+        stats.win_rate = wins as f64 / closed.len() as f64 * 100.0;
+        stats.avg_holding_hours =
+            closed.iter().map(|c| c.held_secs.max(0.0)).sum::<f64>() / closed.len() as f64 / 3600.0;
+    }
+
+    let n = daily_pnl.len() as f64;
+    let mean = daily_pnl.iter().sum::<f64>() / n;
+    let std = (daily_pnl.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if std > 0.0 {
+        stats.sharpe = mean / std * 365f64.sqrt();
+    }
+
+    let capital = peak_exposure.max(1.0);
+    let mut equity = capital;
+    let mut peak = capital;
+    for p in &daily_pnl {
+        equity += p;
+        peak = peak.max(equity);
+        stats.max_drawdown = stats.max_drawdown.max((peak - equity) / peak * 100.0);
+    }
+    stats
+}
+
+// Fetch a wallet's history & open positions and score it
+pub async fn analyze_trader(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    wallet: &str,
+    history_days: u32,
+) -> Result<TraderStats> {
+    let now_ts = chrono::Utc::now().timestamp();
+    let since_ts = now_ts - history_days as i64 * 86_400;
+    let (activities, complete) =
+        fetch_activity_history(config, http_client, wallet, since_ts).await?;
+    let prices: HashMap<String, f64> = fetch_positions(config, http_client, wallet)
+        .await?
+        .into_iter()
+        .filter_map(|p| Some((p.asset?, p.cur_price?)))
+        .collect();
+
+    let mut stats = analyze_activity(wallet, &activities, &prices, history_days, now_ts);
+    stats.history_complete = complete;
+    stats.status = if stats.trades == 0 {
+        "inactive"
+    } else if stats.is_profitable() {
+        "profitable"
+    } else {
+        "unprofitable"
+    }
+    .to_string();
+    Ok(stats)
+}
+
+// Score many wallets a few at a time; failures are logged and left out
+pub async fn analyze_traders(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    wallets: &[String],
+    history_days: u32,
+) -> Vec<TraderStats> {
+    let total = wallets.len();
+    let mut done = 0;
+    let mut results = Vec::new();
+    let mut tasks = stream::iter(wallets.iter().cloned())
+        .map(|w| async move {
+            let r = analyze_trader(config, http_client, &w, history_days).await;
+            (w, r)
+        })
+        .buffer_unordered(SCAN_CONCURRENCY);
+    while let Some((wallet, result)) = tasks.next().await {
+        done += 1;
+        match result {
+            Ok(stats) => {
+                Logger::info(&format!(
+                    "[{}/{}] {} - {} trades, ROI {:+.1}%",
+                    done,
+                    total,
+                    Logger::format_address(&wallet),
+                    stats.trades,
+                    stats.roi
+                ));
+                results.push(stats);
+            }
+            Err(e) => Logger::warning(&format!(
+                "[{}/{}] {} - failed: {}",
+                done,
+                total,
+                Logger::format_address(&wallet),
+                e
+            )),
+        }
+    }
+    results
+}
+
+pub fn print_traders(traders: &[TraderStats], limit: usize) {
+    println!();
+    println!(
+        "{}  #  | Address                                    | ROI      | P&L        | Win%  | Sharpe | MaxDD  | Hold h | Tr/day | HHI{}",
+        colors::BOLD,
+        colors::RESET
+    );
+    println!("{}{}{}", colors::MUTED, "─".repeat(112), colors::RESET);
+    for (i, t) in traders.iter().take(limit).enumerate() {
+        let roi_color = if t.roi >= 0.0 { colors::SUCCESS } else { colors::ERROR };
+        println!(
+            "  {:2} | {}{:42}{} | {}{:>8}{} | {:>10} | {:>5.1} | {:>6.2} | {:>5.1}% | {:>6.1} | {:>6.1} | {:.2}{}",
+            i + 1,
+            colors::ACCENT,
+            t.address,
+            colors::RESET,
+            roi_color,
+            format!("{:+.1}%", t.roi),
+            colors::RESET,
+            format!("${:.0}", t.total_pnl),
+            t.win_rate,
+            t.sharpe,
+            t.max_drawdown,
+            t.avg_holding_hours,
+            t.trades_per_day,
+            t.market_concentration,
+            if t.history_complete { "" } else { " *" }
+        );
+    }
+    if traders.iter().take(limit).any(|t| !t.history_complete) {
+        println!(
+            "\n{}  * history cut off by the API - stats cover the oldest part of the window{}",
+            colors::MUTED,
+            colors::RESET
+        );
+    }
+    println!();
+}
@@ -0,0 +1,389 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::config::{calculate_order_size, get_trade_multiplier, CopyStrategyConfig, EnvConfig};
+use crate::services::{
+    get_aggregation_key, should_aggregate, TradeWithUser, TRADE_AGGREGATION_MIN_TOTAL_USD,
+};
+use crate::types::UserActivity;
+use crate::utils::fetch_positions;
+use crate::utils::post_order::MIN_ORDER_SIZE_TOKENS;
+use crate::utils::scanner::fetch_activity_history;
+
+// What one simulation run is allowed to do
+#[derive(Debug, Clone)]
+pub struct SimOptions {
+    pub history_days: u32,
+    pub starting_capital: f64,
+    // Our fills are this much worse than the trader's (100 bps = 1% of the price)
+    pub slippage_bps: f64,
+}
+
+// A trader's activity in the window plus current prices for marking open positions
+pub struct TraderHistory {
+    pub activities: Vec<UserActivity>,
+    pub prices: HashMap<String, f64>,
+    pub complete: bool,
+}
+
+pub async fn fetch_trader_history(
+    config: &EnvConfig,
+    http_client: &reqwest::Client,
+    trader: &str,
+    history_days: u32,
+) -> Result<TraderHistory> {
+    let since_ts = chrono::Utc::now().timestamp() - history_days as i64 * 86_400;
+    let (activities, complete) =
+        fetch_activity_history(config, http_client, trader, since_ts).await?;
+    let prices = fetch_positions(config, http_client, trader)
+        .await?
+        .into_iter()
+        .filter_map(|p| Some((p.asset?, p.cur_price?)))
+        .collect();
+    Ok(TraderHistory {
+        activities,
+        prices,
+        complete,
+    })
+}
+
+// Strategy with every multiplier (flat and tiered) scaled, for multiplier sweeps
+pub fn scaled_strategy(base: &CopyStrategyConfig, multiplier: f64) -> CopyStrategyConfig {
+    let mut strategy = base.clone();
+    if (multiplier - 1.0).abs() > 1e-9 {
+        strategy.trade_multiplier = Some(strategy.trade_multiplier.unwrap_or(1.0) * multiplier);
+        if let Some(ref mut tiers) = strategy.tiered_multipliers {
+            for tier in tiers.iter_mut() {
+                tier.multiplier *= multiplier;
+            }
+        }
+    }
+    strategy
+}
+
+// One simulated trader; address/roi/total_pnl/win_rate/copied_trades are what `aggregate` reads
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SimResult {
+    pub address: String,
+    pub status: String,
+    // Percent of starting capital
+    pub roi: f64,
+    pub total_pnl: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    // Percent of our closed positions that made money
+    pub win_rate: f64,
+    pub starting_capital: f64,
+    pub final_equity: f64,
+    pub invested: f64,
+    pub slippage_cost: f64,
+    pub trader_trades: u32,
+    pub copied_trades: u32,
+    pub missed_trades: u32,
+    pub missed_below_minimum: u32,
+    pub missed_no_balance: u32,
+    pub missed_no_position: u32,
+    pub missed_by_settings: u32,
+    pub open_positions: u32,
+    pub history_complete: bool,
+}
+
+#[derive(Default)]
+struct SimPosition {
+    condition_id: String,
+    tokens: f64,
+    cost: f64,
+    realized: f64,
+    last_price: f64,
+}
+
+struct PendingGroup {
+    first_ts: i64,
+    trades: Vec<UserActivity>,
+}
+
+struct Simulation<'a> {
+    config: &'a EnvConfig,
+    strategy: &'a CopyStrategyConfig,
+    trader: &'a str,
+    slippage: f64,
+    balance: f64,
+    positions: HashMap<String, SimPosition>,
+    // Trader's tokens per asset, counted from buys inside the window
+    trader_tokens: HashMap<String, f64>,
+    closed: u32,
+    wins: u32,
+    result: SimResult,
+}
+
+impl Simulation<'_> {
+    fn buy(&mut self, trade: &UserActivity, trades_in_order: u32) {
+        let Some(asset) = trade.asset.clone() else {
+            return;
+        };
+        let price = trade.price.unwrap_or(0.0);
+        if price <= 0.0 {
+            return;
+        }
+        let held_value = self.positions.get(&asset).map(|p| p.cost).unwrap_or(0.0);
+        let calc = calculate_order_size(
+            self.strategy,
+            trade.usdc_size.unwrap_or(0.0),
+            self.balance,
+            held_value,
+        );
+        if calc.final_amount < self.strategy.min_order_size_usd {
+            self.result.missed_below_minimum += trades_in_order;
+            return;
+        }
+        // calculate_order_size rounds small orders up to the minimum, which the exchange would reject
+        if calc.final_amount > self.balance {
+            self.result.missed_no_balance += trades_in_order;
+            return;
+        }
+
+        let amount = calc.final_amount;
+        let fill_price = (price * (1.0 + self.slippage)).min(0.999);
+        let tokens = amount / fill_price;
+        self.balance -= amount;
+        self.result.invested += amount;
+        self.result.slippage_cost += amount - tokens * price;
+        self.result.copied_trades += trades_in_order;
+
+        let pos = self.positions.entry(asset).or_default();
+        pos.condition_id = trade.condition_id.clone().unwrap_or_default();
+        pos.tokens += tokens;
+        pos.cost += amount;
+        pos.last_price = price;
+    }
+
+    // Same sizing as execute_sell_strategy: the trader's sold fraction of our tokens, times the multiplier
+    fn sell(&mut self, trade: &UserActivity, trader_before: f64) {
+        let Some(asset) = trade.asset.clone() else {
+            return;
+        };
+        let Some(pos) = self.positions.get_mut(&asset).filter(|p| p.tokens > 0.0) else {
+            self.result.missed_no_position += 1;
+            return;
+        };
+        let size = trade.size.unwrap_or(0.0);
+        let price = trade.price.unwrap_or(0.0);
+        pos.last_price = price;
+
+        let wanted = if trader_before - size <= 1e-6 {
+            pos.tokens
+        } else {
+            pos.tokens
+                * (size / trader_before)
+                * get_trade_multiplier(self.strategy, trade.usdc_size.unwrap_or(0.0))
+        };
+        if wanted < MIN_ORDER_SIZE_TOKENS {
+            self.result.missed_below_minimum += 1;
+            return;
+        }
+        let tokens = wanted.min(pos.tokens);
+        let fill_price = (price * (1.0 - self.slippage)).max(0.001);
+        let proceeds = tokens * fill_price;
+        let cost = pos.cost * tokens / pos.tokens;
+
+        self.balance += proceeds;
+        self.result.slippage_cost += tokens * price - proceeds;
+        self.result.copied_trades += 1;
+        pos.realized += proceeds - cost;
+        pos.cost -= cost;
+        pos.tokens -= tokens;
+        self.result.realized_pnl += proceeds - cost;
+        if pos.tokens < 1e-6 {
+            self.close(&asset);
+        }
+    }
+
+    // Redeem alongside the trader at their payout per token (assumes they held one side)
+    fn redeem(&mut self, activity: &UserActivity) {
+        let size = activity.size.unwrap_or(0.0);
+        if size <= 0.0 {
+            return;
+        }
+        let rate = (activity.usdc_size.unwrap_or(0.0) / size).clamp(0.0, 1.0);
+        let condition_id = activity.condition_id.clone().unwrap_or_default();
+        let assets: Vec<String> = self
+            .positions
+            .iter()
+            .filter(|(_, p)| p.condition_id == condition_id)
+            .map(|(a, _)| a.clone())
+            .collect();
+        for asset in assets {
+            if let Some(pos) = self.positions.get_mut(&asset) {
+                let payout = pos.tokens * rate;
+                self.balance += payout;
+                pos.realized += payout - pos.cost;
+                self.result.realized_pnl += payout - pos.cost;
+                pos.cost = 0.0;
+                pos.tokens = 0.0;
+            }
+            self.close(&asset);
+        }
+    }
+
+    fn close(&mut self, asset: &str) {
+        if let Some(pos) = self.positions.remove(asset) {
+            self.closed += 1;
+            if pos.realized > 0.0 {
+                self.wins += 1;
+            }
+        }
+    }
+
+    // Aggregated small buys execute as one order at their USDC-weighted price, like the executor
+    fn flush_group(&mut self, group: PendingGroup) {
+        let total: f64 = group.trades.iter().map(|t| t.usdc_size.unwrap_or(0.0)).sum();
+        let count = group.trades.len() as u32;
+        if total < TRADE_AGGREGATION_MIN_TOTAL_USD || total <= 0.0 {
+            self.result.missed_below_minimum += count;
+            return;
+        }
+        let weighted: f64 = group
+            .trades
+            .iter()
+            .map(|t| t.usdc_size.unwrap_or(0.0) * t.price.unwrap_or(0.0))
+            .sum();
+        let mut synthetic = group.trades[0].clone();
+        synthetic.usdc_size = Some(total);
+        synthetic.price = Some(weighted / total);
+        self.buy(&synthetic, count);
+    }
+}
+
+// Replay a trader's history through the bot's sizing & aggregation with `strategy`
+pub fn simulate_history(
+    config: &EnvConfig,
+    trader: &str,
+    strategy: &CopyStrategyConfig,
+    history: &TraderHistory,
+    opts: &SimOptions,
+) -> SimResult {
+    let mut sim = Simulation {
+        config,
+        strategy,
+        trader,
+        slippage: opts.slippage_bps / 10_000.0,
+        balance: opts.starting_capital,
+        positions: HashMap::new(),
+        trader_tokens: HashMap::new(),
+        closed: 0,
+        wins: 0,
+        result: SimResult {
+            address: trader.to_string(),
+            starting_capital: opts.starting_capital,
+            history_complete: history.complete,
+            ..Default::default()
+        },
+    };
+    let window = sim.config.trade_aggregation_window_seconds as i64;
+    let mut pending: HashMap<String, PendingGroup> = HashMap::new();
+
+    for a in &history.activities {
+        let ts = a.timestamp.unwrap_or(0);
+
+        // Groups whose window has passed execute before anything newer
+        if sim.config.trade_aggregation_enabled {
+            let mut due: Vec<String> = pending
+                .iter()
+                .filter(|(_, g)| ts - g.first_ts >= window)
+                .map(|(k, _)| k.clone())
+                .collect();
+            due.sort_by_key(|k| pending[k].first_ts);
+            for key in due {
+                if let Some(group) = pending.remove(&key) {
+                    sim.flush_group(group);
+                }
+            }
+        }
+
+        match a.activity_type.as_deref() {
+            Some("TRADE") => {
+                sim.result.trader_trades += 1;
+                let asset = a.asset.clone().unwrap_or_default();
+                let size = a.size.unwrap_or(0.0);
+                let held = sim.trader_tokens.entry(asset).or_default();
+                let trader_before = *held;
+                if a.side_buy() {
+                    *held += size;
+                } else {
+                    *held = (*held - size).max(0.0);
+                }
+
+                let condition = if a.side_buy() { "buy" } else { "sell" };
+                if sim
+                    .config
+                    .trader_override(sim.trader)
+                    .and_then(|t| t.skip_reason(condition, a))
+                    .is_some()
+                {
+                    sim.result.missed_by_settings += 1;
+                    continue;
+                }
+
+                if sim.config.trade_aggregation_enabled && should_aggregate(a) {
+                    let key = get_aggregation_key(&TradeWithUser {
+                        trade: a.clone(),
+                        user_address: sim.trader.to_string(),
+                    });
+                    pending
+                        .entry(key)
+                        .or_insert_with(|| PendingGroup {
+                            first_ts: ts,
+                            trades: Vec::new(),
+                        })
+                        .trades
+                        .push(a.clone());
+                } else if a.side_buy() {
+                    sim.buy(a, 1);
+                } else {
+                    sim.sell(a, trader_before);
+                }
+            }
+            Some("REDEEM") => sim.redeem(a),
+            _ => {}
+        }
+    }
+
+    let mut leftover: Vec<PendingGroup> = pending.into_values().collect();
+    leftover.sort_by_key(|g| g.first_ts);
+    for group in leftover {
+        sim.flush_group(group);
+    }
+
+    // Open positions at the trader's current price, else the last price we saw
+    let mut open_value = 0.0;
+    let mut open_cost = 0.0;
+    for (asset, pos) in &sim.positions {
+        let price = history.prices.get(asset).copied().unwrap_or(pos.last_price);
+        open_value += pos.tokens * price;
+        open_cost += pos.cost;
+    }
+
+    let mut r = sim.result;
+    r.open_positions = sim.positions.len() as u32;
+    r.unrealized_pnl = open_value - open_cost;
+    r.final_equity = sim.balance + open_value;
+    r.total_pnl = r.final_equity - opts.starting_capital;
+    if opts.starting_capital > 0.0 {
+        r.roi = r.total_pnl / opts.starting_capital * 100.0;
+    }
+    if sim.closed > 0 {
+        r.win_rate = sim.wins as f64 / sim.closed as f64 * 100.0;
+    }
+    r.missed_trades =
+        r.missed_below_minimum + r.missed_no_balance + r.missed_no_position + r.missed_by_settings;
+    r.status = if r.copied_trades == 0 {
+        "no_trades"
+    } else if r.total_pnl > 0.0 {
+        "profitable"
+    } else {
+        "unprofitable"
+    }
+    .to_string();
+    r
+}